@@ -0,0 +1,105 @@
+//! Compares heap allocations and run time of the `Vec`- and array-backed drivers.
+//!
+//! Run with `cargo bench --bench allocations`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use wanglandau::array::WLDriverArray;
+use wanglandau::prelude::*;
+
+/// Global allocator that counts allocations
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// A six-sided die
+#[derive(Clone)]
+struct Dice(u8);
+impl State for Dice {}
+
+/// Rolls the die to a random face
+struct Roll;
+impl<R: rand::RngCore> Move<Dice, R> for Roll {
+    fn propose(&mut self, s: &mut Dice, rng: &mut R) {
+        use rand::Rng;
+        s.0 = rng.random_range(1..=6);
+    }
+}
+
+/// One bin per face
+struct Face;
+impl Macrospace<Dice> for Face {
+    type Bin = usize;
+    fn locate(&self, s: &Dice) -> usize {
+        (s.0 - 1) as usize
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4, 5]
+    }
+}
+
+const STEPS: u64 = 5_000_000;
+
+/// Runs `body`, reporting its allocation count and wall time.
+fn measure(label: &str, body: impl FnOnce() -> f64) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let ln_f = body();
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:<6} {:>4} allocations  {:>10.2?}  (final ln_f = {:.3e})",
+        label, allocations, elapsed, ln_f
+    );
+}
+
+fn main() {
+    let sched = Geometric {
+        alpha: 0.5,
+        tol: 1e-12,
+    };
+
+    measure("vec", || {
+        let mut drv = WLDriver::new(
+            Dice(1),
+            Roll,
+            Face,
+            Params::default(),
+            sched,
+            Fraction,
+            wanglandau::rng::seeded(1),
+        );
+        drv.run(STEPS);
+        drv.ln_f()
+    });
+
+    measure("array", || {
+        let mut drv: WLDriverArray<_, _, _, 6> = WLDriverArray::new(
+            Dice(1),
+            Roll,
+            Face,
+            Params::default(),
+            sched,
+            Fraction,
+            wanglandau::rng::seeded(1),
+        );
+        drv.run(STEPS);
+        drv.ln_f()
+    });
+}
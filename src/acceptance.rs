@@ -0,0 +1,97 @@
+//! # Acceptance rules
+//!
+//! This module provides implementations of the [`Acceptance`] trait:
+//!
+//! - [`Metropolis`]: The standard Wang-Landau rule `min(1, exp(delta))`
+//! - [`GlauberWL`]: The heat-bath rule `1 / (1 + exp(-delta))`
+//!
+//! Custom rules can be implemented by implementing the [`Acceptance`] trait.
+
+use rand::{Rng, RngCore};
+
+use crate::traits::Acceptance;
+
+/// The standard Wang-Landau acceptance `min(1, exp(delta))`.
+///
+/// This is the driver's default rule.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::prelude::*;
+///
+/// assert_eq!(Metropolis.probability(0.5), 1.0);
+/// assert!((Metropolis.probability(-1.0) - (-1.0f64).exp()).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Metropolis;
+
+impl Acceptance for Metropolis {
+    #[inline]
+    fn probability(&self, delta: f64) -> f64 {
+        delta.exp().min(1.0)
+    }
+}
+
+/// Heat-bath (Glauber) acceptance `1 / (1 + exp(-delta))`.
+///
+/// The logistic form never accepts with certainty, so the walker rejects
+/// more often than with [`Metropolis`], but it varies smoothly with `delta`,
+/// which some systems reward with better mixing. It satisfies the same
+/// detailed balance condition, so ln(g) converges to the same estimate.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::prelude::*;
+///
+/// assert_eq!(GlauberWL.probability(0.0), 0.5);
+/// assert!(GlauberWL.probability(5.0) < 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlauberWL;
+
+impl Acceptance for GlauberWL {
+    #[inline]
+    fn probability(&self, delta: f64) -> f64 {
+        1.0 / (1.0 + (-delta).exp())
+    }
+}
+
+/// Decides a proposal of the drivers with a fixed [`Metropolis`] rule.
+///
+/// Shared by [`WLDriverArray`](crate::array::WLDriverArray),
+/// [`BatchDriver`](crate::batch::BatchDriver) and
+/// [`SharedWalker`](crate::parallel::SharedWalker). A move within a bin is
+/// weighed by its proposal ratio alone and, if symmetric, accepted without a
+/// draw; any other move by `ln_old - ln_new + ln_ratio`.
+///
+/// # Parameters
+///
+/// * `ln_old` - The ln(g) of the walker's bin
+/// * `ln_new` - The ln(g) of the proposed bin
+/// * `ln_ratio` - The move's log proposal ratio
+/// * `same_bin` - Whether the proposal stays in the walker's bin
+/// * `rng` - The generator of the acceptance draw
+///
+/// # Returns
+///
+/// Whether the proposal is accepted
+#[inline]
+pub(crate) fn accept<R: RngCore + ?Sized>(
+    ln_old: f64,
+    ln_new: f64,
+    ln_ratio: f64,
+    same_bin: bool,
+    rng: &mut R,
+) -> bool {
+    if same_bin && ln_ratio == 0.0 {
+        return true;
+    }
+    let delta = if same_bin {
+        ln_ratio
+    } else {
+        ln_old - ln_new + ln_ratio
+    };
+    rng.random::<f64>() < Metropolis.probability(delta)
+}
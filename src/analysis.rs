@@ -0,0 +1,1070 @@
+//! # Post-processing and analysis utilities
+//!
+//! This module collects functions that operate on the outputs of one or more
+//! Wang-Landau runs, such as histograms and ln(g) curves, rather than on a
+//! running driver.
+//!
+//! - [`overlap`]: Bhattacharyya overlap of two histograms over a bin range
+//! - [`Accumulator`]: Running mean and variance using Welford's algorithm
+//! - [`logsumexp`]: Numerically stable `ln Σ exp(xᵢ)`
+//! - [`tv_distance`]: Total-variation distance between two ln(g) curves
+//! - [`kl_divergence`]: Kullback-Leibler divergence between two ln(g) curves
+//! - [`roughness`]: Mean squared second difference of an ln(g) curve
+//! - [`compare`]: Offset-free distances between two ln(g) curves, returned as
+//!   an [`LnGComparison`]
+//! - [`weighted_merge`]: Combines per-stage ln(g) curves weighted by 1/ln_f
+//! - [`jackknife`]: Leave-one-out mean and standard error of an ensemble of
+//!   ln(g) curves
+//! - [`inverse_variance_combine`]: Combines refinement passes aligned with
+//!   inverse-variance bin weights, with per-bin standard errors
+//! - [`ln_g_ratio_with_error`]: Offset-free log ratio of two bins' density
+//!   of states with its standard error over an ensemble
+//! - [`reweight`]: Average of an observable reweighted to a target
+//!   distribution over bins
+//! - [`merge_checkpoints`]: Combines the ln(g) of checkpoints saved by
+//!   separate runs
+//! - [`merge_binary`]: Combines the ln(g) and histograms of binary files
+//!   written by separate runs
+//! - [`autocorrelation`] and [`integrated_autocorr_time`]: Correlations of a
+//!   time series, e.g. the bin index during production
+//! - [`round_trips_for_error`]: Heuristic number of round trips for a target
+//!   ln(g) error
+
+use std::io::{self, Read};
+
+use crate::driver::Checkpoint;
+
+/// Computes the Bhattacharyya coefficient of two histograms over a bin range.
+///
+/// Both histograms are normalized over the half-open bin range
+/// `range.0..range.1` and the coefficient `Σ √(pᵢ qᵢ)` is returned. It is `1.0`
+/// for identical sampling distributions and `0.0` for disjoint ones. When
+/// stitching windowed runs, a low overlap between adjacent windows warns that
+/// the stitched ln(g) will be unreliable.
+///
+/// Pass [`WLDriver::histogram`](crate::driver::WLDriver::histogram) of each
+/// window to compare two drivers that index the same global bins.
+///
+/// # Parameters
+///
+/// * `a` - The first histogram
+/// * `b` - The second histogram
+/// * `range` - The shared bin range `(start, end)`, end exclusive
+///
+/// # Returns
+///
+/// The overlap coefficient in `[0, 1]`, or `0.0` if either histogram has no
+/// visits in the range
+///
+/// # Panics
+///
+/// Panics if the range extends past the end of either histogram.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::overlap;
+///
+/// let a = [0, 10, 10, 0];
+/// let b = [0, 0, 10, 10];
+///
+/// // Half of each distribution sits in the shared bin
+/// assert!((overlap(&a, &b, (0, 4)) - 0.5).abs() < 1e-12);
+/// ```
+pub fn overlap(a: &[u64], b: &[u64], range: (usize, usize)) -> f64 {
+    let (a, b) = (&a[range.0..range.1], &b[range.0..range.1]);
+    let total_a = a.iter().sum::<u64>() as f64;
+    let total_b = b.iter().sum::<u64>() as f64;
+    if total_a == 0.0 || total_b == 0.0 {
+        return 0.0;
+    }
+
+    a.iter()
+        .zip(b)
+        .map(|(&x, &y)| ((x as f64 / total_a) * (y as f64 / total_b)).sqrt())
+        .sum()
+}
+
+/// Computes `ln Σ exp(xᵢ)` without overflow.
+///
+/// The largest value is factored out before exponentiating, so curves with
+/// ln(g) in the thousands are handled exactly as well as small ones.
+///
+/// # Parameters
+///
+/// * `values` - The log-space values to sum
+///
+/// # Returns
+///
+/// The log of the sum of exponentials, or `f64::NEG_INFINITY` if `values`
+/// is empty
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::logsumexp;
+///
+/// let x = logsumexp(&[1000.0, 1000.0]);
+/// assert!((x - (1000.0 + 2f64.ln())).abs() < 1e-12);
+/// ```
+pub fn logsumexp(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values.iter().map(|&x| (x - max).exp()).sum::<f64>().ln()
+}
+
+/// Computes the total-variation distance between two ln(g) curves.
+///
+/// Each curve is turned into a probability distribution
+/// `pᵢ = exp(ln_gᵢ) / Σ exp(ln_g)` using [`logsumexp`] for the normalization,
+/// and `½ Σ |pᵢ - qᵢ|` is returned. Additive offsets of ln(g) do not matter.
+///
+/// # Parameters
+///
+/// * `ln_a` - The first ln(g) curve
+/// * `ln_b` - The second ln(g) curve, over the same bins
+///
+/// # Returns
+///
+/// The distance in `[0, 1]`; `0.0` for curves that differ by a constant
+///
+/// # Panics
+///
+/// Panics if the curves have different lengths.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::tv_distance;
+///
+/// let a = [0.0, 0.0];
+/// let b = [5.0, 5.0];
+/// assert!(tv_distance(&a, &b) < 1e-12);
+///
+/// let c = [0.0, 3f64.ln()]; // p = (1/4, 3/4) against (1/2, 1/2)
+/// assert!((tv_distance(&a, &c) - 0.25).abs() < 1e-12);
+/// ```
+pub fn tv_distance(ln_a: &[f64], ln_b: &[f64]) -> f64 {
+    assert_eq!(ln_a.len(), ln_b.len(), "ln(g) curves differ in length");
+    let (norm_a, norm_b) = (logsumexp(ln_a), logsumexp(ln_b));
+    0.5 * ln_a
+        .iter()
+        .zip(ln_b)
+        .map(|(&a, &b)| ((a - norm_a).exp() - (b - norm_b).exp()).abs())
+        .sum::<f64>()
+}
+
+/// Computes the Kullback-Leibler divergence between two ln(g) curves.
+///
+/// Both curves are normalized to distributions `p` and `q` as in
+/// [`tv_distance`], and `D_KL(p || q) = Σ pᵢ (ln pᵢ - ln qᵢ)` is evaluated
+/// entirely in log space, so it stays accurate for ln(g) values far beyond
+/// the range of `exp`. Unlike the total-variation distance it weighs relative
+/// differences, which makes it sensitive to disagreements in low-density
+/// bins. Bins with `ln_p = -inf` contribute nothing.
+///
+/// # Parameters
+///
+/// * `ln_p` - The ln(g) curve of the reference distribution `p`
+/// * `ln_q` - The ln(g) curve of the approximating distribution `q`
+///
+/// # Returns
+///
+/// The divergence in nats, `0.0` for curves that differ by a constant
+///
+/// # Panics
+///
+/// Panics if the curves have different lengths.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::kl_divergence;
+///
+/// let p = [0.0, 0.0];
+/// let q = [0.0, 3f64.ln()]; // q = (1/4, 3/4)
+/// let expected = 0.5 * (2.0f64).ln() + 0.5 * (2.0f64 / 3.0).ln();
+/// assert!((kl_divergence(&p, &q) - expected).abs() < 1e-12);
+/// ```
+pub fn kl_divergence(ln_p: &[f64], ln_q: &[f64]) -> f64 {
+    assert_eq!(ln_p.len(), ln_q.len(), "ln(g) curves differ in length");
+    let (norm_p, norm_q) = (logsumexp(ln_p), logsumexp(ln_q));
+    ln_p.iter()
+        .zip(ln_q)
+        .filter(|&(&p, _)| p != f64::NEG_INFINITY)
+        .map(|(&p, &q)| {
+            let log_p = p - norm_p;
+            log_p.exp() * (log_p - (q - norm_q))
+        })
+        .sum::<f64>()
+        .max(0.0)
+}
+
+/// Combines per-stage ln(g) curves, weighting later stages more heavily.
+///
+/// Each stage is a curve together with the ln_f used to build it. Curves are
+/// first aligned by shifting each to zero mean, since their offsets are
+/// arbitrary, and then averaged bin by bin with weight `1 / ln_f`: stages run
+/// at a smaller modification factor carry less Wang-Landau noise. The result
+/// is shifted so that its minimum is zero. Feed it the output of
+/// [`WLDriver::stage_ln_g`](crate::driver::WLDriver::stage_ln_g), e.g. from a
+/// converged run restarted at a larger ln_f to refine further.
+///
+/// # Parameters
+///
+/// * `stages` - Pairs of a finite ln(g) curve and the ln_f of its stage
+///
+/// # Returns
+///
+/// The merged ln(g) curve, or an empty vector if `stages` is empty
+///
+/// # Panics
+///
+/// Panics if the curves differ in length or an ln_f is not positive.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::weighted_merge;
+///
+/// let stages = vec![
+///     (vec![0.0, 2.0], 1.0), // ln_f = 1, weight 1
+///     (vec![5.0, 6.0], 0.5), // ln_f = 1/2, weight 2
+/// ];
+/// let merged = weighted_merge(&stages);
+///
+/// // Aligned differences 2 and 1 average to (1·2 + 2·1) / 3
+/// assert!((merged[1] - merged[0] - 4.0 / 3.0).abs() < 1e-12);
+/// ```
+pub fn weighted_merge(stages: &[(Vec<f64>, f64)]) -> Vec<f64> {
+    let Some((first, _)) = stages.first() else {
+        return Vec::new();
+    };
+    let n = first.len();
+
+    let mut merged = vec![0.0; n];
+    let mut total_weight = 0.0;
+    for (ln_g, ln_f) in stages {
+        assert_eq!(ln_g.len(), n, "ln(g) curves differ in length");
+        assert!(*ln_f > 0.0, "stage ln_f must be positive");
+        let weight = 1.0 / ln_f;
+        let mean = ln_g.iter().sum::<f64>() / n as f64;
+        for (m, g) in merged.iter_mut().zip(ln_g) {
+            *m += weight * (g - mean);
+        }
+        total_weight += weight;
+    }
+
+    let min = merged.iter().copied().fold(f64::INFINITY, f64::min);
+    merged.iter().map(|m| (m - min) / total_weight).collect()
+}
+
+/// Estimates the mean ln(g) and its standard error with the jackknife.
+///
+/// The curves, e.g. from independent runs, are first aligned by shifting
+/// each to zero mean. For `n` curves the `n` leave-one-out means are formed
+/// and the standard error of bin `i` is
+/// `sqrt((n - 1) / n · Σⱼ (θⱼ,ᵢ - θ̄ᵢ)²)`, where `θⱼ,ᵢ` is the mean of all
+/// curves but `j`. Unlike a bootstrap no random numbers are involved, so the
+/// result is exactly reproducible.
+///
+/// # Parameters
+///
+/// * `samples` - The finite ln(g) curves of the ensemble
+///
+/// # Returns
+///
+/// The jackknife mean and standard error of each bin
+///
+/// # Panics
+///
+/// Panics if there are fewer than two curves or they differ in length.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::jackknife;
+///
+/// let samples = vec![vec![0.0, 1.0], vec![0.0, 3.0]];
+/// let (mean, err) = jackknife(&samples);
+///
+/// // Aligned to zero mean, bin 1 holds 0.5 and 1.5
+/// assert!((mean[1] - 1.0).abs() < 1e-12);
+/// assert!((err[1] - 0.5).abs() < 1e-12);
+/// ```
+pub fn jackknife(samples: &[Vec<f64>]) -> (Vec<f64>, Vec<f64>) {
+    assert!(
+        samples.len() >= 2,
+        "the jackknife needs at least two curves"
+    );
+    let n_bins = samples[0].len();
+    let aligned: Vec<Vec<f64>> = samples
+        .iter()
+        .map(|ln_g| {
+            assert_eq!(ln_g.len(), n_bins, "ln(g) curves differ in length");
+            let mean = ln_g.iter().sum::<f64>() / n_bins as f64;
+            ln_g.iter().map(|g| g - mean).collect()
+        })
+        .collect();
+
+    let n = aligned.len() as f64;
+    let mut mean = vec![0.0; n_bins];
+    let mut err = vec![0.0; n_bins];
+    for bin in 0..n_bins {
+        let total: f64 = aligned.iter().map(|curve| curve[bin]).sum();
+        let leave_one_out: Vec<f64> = aligned
+            .iter()
+            .map(|curve| (total - curve[bin]) / (n - 1.0))
+            .collect();
+        let centre = leave_one_out.iter().sum::<f64>() / n;
+        let spread: f64 = leave_one_out.iter().map(|t| (t - centre).powi(2)).sum();
+        mean[bin] = centre;
+        err[bin] = ((n - 1.0) / n * spread).sqrt();
+    }
+    (mean, err)
+}
+
+/// Combines refinement passes weighting each bin by its inverse variance.
+///
+/// The offset of every pass is arbitrary, so the passes have to be aligned
+/// before they can be combined, and a plain zero-mean alignment lets the
+/// scatter of a single noisy bin leak into the spread, and hence the error,
+/// of every other bin. Here each pass
+/// is instead shifted by its inverse-variance weighted mean, the weighted
+/// least-squares fit of its offset, where the variance of a bin is taken
+/// once across the zero-mean aligned passes. Poorly determined bins
+/// therefore have little say in where the passes line up, while bins on
+/// which the passes agree closely pin the offsets down. The weights are not
+/// refined further: re-weighting by the variance after the alignment would
+/// let one bin draw the passes onto itself and report a vanishing error.
+/// The combined curve is the mean of the aligned passes, shifted so that its
+/// minimum is zero, and the standard error of bin `i` is
+/// `sqrt(varᵢ / n)` for `n` passes. Feed it the ln(g) recorded after each
+/// pass of a refinement, i.e. the curves that
+/// [`WLDriver::run_refinement_passes`](crate::driver::WLDriver::run_refinement_passes)
+/// averages uniformly, or the results of independent runs.
+///
+/// # Parameters
+///
+/// * `passes` - The finite ln(g) curves of the passes
+///
+/// # Returns
+///
+/// The combined ln(g) and the standard error of each bin
+///
+/// # Panics
+///
+/// Panics if there are fewer than two passes or they differ in length.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::inverse_variance_combine;
+///
+/// // The passes agree on bins 1 and 2 up to an offset but not on bin 0
+/// let passes = vec![vec![4.0, 0.0, 1.0], vec![0.0, 5.0, 6.0]];
+/// let (ln_g, err) = inverse_variance_combine(&passes);
+///
+/// assert!((ln_g[2] - ln_g[1] - 1.0).abs() < 1e-9);
+/// assert!(err[1] < err[0]);
+/// ```
+pub fn inverse_variance_combine(passes: &[Vec<f64>]) -> (Vec<f64>, Vec<f64>) {
+    assert!(
+        passes.len() >= 2,
+        "combining passes needs at least two of them"
+    );
+    let n_bins = passes[0].len();
+    for pass in passes {
+        assert_eq!(pass.len(), n_bins, "ln(g) curves differ in length");
+    }
+    let n = passes.len() as f64;
+
+    // The scatter of each bin across the passes under a given alignment
+    let spread = |offsets: &[f64]| -> (Vec<f64>, Vec<f64>) {
+        (0..n_bins)
+            .map(|bin| {
+                let values = passes.iter().zip(offsets).map(|(p, c)| p[bin] - c);
+                let mean = values.clone().sum::<f64>() / n;
+                let var = values.map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+                (mean, var)
+            })
+            .unzip()
+    };
+
+    // The weights come from a zero-mean alignment and stay fixed, so that no
+    // bin can earn a larger weight by pulling the alignment onto itself
+    let zero_mean: Vec<f64> = passes
+        .iter()
+        .map(|pass| pass.iter().sum::<f64>() / n_bins as f64)
+        .collect();
+    let (_, var) = spread(&zero_mean);
+
+    // No bin is trusted more than the median one, or a bin whose passes
+    // agree by chance would carry the whole alignment
+    let mut sorted = var.clone();
+    sorted.sort_by(f64::total_cmp);
+    let floor = sorted.get(n_bins / 2).copied().unwrap_or(0.0);
+    let weights: Vec<f64> = if floor > 0.0 {
+        var.iter().map(|&v| 1.0 / v.max(floor)).collect()
+    } else {
+        // Most bins agree exactly up to an offset, which no weighting improves
+        vec![1.0; n_bins]
+    };
+    let total: f64 = weights.iter().sum();
+
+    // The weighted least-squares offset of each pass is its weighted mean
+    let offsets: Vec<f64> = passes
+        .iter()
+        .map(|pass| pass.iter().zip(&weights).map(|(p, w)| w * p).sum::<f64>() / total)
+        .collect();
+    let (mean, var) = spread(&offsets);
+
+    let min = mean.iter().copied().fold(f64::INFINITY, f64::min);
+    let ln_g = mean.iter().map(|m| m - min).collect();
+    let err = var.iter().map(|v| (v / n).sqrt()).collect();
+    (ln_g, err)
+}
+
+/// Estimates the log density-of-states ratio of two bins over an ensemble.
+///
+/// Each curve, e.g. the ln(g) of an independent run, gives one sample of
+/// `ln_g[bin_a] - ln_g[bin_b]`, which does not depend on the curve's offset,
+/// so no alignment is needed. The samples are averaged and the standard
+/// error of the mean is returned alongside.
+///
+/// # Parameters
+///
+/// * `samples` - The ln(g) curves of the ensemble
+/// * `bin_a` - The numerator bin
+/// * `bin_b` - The denominator bin
+///
+/// # Returns
+///
+/// The mean ratio and its standard error
+///
+/// # Panics
+///
+/// Panics if there are fewer than two curves or either bin is out of range
+/// for one of them.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::ln_g_ratio_with_error;
+///
+/// let samples = vec![vec![5.0, 6.0], vec![0.0, 2.0]];
+/// let (ratio, err) = ln_g_ratio_with_error(&samples, 1, 0);
+/// assert!((ratio - 1.5).abs() < 1e-12);
+/// assert!((err - 0.5).abs() < 1e-12);
+/// ```
+pub fn ln_g_ratio_with_error(samples: &[Vec<f64>], bin_a: usize, bin_b: usize) -> (f64, f64) {
+    assert!(samples.len() >= 2, "an error needs at least two curves");
+    let ratios: Vec<f64> = samples.iter().map(|g| g[bin_a] - g[bin_b]).collect();
+    let n = ratios.len() as f64;
+    let mean = ratios.iter().sum::<f64>() / n;
+    let var = ratios.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, (var / n).sqrt())
+}
+
+/// Reweights observable samples to an arbitrary target distribution over bins.
+///
+/// Each sample `(bin, value)` is weighted by
+/// `exp(target_ln_weight[bin] - ln_g[bin])`, the ratio of the target bin
+/// probability to the sampled one, and the weighted mean of the values is
+/// returned. With `ln_g` the density of states this turns samples whose bin
+/// frequencies follow `g`, such as configurations drawn uniformly over
+/// microstates, into averages under any ensemble given by its unnormalized
+/// log bin weights; for canonical reweighting use
+/// `target_ln_weight[b] = ln_g[b] - β E_b`. More generally `ln_g` may be the
+/// log bin distribution of whatever ensemble produced the samples. The log
+/// weights are shifted by their maximum before exponentiating, so neither
+/// array needs to be normalized.
+///
+/// # Parameters
+///
+/// * `samples` - The `(bin, observable value)` pairs
+/// * `ln_g` - The log of the sampled bin distribution, up to a constant
+/// * `target_ln_weight` - The log of the target bin distribution, up to a
+///   constant
+///
+/// # Returns
+///
+/// The reweighted average, or NaN if `samples` is empty or no sample falls in
+/// a bin with finite weight
+///
+/// # Panics
+///
+/// Panics if a sample's bin is out of range for either array.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::reweight;
+///
+/// // Bin 1 is sampled twice as often as bin 0, the target weights them equally
+/// let samples = [(0, 1.0), (1, 4.0), (1, 4.0)];
+/// let ln_g = [0.0, 2f64.ln()];
+/// let mean = reweight(&samples, &ln_g, &[0.0, 0.0]);
+/// assert!((mean - 2.5).abs() < 1e-12);
+/// ```
+pub fn reweight(samples: &[(usize, f64)], ln_g: &[f64], target_ln_weight: &[f64]) -> f64 {
+    let ln_w: Vec<f64> = samples
+        .iter()
+        .map(|&(bin, _)| target_ln_weight[bin] - ln_g[bin])
+        .collect();
+    let max = ln_w.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    let (mut total, mut weighted) = (0.0, 0.0);
+    for (&(_, value), w) in samples.iter().zip(&ln_w) {
+        let w = (w - max).exp();
+        total += w;
+        weighted += w * value;
+    }
+    weighted / total
+}
+
+/// Merges the ln(g) curves of checkpoints saved by independent runs.
+///
+/// Each reader holds one [`Checkpoint`], e.g. written by a separate node of
+/// a distributed run, and `load` deserializes it with whichever `serde`
+/// format the checkpoints were saved in. The ln(g) curves have arbitrary
+/// offsets, so they are aligned to zero mean and averaged with weight
+/// `1 / ln_f` of each checkpoint, as [`weighted_merge`] does, and the result
+/// is shifted to a zero minimum. Forbidden bins at ln(g) = -inf are allowed
+/// as long as every checkpoint has them in the same places; they stay at
+/// -inf and are left out of the alignment. This is the offline counterpart
+/// to the shared estimate of a [`BatchDriver`](crate::batch::BatchDriver);
+/// see [`merge_binary`] for files written with
+/// [`io::write_binary`](crate::io::write_binary).
+///
+/// # Parameters
+///
+/// * `readers` - One reader per checkpoint
+/// * `load` - Deserializes a checkpoint from a reader
+///
+/// # Returns
+///
+/// The merged ln(g) curve, empty if `readers` is empty
+///
+/// # Errors
+///
+/// Returns any error from `load`, and an
+/// [`InvalidData`](io::ErrorKind::InvalidData) error if the checkpoints
+/// differ in bin count or in their forbidden bins, contain a NaN or `+inf`
+/// ln(g), or have an ln_f that is not positive and finite.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::fs::File;
+/// # use wanglandau::analysis;
+/// # use wanglandau::driver::Checkpoint;
+/// # fn from_json<T>(_: File) -> std::io::Result<T> { unimplemented!() }
+/// # fn main() -> std::io::Result<()> {
+/// // Saved with `serde_json::to_writer(file, &driver.checkpoint())` and read
+/// // back by a wrapper around `serde_json::from_reader`
+/// let files = vec![File::open("node0.json")?, File::open("node1.json")?];
+/// let ln_g = analysis::merge_checkpoints(files, from_json::<Checkpoint<u8>>)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn merge_checkpoints<Rd, S, R, Sch, F>(
+    readers: Vec<Rd>,
+    mut load: impl FnMut(Rd) -> io::Result<Checkpoint<S, R, Sch, F>>,
+) -> io::Result<Vec<f64>>
+where
+    Rd: Read,
+{
+    let mut curves = Vec::with_capacity(readers.len());
+    for r in readers {
+        let checkpoint = load(r)?;
+        if !(checkpoint.ln_f > 0.0 && checkpoint.ln_f.is_finite()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "checkpoint has an ln_f that is not positive and finite",
+            ));
+        }
+        curves.push((checkpoint.ln_g, checkpoint.ln_f));
+    }
+    merge_curves(curves)
+}
+
+/// Merges the ln(g) and histograms of binary files written by independent runs.
+///
+/// Each reader holds one file in the format of
+/// [`io::write_binary`](crate::io::write_binary). The ln(g) curves are
+/// merged as by [`merge_checkpoints`], with equal weights since the files
+/// carry no ln_f, and the histograms are summed bin by bin.
+///
+/// # Parameters
+///
+/// * `readers` - One reader per file
+///
+/// # Returns
+///
+/// The merged ln(g) curve and the summed histogram, both empty if `readers`
+/// is empty
+///
+/// # Errors
+///
+/// Returns any error from reading a file, and an
+/// [`InvalidData`](io::ErrorKind::InvalidData) error if the files differ in
+/// bin count or in their forbidden bins, or contain a NaN or `+inf` ln(g).
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::{analysis, io};
+///
+/// let mut a = Vec::new();
+/// let mut b = Vec::new();
+/// io::write_binary(&mut a, &[0.0, 1.0, 2.0], &[5, 5, 5]).unwrap();
+/// io::write_binary(&mut b, &[10.0, 11.2, 12.0], &[4, 6, 5]).unwrap();
+///
+/// let (ln_g, hist) = analysis::merge_binary(vec![&a[..], &b[..]]).unwrap();
+/// assert!((ln_g[1] - 1.1).abs() < 1e-12);
+/// assert_eq!(hist, [9, 11, 10]);
+/// ```
+pub fn merge_binary(readers: Vec<impl Read>) -> io::Result<(Vec<f64>, Vec<u64>)> {
+    let mut curves = Vec::with_capacity(readers.len());
+    let mut hists = Vec::with_capacity(readers.len());
+    for r in readers {
+        let (ln_g, hist) = crate::io::read_binary(r)?;
+        curves.push((ln_g, 1.0));
+        hists.push(hist);
+    }
+    let ln_g = merge_curves(curves)?;
+
+    // The curves were checked to agree in bin count
+    let mut total = vec![0; ln_g.len()];
+    for hist in hists {
+        for (t, h) in total.iter_mut().zip(hist) {
+            *t += h;
+        }
+    }
+    Ok((ln_g, total))
+}
+
+/// Aligns and averages ln(g) curves with their ln_f, keeping shared
+/// forbidden bins at -inf.
+fn merge_curves(curves: Vec<(Vec<f64>, f64)>) -> io::Result<Vec<f64>> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+
+    let mut forbidden: Vec<bool> = Vec::new();
+    let mut allowed = Vec::with_capacity(curves.len());
+    for (ln_g, ln_f) in curves {
+        if ln_g.iter().any(|g| g.is_nan() || *g == f64::INFINITY) {
+            return Err(invalid("checkpoint contains a NaN or +inf ln(g)"));
+        }
+        let mask: Vec<bool> = ln_g.iter().map(|&g| g == f64::NEG_INFINITY).collect();
+        if allowed.is_empty() {
+            forbidden = mask;
+        } else if ln_g.len() != forbidden.len() {
+            return Err(invalid("checkpoints differ in bin count"));
+        } else if mask != forbidden {
+            return Err(invalid("checkpoints differ in their forbidden bins"));
+        }
+        // Align and average the allowed bins only
+        let finite: Vec<f64> = ln_g.into_iter().filter(|g| g.is_finite()).collect();
+        allowed.push((finite, ln_f));
+    }
+
+    let mut merged = weighted_merge(&allowed).into_iter();
+    Ok(forbidden
+        .iter()
+        .map(|&f| {
+            if f {
+                f64::NEG_INFINITY
+            } else {
+                merged.next().unwrap_or(0.0)
+            }
+        })
+        .collect())
+}
+
+/// Distances between two ln(g) curves after offset alignment.
+///
+/// # Fields
+///
+/// * `max_abs_diff` - The largest absolute difference over the compared bins
+/// * `rms_diff` - The root-mean-square difference over the compared bins
+/// * `correlation` - The Pearson correlation of the two curves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LnGComparison {
+    /// Largest absolute difference of the aligned curves
+    pub max_abs_diff: f64,
+
+    /// Root-mean-square difference of the aligned curves
+    pub rms_diff: f64,
+
+    /// Pearson correlation coefficient, NaN if either curve is constant
+    pub correlation: f64,
+}
+
+/// Compares two ln(g) curves independently of their additive constants.
+///
+/// Both curves are shifted so that their smallest compared entry is zero,
+/// which removes the arbitrary offset of a Wang-Landau estimate, before the
+/// differences are taken. Only bins finite in both curves are compared, so
+/// forbidden bins at `-inf` are skipped. This is meant for regression tests
+/// of a fresh estimate against a stored reference.
+///
+/// # Parameters
+///
+/// * `a` - The first ln(g) curve
+/// * `b` - The second ln(g) curve
+///
+/// # Returns
+///
+/// The maximum and RMS difference and the correlation of the aligned curves;
+/// all NaN if no bin is finite in both
+///
+/// # Panics
+///
+/// Panics if the curves differ in length.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::compare;
+///
+/// let cmp = compare(&[0.0, 1.0, 3.0], &[5.0, 6.0, 8.5]);
+/// assert_eq!(cmp.max_abs_diff, 0.5);
+/// assert!(cmp.correlation > 0.99);
+/// ```
+pub fn compare(a: &[f64], b: &[f64]) -> LnGComparison {
+    assert_eq!(a.len(), b.len(), "ln(g) curves differ in length");
+    let (a, b): (Vec<f64>, Vec<f64>) = a
+        .iter()
+        .zip(b)
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .unzip();
+    let align = |curve: &[f64]| {
+        let min = curve.iter().copied().fold(f64::INFINITY, f64::min);
+        curve.iter().map(|g| g - min).collect::<Vec<f64>>()
+    };
+    let (a, b) = (align(&a), align(&b));
+
+    let n = a.len() as f64;
+    let diffs = a.iter().zip(&b).map(|(x, y)| x - y);
+    let max_abs_diff = if a.is_empty() {
+        f64::NAN
+    } else {
+        diffs.clone().map(f64::abs).fold(0.0, f64::max)
+    };
+    let rms_diff = (diffs.map(|d| d * d).sum::<f64>() / n).sqrt();
+
+    let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(&b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    let correlation = if var_a > 0.0 && var_b > 0.0 {
+        cov / (var_a * var_b).sqrt()
+    } else {
+        f64::NAN
+    };
+
+    LnGComparison {
+        max_abs_diff,
+        rms_diff,
+        correlation,
+    }
+}
+
+/// Computes the roughness of an ln(g) curve over ordered bins.
+///
+/// The roughness is the mean squared second difference
+/// `(ln_g[i+1] - 2 ln_g[i] + ln_g[i-1])²` over the interior bins. A converged
+/// density of states of a physical system is usually smooth, so spikes from
+/// under-sampling or binning artifacts show up as a large value, while any
+/// linear curve scores zero. The bins are assumed to be ordered in energy.
+///
+/// # Parameters
+///
+/// * `ln_g` - The ln(g) curve
+///
+/// # Returns
+///
+/// The mean squared second difference, or `0.0` for fewer than three bins
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::roughness;
+///
+/// assert_eq!(roughness(&[0.0, 1.0, 2.0, 3.0]), 0.0);
+/// assert_eq!(roughness(&[0.0, 1.0, 0.0]), 4.0);
+/// ```
+pub fn roughness(ln_g: &[f64]) -> f64 {
+    if ln_g.len() < 3 {
+        return 0.0;
+    }
+    let sum: f64 = ln_g
+        .windows(3)
+        .map(|w| {
+            let d2 = w[2] - 2.0 * w[1] + w[0];
+            d2 * d2
+        })
+        .sum();
+    sum / (ln_g.len() - 2) as f64
+}
+
+/// Computes the normalized autocorrelation function of a time series.
+///
+/// Uses the standard biased estimator
+/// `ρ(t) = Σᵢ (xᵢ - x̄)(xᵢ₊ₜ - x̄) / Σᵢ (xᵢ - x̄)²`, so `ρ(0) = 1`.
+/// The cost is `O(n · max_lag)`.
+///
+/// # Parameters
+///
+/// * `series` - The time series
+/// * `max_lag` - The largest lag to evaluate, capped at `series.len() - 1`
+///
+/// # Returns
+///
+/// `ρ(0), ..., ρ(max_lag)`; empty for an empty series, and `[1.0]` followed by
+/// zeros for a constant one
+pub fn autocorrelation(series: &[f64], max_lag: usize) -> Vec<f64> {
+    if series.is_empty() {
+        return Vec::new();
+    }
+    let max_lag = max_lag.min(series.len() - 1);
+    let mean = series.iter().sum::<f64>() / series.len() as f64;
+    let dev: Vec<f64> = series.iter().map(|&x| x - mean).collect();
+    let c0: f64 = dev.iter().map(|d| d * d).sum();
+    if c0 == 0.0 {
+        let mut rho = vec![0.0; max_lag + 1];
+        rho[0] = 1.0;
+        return rho;
+    }
+
+    (0..=max_lag)
+        .map(|t| dev.iter().zip(&dev[t..]).map(|(a, b)| a * b).sum::<f64>() / c0)
+        .collect()
+}
+
+/// Estimates the integrated autocorrelation time of a time series.
+///
+/// Returns `τ = 1 + 2 Σₜ ρ(t)`, the number of steps between effectively
+/// independent samples (`τ = 1` for uncorrelated data). The sum is truncated
+/// with Sokal's automatic window, at the first lag `W ≥ 5 τ(W)`, which keeps
+/// the variance of the estimate bounded without evaluating every lag.
+///
+/// # Parameters
+///
+/// * `series` - The time series
+///
+/// # Returns
+///
+/// The integrated autocorrelation time in steps, or `1.0` for series that are
+/// constant or shorter than two values
+///
+/// # Example
+///
+/// ```
+/// use rand::Rng;
+/// use wanglandau::analysis::integrated_autocorr_time;
+///
+/// // Each random value is held for 10 steps, so τ is close to 10
+/// let mut rng = wanglandau::rng::seeded(1);
+/// let mut series = Vec::new();
+/// for _ in 0..2000 {
+///     let x: f64 = rng.random();
+///     series.extend([x; 10]);
+/// }
+/// let tau = integrated_autocorr_time(&series);
+/// assert!(tau > 7.0 && tau < 13.0);
+/// ```
+pub fn integrated_autocorr_time(series: &[f64]) -> f64 {
+    autocorr_window(series).0
+}
+
+/// Estimates the round trips between the energy extremes needed for a target
+/// ln(g) error.
+///
+/// A planning heuristic based on the usual flat-histogram scaling: each round
+/// trip from the lowest to the highest bin and back contributes about one
+/// independent sample of every bin-to-bin ratio of g, each with an ln error
+/// of order one. Across `n_bins` bins these errors add up like a random walk,
+/// and averaging over `N` round trips divides them by `√N`, so the error of
+/// ln(g) over the range is about `√(n_bins / N)`. The estimate inverts that,
+/// `N = ⌈n_bins / target_error²⌉`. Only the scaling is meaningful; the
+/// prefactor depends on the model and the move set, so calibrate it against
+/// a pilot run where accuracy matters. Multiply by the measured steps per
+/// round trip to estimate the run length.
+///
+/// # Parameters
+///
+/// * `n_bins` - The number of bins between the extremes
+/// * `target_error` - The tolerated error of ln(g)
+///
+/// # Returns
+///
+/// The estimated number of round trips, at least 1
+///
+/// # Panics
+///
+/// Panics if `n_bins` is 0 or `target_error` is not positive and finite.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::round_trips_for_error;
+///
+/// assert_eq!(round_trips_for_error(100, 0.1), 10_000);
+/// // Halving the error takes four times as many round trips
+/// assert_eq!(round_trips_for_error(100, 0.05), 40_000);
+/// ```
+pub fn round_trips_for_error(n_bins: usize, target_error: f64) -> u64 {
+    assert!(n_bins > 0, "need at least one bin");
+    assert!(
+        target_error > 0.0 && target_error.is_finite(),
+        "target error must be positive and finite, got {}",
+        target_error
+    );
+    // The float-to-int cast saturates for huge estimates
+    ((n_bins as f64 / (target_error * target_error)).ceil() as u64).max(1)
+}
+
+/// Runs Sokal's windowing procedure.
+///
+/// # Returns
+///
+/// The integrated autocorrelation time and the window it was truncated at
+pub(crate) fn autocorr_window(series: &[f64]) -> (f64, usize) {
+    let n = series.len();
+    if n < 2 {
+        return (1.0, 0);
+    }
+    let mean = series.iter().sum::<f64>() / n as f64;
+    let dev: Vec<f64> = series.iter().map(|&x| x - mean).collect();
+    let c0: f64 = dev.iter().map(|d| d * d).sum();
+    if c0 == 0.0 {
+        return (1.0, 0);
+    }
+
+    let mut tau = 1.0;
+    for t in 1..n {
+        let rho = dev.iter().zip(&dev[t..]).map(|(a, b)| a * b).sum::<f64>() / c0;
+        tau += 2.0 * rho;
+        if t as f64 >= 5.0 * tau {
+            return (tau, t);
+        }
+    }
+    (tau, n - 1)
+}
+
+/// Running mean and variance using Welford's online algorithm.
+///
+/// The accumulator keeps only the count, the mean and the sum of squared
+/// deviations, updating them incrementally. Unlike the naive
+/// `Σx² / n - (Σx / n)²` formula it does not suffer catastrophic cancellation
+/// when the mean is large compared to the spread, which makes it suitable for
+/// long production runs.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::analysis::Accumulator;
+///
+/// let mut acc = Accumulator::new();
+/// for x in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+///     acc.push(x);
+/// }
+///
+/// assert_eq!(acc.count(), 8);
+/// assert_eq!(acc.mean(), 5.0);
+/// assert_eq!(acc.population_variance(), 4.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Accumulator {
+    /// Number of values pushed
+    n: u64,
+
+    /// Running mean
+    mean: f64,
+
+    /// Running sum of squared deviations from the mean
+    m2: f64,
+}
+
+impl Accumulator {
+    /// Creates an empty accumulator.
+    ///
+    /// # Returns
+    ///
+    /// An accumulator with no recorded values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a value to the accumulator.
+    ///
+    /// # Parameters
+    ///
+    /// * `x` - The value to record
+    pub fn push(&mut self, x: f64) {
+        self.n += 1;
+        let d = x - self.mean;
+        self.mean += d / self.n as f64;
+        self.m2 += d * (x - self.mean);
+    }
+
+    /// Returns the number of recorded values.
+    ///
+    /// # Returns
+    ///
+    /// The count of values pushed so far
+    pub fn count(&self) -> u64 {
+        self.n
+    }
+
+    /// Returns the mean of the recorded values.
+    ///
+    /// # Returns
+    ///
+    /// The running mean, or `0.0` if nothing was recorded
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the unbiased sample variance of the recorded values.
+    ///
+    /// # Returns
+    ///
+    /// The variance with Bessel's correction, or `0.0` for fewer than two values
+    pub fn variance(&self) -> f64 {
+        if self.n < 2 {
+            0.0
+        } else {
+            self.m2 / (self.n - 1) as f64
+        }
+    }
+
+    /// Returns the population variance of the recorded values.
+    ///
+    /// # Returns
+    ///
+    /// The variance normalized by the count, or `0.0` if nothing was recorded
+    pub fn population_variance(&self) -> f64 {
+        if self.n == 0 {
+            0.0
+        } else {
+            self.m2 / self.n as f64
+        }
+    }
+
+    /// Returns the unbiased sample standard deviation of the recorded values.
+    ///
+    /// # Returns
+    ///
+    /// The square root of [`variance`](Self::variance)
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
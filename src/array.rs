@@ -0,0 +1,303 @@
+//! # Fixed-size Wang-Landau driver
+//!
+//! This module provides [`WLDriverArray`], a variant of
+//! [`WLDriver`](crate::driver::WLDriver) that stores ln(g) and the histogram
+//! in `[f64; N]` and `[u64; N]` arrays instead of vectors.
+//!
+//! For small bin counts known at compile time (a coin with `N = 2`, a die with
+//! `N = 6`) this removes the heap indirection from the inner loop and lets the
+//! compiler see the array bounds. The sampling algorithm is the same as the
+//! `Vec`-backed driver, so both produce identical trajectories from the same
+//! seed. Only the core loop is provided; diagnostics such as pinning or stage
+//! histograms remain specific to [`WLDriver`](crate::driver::WLDriver).
+
+use rand::RngCore;
+
+use crate::acceptance::accept;
+use crate::driver::Params;
+use crate::rng::Rng64;
+use crate::traits::{BinIndex, Flatness, Macrospace, Move, Schedule, State};
+
+/// Single-walker Wang-Landau engine with compile-time bin count.
+///
+/// Only one-dimensional mappers are supported: the mapper's
+/// [`shape`](Macrospace::shape) must be `[N]`.
+///
+/// # Type Parameters
+///
+/// * `S` - The system state type
+/// * `Mv` - The move proposal type
+/// * `Map` - The state-to-bin mapping type
+/// * `N` - The number of bins
+/// * `R` - The random number generator type (defaults to PCG-64)
+/// * `Sch` - The modification factor schedule type (defaults to geometric)
+/// * `F` - The histogram flatness criterion type (defaults to fraction-based)
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::array::WLDriverArray;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Coin(bool);
+/// impl State for Coin {}
+///
+/// struct Flip;
+/// impl<R: rand::RngCore> Move<Coin, R> for Flip {
+///     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+///         use rand::Rng;
+///         s.0 = rng.random();
+///     }
+/// }
+///
+/// struct CoinMapper;
+/// impl Macrospace<Coin> for CoinMapper {
+///     type Bin = usize;
+///     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+///     fn bins(&self) -> &[usize] { &[0, 1] }
+/// }
+///
+/// let mut driver: WLDriverArray<_, _, _, 2> = WLDriverArray::new(
+///     Coin(false),
+///     Flip,
+///     CoinMapper,
+///     Params::default(),
+///     Geometric { alpha: 0.5, tol: 1e-6 },
+///     Fraction,
+///     wanglandau::rng::seeded(42),
+/// );
+/// driver.run(1_000_000);
+///
+/// let ln_g: &[f64; 2] = driver.ln_g();
+/// assert!((ln_g[0] - ln_g[1]).abs() < 0.5);
+/// ```
+#[allow(clippy::type_complexity)]
+pub struct WLDriverArray<
+    S,
+    Mv,
+    Map,
+    const N: usize,
+    R = Rng64,
+    Sch = crate::schedule::Geometric,
+    F = crate::flatness::Fraction,
+> where
+    S: State,
+    Mv: Move<S, R>,
+    Map: Macrospace<S>,
+    R: RngCore,
+    Sch: Schedule,
+    F: Flatness,
+{
+    /// Current system state
+    state: S,
+
+    /// Move proposal generator
+    moves: Mv,
+
+    /// Maps states to macroscopic bins
+    mapper: Map,
+
+    /// Current estimate of ln(density of states)
+    ln_g: [f64; N],
+
+    /// Histogram of visited states
+    hist: [u64; N],
+
+    /// Current modification factor (ln f)
+    ln_f: f64,
+
+    /// Algorithm parameters
+    params: Params,
+
+    /// Random number generator
+    rng: R,
+
+    /// Modification factor update schedule
+    sched: Sch,
+
+    /// Histogram flatness criterion
+    flat: F,
+
+    /// Current step count
+    step: u64,
+}
+
+impl<S, Mv, Map, const N: usize, R, Sch, F> WLDriverArray<S, Mv, Map, N, R, Sch, F>
+where
+    S: State,
+    Mv: Move<S, R>,
+    Map: Macrospace<S>,
+    R: RngCore,
+    Sch: Schedule,
+    F: Flatness,
+{
+    /// Creates a new fixed-size Wang-Landau driver.
+    ///
+    /// # Parameters
+    ///
+    /// * `state` - The initial system state
+    /// * `moves` - The move proposal generator
+    /// * `mapper` - The state-to-bin mapping
+    /// * `params` - The algorithm parameters
+    /// * `sched` - The modification factor update schedule
+    /// * `flat` - The histogram flatness criterion
+    /// * `rng` - The random number generator
+    ///
+    /// # Returns
+    ///
+    /// A new `WLDriverArray` instance initialized and ready to run
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mapper's shape is not `[N]`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        state: S,
+        moves: Mv,
+        mapper: Map,
+        params: Params,
+        sched: Sch,
+        flat: F,
+        rng: R,
+    ) -> Self {
+        assert_eq!(
+            mapper.shape(),
+            [N],
+            "mapper shape does not match the array length"
+        );
+        Self {
+            state,
+            moves,
+            mapper,
+            ln_g: [0.0; N],
+            hist: [0; N],
+            ln_f: params.ln_f0,
+            params,
+            rng,
+            sched,
+            flat,
+            step: 0,
+        }
+    }
+
+    /// Performs one Wang-Landau step of `sweep_len` move proposals.
+    ///
+    /// See [`WLDriver::step`](crate::driver::WLDriver::step).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the algorithm has converged (ln_f below tolerance), `false` otherwise
+    pub fn step(&mut self) -> bool {
+        for _ in 0..self.params.sweep_len {
+            let bin_final = self.propose_and_accept();
+            self.ln_g[bin_final] += self.ln_f;
+            self.hist[bin_final] += 1;
+        }
+
+        if self.flat.is_flat(&self.hist, self.params.flatness) {
+            self.hist = [0; N];
+            self.sched.on_flat(self.step);
+            if self.sched.update(&mut self.ln_f) {
+                return true;
+            }
+        }
+
+        self.step += 1;
+        false
+    }
+
+    /// Proposes one move and applies the Wang-Landau acceptance rule.
+    ///
+    /// # Returns
+    ///
+    /// The bin the walker occupies after the proposal
+    fn propose_and_accept(&mut self) -> usize {
+        let bin_old = self.bin_of(&self.state);
+        let prev_state = self.state.clone();
+
+        let ln_ratio = self.moves.propose_biased(&mut self.state, &mut self.rng);
+        let bin_new = self.bin_of(&self.state);
+
+        let (ln_old, ln_new) = (self.ln_g[bin_old], self.ln_g[bin_new]);
+        if accept(ln_old, ln_new, ln_ratio, bin_new == bin_old, &mut self.rng) {
+            bin_new
+        } else {
+            self.state = prev_state;
+            bin_old
+        }
+    }
+
+    /// Maps a state to its bin index.
+    #[inline]
+    fn bin_of(&self, state: &S) -> usize {
+        self.mapper.locate(state).to_flat(&[N])
+    }
+
+    /// Runs the simulation for up to `max_steps` steps or until convergence.
+    ///
+    /// Like [`WLDriver::run`](crate::driver::WLDriver::run), convergence is
+    /// followed by [`Params::production_tail`] steps with ln(g) frozen.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The maximum number of Wang-Landau steps to perform
+    pub fn run(&mut self, max_steps: u64) {
+        for _ in 0..max_steps {
+            if self.step() {
+                for _ in 0..self.params.production_tail {
+                    for _ in 0..self.params.sweep_len {
+                        self.propose_and_accept();
+                    }
+                    self.step += 1;
+                }
+                break;
+            }
+        }
+    }
+
+    /// Returns the current estimate of ln(density of states).
+    ///
+    /// # Returns
+    ///
+    /// The ln(g) value of each bin
+    pub fn ln_g(&self) -> &[f64; N] {
+        &self.ln_g
+    }
+
+    /// Returns the current histogram of visited states.
+    ///
+    /// # Returns
+    ///
+    /// The visit count of each bin
+    pub fn histogram(&self) -> &[u64; N] {
+        &self.hist
+    }
+
+    /// Returns the current modification factor (ln f).
+    ///
+    /// # Returns
+    ///
+    /// The current ln_f value
+    pub fn ln_f(&self) -> f64 {
+        self.ln_f
+    }
+
+    /// Returns the number of Wang-Landau steps performed so far.
+    ///
+    /// # Returns
+    ///
+    /// The current step count
+    pub fn step_count(&self) -> u64 {
+        self.step
+    }
+
+    /// Returns a reference to the current system state.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the current state
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
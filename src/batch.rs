@@ -0,0 +1,420 @@
+//! # Batched walkers
+//!
+//! This module provides [`BatchDriver`], which advances several walkers in
+//! lockstep on a single thread. Walkers either share one ln(g) estimate, the
+//! standard "multiple walkers, one density of states" parallel Wang-Landau
+//! scheme, or each carry their own, which amounts to independent runs that
+//! amortize the driver overhead.
+
+use rand::RngCore;
+
+use crate::acceptance::accept;
+use crate::driver::Params;
+use crate::rng::Rng64;
+use crate::traits::{BinIndex, Flatness, Macrospace, Move, Schedule, State};
+
+/// Density-of-states estimate updated by one or more walkers.
+struct Dos<Sch> {
+    /// Current estimate of ln(density of states)
+    ln_g: Vec<f64>,
+
+    /// Histogram of visited states
+    hist: Vec<u64>,
+
+    /// Current modification factor (ln f)
+    ln_f: f64,
+
+    /// Modification factor update schedule
+    sched: Sch,
+
+    /// Whether the schedule has reported convergence
+    converged: bool,
+}
+
+/// Several Wang-Landau walkers advanced in lockstep.
+///
+/// Each step performs `sweep_len` rounds in which every walker proposes one
+/// move, is accepted or rejected against its ln(g), and updates that ln(g)
+/// and histogram immediately. With a shared estimate the walkers therefore
+/// apply their updates sequentially, so later walkers in a round already see
+/// the updates of earlier ones. After the sweep each estimate is checked for
+/// flatness and has its own schedule advanced.
+///
+/// The walkers share one move generator, one RNG and one flatness criterion.
+/// An estimate that has converged stops being updated, and its walkers stop
+/// moving.
+///
+/// # Example
+///
+/// ```no_run
+/// use wanglandau::batch::BatchDriver;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Coin(bool);
+/// impl State for Coin {}
+///
+/// struct Flip;
+/// impl<R: rand::RngCore> Move<Coin, R> for Flip {
+///     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+///         use rand::Rng;
+///         s.0 = rng.random();
+///     }
+/// }
+///
+/// struct CoinMapper;
+/// impl Macrospace<Coin> for CoinMapper {
+///     type Bin = usize;
+///     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+///     fn bins(&self) -> &[usize] { &[0, 1] }
+/// }
+///
+/// // Four walkers feeding one ln(g)
+/// let mut batch = BatchDriver::shared(
+///     vec![Coin(false); 4],
+///     Flip,
+///     CoinMapper,
+///     Params::default(),
+///     Geometric { alpha: 0.5, tol: 1e-8 },
+///     Fraction,
+///     wanglandau::rng::seeded(42),
+/// );
+/// batch.run(1_000_000);
+/// println!("{:?}", batch.ln_g(0));
+/// ```
+#[allow(clippy::type_complexity)]
+pub struct BatchDriver<
+    S,
+    Mv,
+    Map,
+    R = Rng64,
+    Sch = crate::schedule::Geometric,
+    F = crate::flatness::Fraction,
+> where
+    S: State,
+    Mv: Move<S, R>,
+    Map: Macrospace<S>,
+    R: RngCore,
+    Sch: Schedule + Clone,
+    F: Flatness,
+{
+    /// Current state of each walker
+    states: Vec<S>,
+
+    /// Current bin of each walker
+    bins: Vec<usize>,
+
+    /// Move proposal generator
+    moves: Mv,
+
+    /// Maps states to macroscopic bins
+    mapper: Map,
+
+    /// Extent of each bin dimension, used to flatten bins
+    shape: Vec<usize>,
+
+    /// One shared estimate, or one per walker
+    dos: Vec<Dos<Sch>>,
+
+    /// Whether all walkers update `dos[0]`
+    shared: bool,
+
+    /// Algorithm parameters
+    params: Params,
+
+    /// Random number generator
+    rng: R,
+
+    /// Histogram flatness criterion
+    flat: F,
+
+    /// Current step count
+    step: u64,
+}
+
+impl<S, Mv, Map, R, Sch, F> BatchDriver<S, Mv, Map, R, Sch, F>
+where
+    S: State,
+    Mv: Move<S, R>,
+    Map: Macrospace<S>,
+    R: RngCore,
+    Sch: Schedule + Clone,
+    F: Flatness,
+{
+    /// Creates a batch whose walkers all update one shared ln(g).
+    ///
+    /// # Parameters
+    ///
+    /// * `states` - The initial state of each walker
+    /// * `moves` - The move proposal generator
+    /// * `mapper` - The state-to-bin mapping
+    /// * `params` - The algorithm parameters
+    /// * `sched` - The modification factor update schedule
+    /// * `flat` - The histogram flatness criterion
+    /// * `rng` - The random number generator
+    ///
+    /// # Returns
+    ///
+    /// A new `BatchDriver` with a single shared estimate
+    ///
+    /// # Panics
+    ///
+    /// Panics if `states` is empty.
+    #[allow(clippy::too_many_arguments)]
+    pub fn shared(
+        states: Vec<S>,
+        moves: Mv,
+        mapper: Map,
+        params: Params,
+        sched: Sch,
+        flat: F,
+        rng: R,
+    ) -> Self {
+        Self::build(states, moves, mapper, params, sched, flat, rng, true)
+    }
+
+    /// Creates a batch in which every walker has its own ln(g).
+    ///
+    /// This runs independent Wang-Landau simulations in lockstep; each
+    /// estimate starts from a clone of `sched`.
+    ///
+    /// # Parameters
+    ///
+    /// * `states` - The initial state of each walker
+    /// * `moves` - The move proposal generator
+    /// * `mapper` - The state-to-bin mapping
+    /// * `params` - The algorithm parameters
+    /// * `sched` - The modification factor update schedule
+    /// * `flat` - The histogram flatness criterion
+    /// * `rng` - The random number generator
+    ///
+    /// # Returns
+    ///
+    /// A new `BatchDriver` with one estimate per walker
+    ///
+    /// # Panics
+    ///
+    /// Panics if `states` is empty.
+    #[allow(clippy::too_many_arguments)]
+    pub fn independent(
+        states: Vec<S>,
+        moves: Mv,
+        mapper: Map,
+        params: Params,
+        sched: Sch,
+        flat: F,
+        rng: R,
+    ) -> Self {
+        Self::build(states, moves, mapper, params, sched, flat, rng, false)
+    }
+
+    /// Builds a batch with one shared estimate or one per walker.
+    #[allow(clippy::too_many_arguments)]
+    fn build(
+        states: Vec<S>,
+        moves: Mv,
+        mapper: Map,
+        params: Params,
+        sched: Sch,
+        flat: F,
+        rng: R,
+        shared: bool,
+    ) -> Self {
+        assert!(!states.is_empty(), "a batch needs at least one walker");
+        let shape = mapper.shape();
+        let n_bins = shape.iter().product();
+        let bins = states
+            .iter()
+            .map(|s| mapper.locate(s).to_flat(&shape))
+            .collect();
+        let n_dos = if shared { 1 } else { states.len() };
+        let dos = (0..n_dos)
+            .map(|_| Dos {
+                ln_g: vec![0.0; n_bins],
+                hist: vec![0; n_bins],
+                ln_f: params.ln_f0,
+                sched: sched.clone(),
+                converged: false,
+            })
+            .collect();
+        Self {
+            states,
+            bins,
+            moves,
+            mapper,
+            shape,
+            dos,
+            shared,
+            params,
+            rng,
+            flat,
+            step: 0,
+        }
+    }
+
+    /// Performs one step: `sweep_len` proposals for every walker.
+    ///
+    /// # Returns
+    ///
+    /// `true` once every estimate has converged, `false` otherwise
+    pub fn step(&mut self) -> bool {
+        for _ in 0..self.params.sweep_len {
+            for walker in 0..self.states.len() {
+                let d = self.dos_index(walker);
+                if self.dos[d].converged {
+                    continue;
+                }
+                let bin = self.propose_and_accept(walker, d);
+                let dos = &mut self.dos[d];
+                dos.ln_g[bin] += dos.ln_f;
+                dos.hist[bin] += 1;
+            }
+        }
+
+        for dos in self.dos.iter_mut().filter(|d| !d.converged) {
+            if self.flat.is_flat(&dos.hist, self.params.flatness) {
+                dos.hist.fill(0);
+                dos.sched.on_flat(self.step);
+                dos.converged = dos.sched.update(&mut dos.ln_f);
+            }
+        }
+
+        if self.dos.iter().all(|d| d.converged) {
+            return true;
+        }
+        self.step += 1;
+        false
+    }
+
+    /// Proposes one move for a walker and applies the acceptance rule.
+    ///
+    /// # Returns
+    ///
+    /// The bin the walker occupies after the proposal
+    fn propose_and_accept(&mut self, walker: usize, d: usize) -> usize {
+        let bin_old = self.bins[walker];
+        let prev_state = self.states[walker].clone();
+
+        let ln_ratio = self
+            .moves
+            .propose_biased(&mut self.states[walker], &mut self.rng);
+        let bin_new = self
+            .mapper
+            .locate(&self.states[walker])
+            .to_flat(&self.shape);
+
+        let ln_g = &self.dos[d].ln_g;
+        if accept(
+            ln_g[bin_old],
+            ln_g[bin_new],
+            ln_ratio,
+            bin_new == bin_old,
+            &mut self.rng,
+        ) {
+            self.bins[walker] = bin_new;
+            bin_new
+        } else {
+            self.states[walker] = prev_state;
+            bin_old
+        }
+    }
+
+    /// Index of the estimate a walker updates.
+    #[inline]
+    fn dos_index(&self, walker: usize) -> usize {
+        if self.shared {
+            0
+        } else {
+            walker
+        }
+    }
+
+    /// Runs for up to `max_steps` steps or until every estimate has converged.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The maximum number of steps to perform
+    pub fn run(&mut self, max_steps: u64) {
+        for _ in 0..max_steps {
+            if self.step() {
+                break;
+            }
+        }
+    }
+
+    /// Returns whether all walkers update a single shared ln(g).
+    ///
+    /// # Returns
+    ///
+    /// `true` for a batch built with [`shared`](Self::shared)
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// Returns the number of walkers.
+    ///
+    /// # Returns
+    ///
+    /// The number of walkers in the batch
+    pub fn n_walkers(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns the ln(g) estimate a walker updates.
+    ///
+    /// # Parameters
+    ///
+    /// * `walker` - The walker index
+    ///
+    /// # Returns
+    ///
+    /// The ln(g) values for each bin; the same slice for every walker of a
+    /// shared batch
+    pub fn ln_g(&self, walker: usize) -> &[f64] {
+        &self.dos[self.dos_index(walker)].ln_g
+    }
+
+    /// Returns the histogram a walker updates.
+    ///
+    /// # Parameters
+    ///
+    /// * `walker` - The walker index
+    ///
+    /// # Returns
+    ///
+    /// The visit counts for each bin in the current stage
+    pub fn histogram(&self, walker: usize) -> &[u64] {
+        &self.dos[self.dos_index(walker)].hist
+    }
+
+    /// Returns the modification factor of the estimate a walker updates.
+    ///
+    /// # Parameters
+    ///
+    /// * `walker` - The walker index
+    ///
+    /// # Returns
+    ///
+    /// The current ln_f value
+    pub fn ln_f(&self, walker: usize) -> f64 {
+        self.dos[self.dos_index(walker)].ln_f
+    }
+
+    /// Returns the current states of all walkers.
+    ///
+    /// # Returns
+    ///
+    /// One state per walker
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// Returns the number of steps performed so far.
+    ///
+    /// # Returns
+    ///
+    /// The current step count
+    pub fn step_count(&self) -> u64 {
+        self.step
+    }
+}
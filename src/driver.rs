@@ -7,10 +7,19 @@
 //! The key component is the [`WLDriver`] struct, which orchestrates the
 //! sampling process using the traits defined in the crate.
 
+use std::fmt;
+use std::io::{self, Write};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
 use rand::{Rng, RngCore};
 
-use crate::rng::Rng64;
-use crate::traits::{Flatness, Macrospace, Move, Schedule, State};
+use crate::acceptance::Metropolis;
+use crate::analysis::{self, Accumulator};
+use crate::error::WLError;
+use crate::float::Float;
+use crate::rng::{CountingRng, Rng64};
+use crate::traits::{Acceptance, BinIndex, Flatness, Macrospace, Move, Schedule, State};
 
 /// Configurable parameters for Wang-Landau sampling.
 ///
@@ -22,6 +31,10 @@ use crate::traits::{Flatness, Macrospace, Move, Schedule, State};
 /// * `ln_f_min` - The minimum value of ln_f for convergence (not used directly by the driver)
 /// * `flatness` - The flatness parameter (typically between 0.0 and 1.0)
 /// * `sweep_len` - The number of move proposals per Wang-Landau step
+/// * `production_tail` - Extra frozen-ln(g) steps [`WLDriver::run`] performs after convergence
+/// * `clamp_ln_g_nonneg` - Shift ln(g) up so that no entry is negative
+/// * `kl_stop` - Stop once consecutive stages agree to within this KL divergence
+/// * `check_finite` - Panic as soon as an ln(g) entry becomes NaN or infinite
 ///
 /// # Example
 ///
@@ -37,9 +50,11 @@ use crate::traits::{Flatness, Macrospace, Move, Schedule, State};
 ///     ln_f_min: 1e-8,
 ///     flatness: 0.9, // Stricter flatness criterion
 ///     sweep_len: 10, // More move proposals per step
+///     ..Default::default()
 /// };
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Params {
     /// Initial modification factor value (ln f)
     pub ln_f0: f64,
@@ -52,6 +67,28 @@ pub struct Params {
 
     /// Number of move proposals per Wang-Landau step
     pub sweep_len: usize,
+
+    /// Number of frozen-ln(g) steps to run after convergence (default 0)
+    pub production_tail: u64,
+
+    /// Keep every ln(g) entry non-negative by shifting the whole curve up at
+    /// each flat event and at the end of [`WLDriver::run`] (default `false`).
+    /// Only differences of ln(g) enter the acceptance, so this is purely a
+    /// normalization convention, e.g. for tools expecting non-negative
+    /// log-densities. Pinned bins are shifted along with the rest.
+    pub clamp_ln_g_nonneg: bool,
+
+    /// Report convergence once the KL divergence between the ln(g) of
+    /// consecutive stages, see [`analysis::kl_divergence`], is below this
+    /// value (default `None`)
+    pub kl_stop: Option<f64>,
+
+    /// Verify after every step that ln(g) is finite and panic with
+    /// [`WLError::NonFinite`] naming the first offending bin otherwise
+    /// (default `false`). Catches a custom component poisoning ln(g) early,
+    /// at the cost of a scan over all bins per step. Pinned bins at `-inf`
+    /// are forbidden by design and exempt.
+    pub check_finite: bool,
 }
 
 impl Default for Params {
@@ -61,10 +98,327 @@ impl Default for Params {
             ln_f_min: 1e-8,
             flatness: 0.8,
             sweep_len: 1,
+            production_tail: 0,
+            clamp_ln_g_nonneg: false,
+            kl_stop: None,
+            check_finite: false,
         }
     }
 }
 
+/// Outcome of a single [`WLDriver::step_detailed`].
+///
+/// # Fields
+///
+/// * `converged` - Whether the schedule reported convergence
+/// * `flat_event` - Whether the histogram was flat, ending a stage
+/// * `ln_f` - The modification factor after the step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepOutcome {
+    /// Whether the schedule reported convergence
+    pub converged: bool,
+
+    /// Whether the histogram was flat, ending a stage
+    pub flat_event: bool,
+
+    /// Modification factor after the step
+    pub ln_f: f64,
+}
+
+/// Progress handed to the [`WLDriver::on_flat`] callback at a flat event.
+///
+/// # Fields
+///
+/// * `stage` - The number of stages completed, counting the one that just ended
+/// * `ln_f` - The modification factor after the schedule's update
+/// * `step` - The step count at the flat event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlatEvent {
+    /// Number of stages completed, counting the one that just ended
+    pub stage: u32,
+
+    /// Modification factor after the schedule's update
+    pub ln_f: f64,
+
+    /// Step count at the flat event
+    pub step: u64,
+}
+
+/// The dynamic state of a driver, captured by [`WLDriver::checkpoint`].
+///
+/// Holds everything a run needs to continue exactly where it stopped:
+/// the walker's state, ln(g), the histograms, ln_f, the step count, the
+/// parameters and the random number generator, as well as the schedule and
+/// flatness criterion with their internal counters (e.g. `OneOverT::t`).
+/// Moves and mappers are logic rather than state, so they are supplied
+/// afresh to [`WLDriver::from_checkpoint`]. With the `serde` feature the
+/// checkpoint is `Serialize` and `Deserialize` whenever its components are,
+/// which includes [`Rng64`] and the built-in schedules and flatness
+/// criteria.
+///
+/// Opt-in recorders and monitors, callbacks, per-bin and
+/// scheduled flatness thresholds, the initial ensemble and the acceptance
+/// rule are not captured; re-enable them on the restored driver.
+///
+/// # Fields
+///
+/// * `state` - The walker's current state
+/// * `ln_g` - The ln(g) value of each bin
+/// * `hist` - The visit histogram of the current stage
+/// * `total_hist` - The visits to each bin over the whole run
+/// * `first_visit` - The step at which each bin was first visited
+/// * `pinned` - Whether each bin's ln(g) is held fixed
+/// * `active` - The active-bin mask, if one was set
+/// * `bias` - The static external bias of each bin
+/// * `kl_prev` - The ln(g) of the previous stage, kept for `Params::kl_stop`
+/// * `freeze` - The local-freezing state, if freezing was enabled
+/// * `ln_f` - The current modification factor
+/// * `step` - The current step count
+/// * `params` - The algorithm parameters
+/// * `seed` - The seed the generator was created from, if known
+/// * `rng` - The random number generator
+/// * `sched` - The schedule
+/// * `flat` - The flatness criterion
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint<S, R = Rng64, Sch = crate::schedule::Geometric, F = crate::flatness::Fraction>
+{
+    /// The walker's current state
+    pub state: S,
+
+    /// ln(g) value of each bin
+    pub ln_g: Vec<f64>,
+
+    /// Visit histogram of the current stage
+    pub hist: Vec<u64>,
+
+    /// Visits to each bin over the whole run
+    pub total_hist: Vec<u64>,
+
+    /// Step at which each bin was first visited
+    pub first_visit: Vec<Option<u64>>,
+
+    /// Whether each bin's ln(g) is held fixed
+    pub pinned: Vec<bool>,
+
+    /// Active-bin mask, if one was set
+    pub active: Option<Vec<bool>>,
+
+    /// Static external bias of each bin
+    pub bias: Vec<f64>,
+
+    /// ln(g) of the previous stage, kept for `Params::kl_stop`
+    pub kl_prev: Vec<f64>,
+
+    /// Local-freezing state, if freezing was enabled
+    pub freeze: Option<FreezeState>,
+
+    /// Current modification factor
+    pub ln_f: f64,
+
+    /// Current step count
+    pub step: u64,
+
+    /// Algorithm parameters
+    pub params: Params,
+
+    /// Seed the generator was created from, if known
+    pub seed: Option<u64>,
+
+    /// Random number generator
+    pub rng: R,
+
+    /// Schedule, including its internal counters
+    pub sched: Sch,
+
+    /// Flatness criterion
+    pub flat: F,
+}
+
+/// One acceptance decision recorded by the audit log.
+///
+/// Symmetric moves within a bin and moves into or out of a forbidden bin are
+/// decided without a random draw; for those `delta` and `draw` are `None`.
+/// Otherwise the move was accepted exactly when
+/// `draw < acceptance.probability(delta)`, where `delta` is only the
+/// proposal's log ratio for a move within a bin.
+///
+/// # Fields
+///
+/// * `step` - The step count when the proposal was made
+/// * `bin_old` - The bin before the proposal
+/// * `bin_new` - The bin of the proposed state
+/// * `delta` - The log acceptance ratio passed to the acceptance rule
+/// * `draw` - The uniform random number compared with the probability
+/// * `accepted` - Whether the move was accepted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AuditEntry {
+    /// Step count when the proposal was made
+    pub step: u64,
+
+    /// Bin before the proposal
+    pub bin_old: usize,
+
+    /// Bin of the proposed state
+    pub bin_new: usize,
+
+    /// Log acceptance ratio, if the decision was random
+    pub delta: Option<f64>,
+
+    /// Uniform random draw, if the decision was random
+    pub draw: Option<f64>,
+
+    /// Whether the move was accepted
+    pub accepted: bool,
+}
+
+/// Fraction of empty bins above which [`WLDriver::binning_quality`] calls a
+/// binning too fine.
+const TOO_FINE_EMPTY_FRACTION: f64 = 0.1;
+
+/// Largest ln(g) change between adjacent visited bins below which
+/// [`WLDriver::binning_quality`] accepts a binning as fine enough.
+const TOO_COARSE_LN_G_STEP: f64 = 3.0;
+
+/// Verdict of [`WLDriver::binning_quality`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinningSuggestion {
+    /// Many bins are never visited; wider bins would waste less of the run
+    TooFine,
+
+    /// ln(g) jumps sharply between neighbouring bins; narrower bins would
+    /// resolve its structure
+    TooCoarse,
+
+    /// Neither heuristic fired
+    Ok,
+}
+
+/// Diagnostic summary of how well the binning matches the sampled states.
+///
+/// # Fields
+///
+/// * `empty_fraction` - The fraction of active bins never visited
+/// * `occupancy_ratio` - The max/min cumulative count over visited bins
+/// * `suggestion` - The heuristic verdict
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinningReport {
+    /// Fraction of active bins with no cumulative visits
+    pub empty_fraction: f64,
+
+    /// Ratio of the largest to the smallest cumulative count among visited
+    /// bins, or `None` if no bin was visited
+    pub occupancy_ratio: Option<f64>,
+
+    /// Heuristic verdict on the bin width
+    pub suggestion: BinningSuggestion,
+}
+
+/// Snapshot of the driver's internal state returned by [`WLDriver::debug_state`].
+///
+/// The arrays are copies, so the snapshot stays valid while the driver runs
+/// on. Its `Debug` output is a summary rather than a dump of every bin:
+/// ranges and totals of the arrays alongside the scalar fields, which reads
+/// well with `{:#?}`.
+///
+/// # Fields
+///
+/// * `ln_g` - The ln(g) value of each bin, as `f64`
+/// * `hist` - The visit histogram of the current stage
+/// * `ln_f` - The current modification factor
+/// * `step` - The number of Wang-Landau steps performed
+/// * `bin` - The bin of the current state
+/// * `acceptance_rate` - The inter-bin acceptance rate of the current stage
+/// * `visited_bins` - The number of bins visited at least once in the run
+#[derive(Clone, PartialEq)]
+pub struct DebugState {
+    /// ln(g) of each bin
+    pub ln_g: Vec<f64>,
+
+    /// Visit histogram of the current stage
+    pub hist: Vec<u64>,
+
+    /// Current modification factor
+    pub ln_f: f64,
+
+    /// Wang-Landau steps performed
+    pub step: u64,
+
+    /// Bin of the current state
+    pub bin: usize,
+
+    /// Inter-bin acceptance rate of the current stage, if recorded via
+    /// [`WLDriver::with_stage_acceptance`] and any such proposal was made
+    pub acceptance_rate: Option<f64>,
+
+    /// Bins with at least one visit in the cumulative histogram
+    pub visited_bins: usize,
+}
+
+impl fmt::Debug for DebugState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let finite = self.ln_g.iter().copied().filter(|g| g.is_finite());
+        let ln_g_min = finite.clone().fold(f64::INFINITY, f64::min);
+        let ln_g_max = finite.fold(f64::NEG_INFINITY, f64::max);
+        let hist_min = self.hist.iter().copied().min().unwrap_or(0);
+        let hist_max = self.hist.iter().copied().max().unwrap_or(0);
+        f.debug_struct("DebugState")
+            .field("step", &self.step)
+            .field("ln_f", &self.ln_f)
+            .field("bin", &self.bin)
+            .field(
+                "coverage",
+                &format_args!("{}/{} bins", self.visited_bins, self.ln_g.len()),
+            )
+            .field("acceptance_rate", &self.acceptance_rate)
+            .field("ln_g_range", &(ln_g_min, ln_g_max))
+            .field("hist_total", &self.hist.iter().sum::<u64>())
+            .field("hist_range", &(hist_min, hist_max))
+            .finish()
+    }
+}
+
+/// Steps between wall-clock reads in [`WLDriver::run_for`].
+#[cfg(feature = "std")]
+const CLOCK_CHECK_INTERVAL: u64 = 1000;
+
+/// Outcome of a time-limited [`WLDriver::run_for`].
+///
+/// # Fields
+///
+/// * `steps` - The number of Wang-Landau steps performed by this call
+/// * `elapsed` - The wall time the call took
+/// * `converged` - Whether the schedule reported convergence
+/// * `ln_f` - The modification factor when the call returned
+/// * `coverage` - The fraction of active bins visited at least once in the run
+/// * `sampling_efficiency` - min/mean of the cumulative histogram over the
+///   active bins
+///
+/// `converged` only says that ln_f reached the tolerance. A mis-set flatness
+/// criterion can let that happen with bins never visited, which shows up as
+/// `coverage` below 1 and a `sampling_efficiency` of 0.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct RunReport {
+    /// Wang-Landau steps performed by this call
+    pub steps: u64,
+
+    /// Wall time the call took
+    pub elapsed: Duration,
+
+    /// Whether the schedule reported convergence
+    pub converged: bool,
+
+    /// Modification factor when the call returned
+    pub ln_f: f64,
+
+    /// Fraction of active bins visited at least once in the run
+    pub coverage: f64,
+
+    /// min/mean of the cumulative histogram over the active bins
+    pub sampling_efficiency: f64,
+}
+
 /// Generic single-walker Wang-Landau sampling engine.
 ///
 /// This struct implements the Wang-Landau algorithm for arbitrary state spaces
@@ -80,6 +434,16 @@ impl Default for Params {
 /// * `R` - The random number generator type (defaults to PCG-64)
 /// * `Sch` - The modification factor schedule type (defaults to geometric)
 /// * `F` - The histogram flatness criterion type (defaults to fraction-based)
+/// * `Flt` - The floating-point type of ln(g) (defaults to `f64`, see [`crate::float`])
+/// * `A` - The acceptance rule (defaults to [`Metropolis`])
+///
+/// # Callbacks
+///
+/// Closures installed with [`on_first_visit`](Self::on_first_visit),
+/// [`on_flat`](Self::on_flat),
+/// [`with_beta_schedule`](Self::with_beta_schedule) and
+/// [`with_flatness_schedule`](Self::with_flatness_schedule) must be `Send`,
+/// so that a driver holding them can still be moved to another thread.
 ///
 /// # Example
 ///
@@ -135,13 +499,17 @@ pub struct WLDriver<
     R = Rng64,
     Sch = crate::schedule::Geometric,
     F = crate::flatness::Fraction,
+    Flt = f64,
+    A = Metropolis,
 > where
     S: State,
     Mv: Move<S, R>,
-    Map: Macrospace<S, Bin = usize>,
+    Map: Macrospace<S>,
     R: RngCore,
     Sch: Schedule,
     F: Flatness,
+    Flt: Float,
+    A: Acceptance,
 {
     /// Current system state
     state: S,
@@ -152,8 +520,11 @@ pub struct WLDriver<
     /// Maps states to macroscopic bins
     mapper: Map,
 
+    /// Extent of each bin dimension, used to flatten bins
+    shape: Vec<usize>,
+
     /// Current estimate of ln(density of states)
-    ln_g: Vec<f64>,
+    ln_g: Vec<Flt>,
 
     /// Histogram of visited states
     hist: Vec<u64>,
@@ -167,27 +538,230 @@ pub struct WLDriver<
     /// Random number generator
     rng: R,
 
+    /// Seed `rng` was created from, when the driver constructed it
+    seed: Option<u64>,
+
+    /// Reads the draw count of a counting `rng` (opt-in)
+    draw_count: Option<fn(&R) -> u64>,
+
     /// Modification factor update schedule
     sched: Sch,
 
     /// Histogram flatness criterion
     flat: F,
 
+    /// Acceptance rule for moves between bins
+    acceptance: A,
+
     /// Current step count
     step: u64,
+
+    /// Histograms captured at each flat event (opt-in)
+    stage_hists: Option<Vec<Vec<u64>>>,
+
+    /// ln_g and ln_f captured at each flat event (opt-in)
+    stage_ln_g: Option<Vec<(Vec<f64>, f64)>>,
+
+    /// ln_g of every bin at each flat event, `[stage][bin]` (opt-in)
+    ln_g_history: Option<Vec<Vec<f64>>>,
+
+    /// Inter-bin acceptance counters and per-stage rates (opt-in)
+    stage_accept: Option<StageAcceptance>,
+
+    /// Bins whose ln_g is held fixed
+    pinned: Vec<bool>,
+
+    /// Bins the walker may enter and flatness considers (all if `None`)
+    active: Option<Vec<bool>>,
+
+    /// Scratch buffer for the histogram of active bins
+    active_hist: Vec<u64>,
+
+    /// Scratch buffer for the bin number of each entry of `active_hist`
+    active_bins: Vec<usize>,
+
+    /// Per-bin flatness thresholds replacing `params.flatness` (opt-in)
+    bin_flatness: Option<Vec<f64>>,
+
+    /// Total-variation convergence monitor (opt-in)
+    tv: Option<TvMonitor>,
+
+    /// Configurations the walker may be (re)started from
+    ensemble: Vec<S>,
+
+    /// Static bias added to ln_g during acceptance only
+    bias: Vec<Flt>,
+
+    /// Bin index after each frozen-ln_g step (opt-in)
+    bin_series: Option<Vec<usize>>,
+
+    /// Downsampled trajectory of visited states (opt-in)
+    trajectory: Option<Trajectory<S>>,
+
+    /// ln_g at the end of the previous stage, kept for `Params::kl_stop`
+    kl_prev: Vec<f64>,
+
+    /// Visits to each bin over the whole run, never reset
+    total_hist: Vec<u64>,
+
+    /// Step at which each bin was first visited
+    first_visit: Vec<Option<u64>>,
+
+    /// Callback invoked on each bin's first visit
+    on_first_visit: Option<Box<dyn FnMut(usize, u64) + Send>>,
+
+    /// Exponential moving average of recent visits (opt-in)
+    visit_rate: Option<VisitRate>,
+
+    /// Every acceptance decision (opt-in)
+    audit: Option<Vec<AuditEntry>>,
+
+    /// Adaptive freezing of locally converged bins (opt-in)
+    freeze: Option<FreezeState>,
+
+    /// Prefactor applied to the ln(g) and bias difference in the acceptance
+    tempering_beta: f64,
+
+    /// Sets `tempering_beta` from the step count at the start of each step
+    beta_schedule: Option<Box<dyn FnMut(u64) -> f64 + Send>>,
+
+    /// Stage-dependent replacement for `params.flatness` (opt-in)
+    flatness_schedule: Option<FlatnessSchedule>,
+
+    /// Callback invoked after each flat event, with the stages completed
+    on_flat: Option<(Box<dyn FnMut(FlatEvent) + Send>, u32)>,
+
+    /// Split ln_f updates between neighboring bins with a linear kernel
+    kernel_deposition: bool,
+
+    /// Skip the `+= ln_f` update while still filling the histogram
+    ln_g_frozen: bool,
+}
+
+/// Stage-dependent flatness threshold.
+struct FlatnessSchedule {
+    /// Returns the threshold for a stage index
+    threshold: Box<dyn Fn(u32) -> f64 + Send>,
+
+    /// Index of the current stage, counting flat events since installation
+    stage: u32,
+}
+
+/// Downsampled record of the walker's states.
+struct Trajectory<S> {
+    /// Record one state every this many steps
+    every: usize,
+
+    /// Steps since the last recorded state
+    since: usize,
+
+    /// Recorded states
+    states: Vec<S>,
+}
+
+/// Per-bin exponential moving average of visits per step.
+///
+/// Decay is applied lazily: a bin's average is only brought up to date when
+/// it is visited or read, so tracking costs O(1) per proposal.
+struct VisitRate {
+    /// Factor applied to the average per step, `1 - 1/window`
+    decay: f64,
+
+    /// Average as of `last[bin]`
+    rates: Vec<f64>,
+
+    /// Step at which each average was last brought up to date
+    last: Vec<u64>,
+}
+
+impl VisitRate {
+    /// Records one visit to `bin` during step `step`.
+    fn visit(&mut self, bin: usize, step: u64) {
+        // The visit counts towards the average at the end of the step
+        let end = step + 1;
+        let rate = self.rate(bin, end);
+        self.rates[bin] = rate + (1.0 - self.decay);
+        self.last[bin] = end;
+    }
+
+    /// Average of `bin` once `steps` steps have completed.
+    fn rate(&self, bin: usize, steps: u64) -> f64 {
+        self.rates[bin] * self.decay.powf(steps.saturating_sub(self.last[bin]) as f64)
+    }
+}
+
+/// State of adaptive freezing of locally converged bins.
+///
+/// Set up by [`WLDriver::with_local_freezing`] and carried by a
+/// [`Checkpoint`] so that frozen bins stay frozen on restore.
+///
+/// # Fields
+///
+/// * `threshold` - The largest stage-to-stage ln(g) change that counts as
+///   converged
+/// * `stages` - The number of consecutive converged stages after which a bin
+///   is frozen
+/// * `prev_ln_g` - The ln(g) at the end of the previous stage
+/// * `calm` - The consecutive stages each bin has changed by less than
+///   `threshold`
+/// * `frozen` - Whether each bin has been frozen
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FreezeState {
+    /// Largest stage-to-stage ln(g) change that counts as converged
+    pub threshold: f64,
+
+    /// Consecutive converged stages after which a bin is frozen
+    pub stages: usize,
+
+    /// ln(g) at the end of the previous stage
+    pub prev_ln_g: Vec<f64>,
+
+    /// Consecutive stages each bin has changed by less than `threshold`
+    pub calm: Vec<usize>,
+
+    /// Whether each bin has been frozen
+    pub frozen: Vec<bool>,
+}
+
+/// Inter-bin acceptance counts of the current stage and rates of past ones.
+struct StageAcceptance {
+    /// Proposals into a different bin during the current stage
+    proposed: u64,
+
+    /// Of those, the number accepted
+    accepted: u64,
+
+    /// Acceptance rate of every finished stage, `None` without any proposal
+    rates: Vec<Option<f64>>,
+}
+
+/// State of the total-variation convergence monitor.
+struct TvMonitor {
+    /// Distance below which the run counts as converged
+    threshold: f64,
+
+    /// ln_g at the end of the previous stage
+    prev_ln_g: Vec<f64>,
+
+    /// Distance recorded at each flat event
+    history: Vec<f64>,
 }
 
 impl<S, Mv, Map, R, Sch, F> WLDriver<S, Mv, Map, R, Sch, F>
 where
     S: State,
     Mv: Move<S, R>,
-    Map: Macrospace<S, Bin = usize>,
+    Map: Macrospace<S>,
     R: RngCore,
     Sch: Schedule,
     F: Flatness,
 {
     /// Creates a new Wang-Landau driver with the specified components.
     ///
+    /// ln(g) is stored as `f64`; use [`with_precision`](Self::with_precision)
+    /// to select another [`Float`] type.
+    ///
     /// # Parameters
     ///
     /// * `state` - The initial system state
@@ -211,114 +785,2861 @@ where
         flat: F,
         rng: R,
     ) -> Self {
-        let n_bins = mapper.bins().len();
+        let shape = mapper.shape();
+        let n_bins = shape.iter().product();
         Self {
+            ensemble: vec![state.clone()],
             state,
             moves,
             mapper,
+            shape,
             ln_g: vec![0.0; n_bins],
             hist: vec![0; n_bins],
             ln_f: params.ln_f0,
             params,
             rng,
+            seed: None,
+            draw_count: None,
             sched,
             flat,
+            acceptance: Metropolis,
             step: 0,
+            stage_hists: None,
+            stage_ln_g: None,
+            ln_g_history: None,
+            stage_accept: None,
+            pinned: vec![false; n_bins],
+            active: None,
+            active_hist: Vec::new(),
+            active_bins: Vec::new(),
+            bin_flatness: None,
+            tv: None,
+            bias: vec![0.0; n_bins],
+            bin_series: None,
+            trajectory: None,
+            kl_prev: match params.kl_stop {
+                Some(_) => vec![0.0; n_bins],
+                None => Vec::new(),
+            },
+            total_hist: vec![0; n_bins],
+            first_visit: vec![None; n_bins],
+            on_first_visit: None,
+            visit_rate: None,
+            audit: None,
+            freeze: None,
+            tempering_beta: 1.0,
+            beta_schedule: None,
+            flatness_schedule: None,
+            on_flat: None,
+            kernel_deposition: false,
+            ln_g_frozen: false,
         }
     }
 
-    /// Performs one Wang-Landau step, consisting of multiple move proposals and histogram updates.
+    /// Creates a new driver after checking that the mapper's bins are contiguous.
     ///
-    /// A single step consists of:
-    /// 1. Proposing `sweep_len` moves and applying Wang-Landau acceptance
-    /// 2. Checking histogram flatness
-    /// 3. Updating the modification factor if the histogram is flat
+    /// The driver stores ln(g) and the histogram in flat arrays indexed by
+    /// the bins' flat indices, so [`Macrospace::bins`] must list exactly the
+    /// flat indices `0..n` in order, `n` being the product of
+    /// [`Macrospace::shape`]. A mapper that lists e.g. `[0, 2, 4]` would
+    /// otherwise index past the arrays or silently share storage between
+    /// bins. [`new`](Self::new) skips this check.
+    ///
+    /// # Parameters
+    ///
+    /// The same as for [`new`](Self::new).
     ///
     /// # Returns
     ///
-    /// `true` if the algorithm has converged (ln_f below tolerance), `false` otherwise
-    pub fn step(&mut self) -> bool {
-        for _ in 0..self.params.sweep_len {
-            // --- propose move & evaluate bins --------------------
-            let bin_old: usize = self.mapper.locate(&self.state);
-            let prev_state = self.state.clone();
-
-            self.moves.propose(&mut self.state, &mut self.rng);
-            let bin_new: usize = self.mapper.locate(&self.state);
-
-            // --- WL acceptance -----------------------------------
-            let accept = if bin_new == bin_old {
-                true
-            } else {
-                let delta = self.ln_g[bin_old] - self.ln_g[bin_new];
-                self.rng.random::<f64>() < delta.exp()
-            };
-            let bin_final = if accept {
-                bin_new
-            } else {
-                self.state = prev_state;
-                bin_old
-            };
-
-            // --- WL bookkeeping ----------------------------------
-            self.ln_g[bin_final] += self.ln_f;
-            self.hist[bin_final] += 1;
+    /// The new driver, [`WLError::NonContiguousBins`] at the first bin whose
+    /// flat index is not its position, or [`WLError::LengthMismatch`] if
+    /// `bins()` and `shape()` disagree on the number of bins
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        state: S,
+        moves: Mv,
+        mapper: Map,
+        params: Params,
+        sched: Sch,
+        flat: F,
+        rng: R,
+    ) -> Result<Self, WLError> {
+        let shape = mapper.shape();
+        let bins = mapper.bins();
+        let n_bins: usize = shape.iter().product();
+        if bins.len() != n_bins {
+            return Err(WLError::LengthMismatch {
+                expected: n_bins,
+                found: bins.len(),
+            });
         }
-
-        if self.flat.is_flat(&self.hist, self.params.flatness) {
-            self.hist.fill(0);
-            if self.sched.update(&mut self.ln_f) {
-                return true;
-            }
+        if let Some((position, found)) = bins
+            .iter()
+            .map(|bin| bin.to_flat(&shape))
+            .enumerate()
+            .find(|&(position, flat)| position != flat)
+        {
+            return Err(WLError::NonContiguousBins { position, found });
         }
-
-        self.step += 1;
-        false
+        Ok(Self::new(state, moves, mapper, params, sched, flat, rng))
     }
 
-    /// Runs the Wang-Landau simulation for up to `max_steps` steps or until convergence.
+    /// Restores a driver from a checkpoint with fresh moves and mapper.
     ///
-    /// The simulation will stop early if the modification factor falls below
-    /// the tolerance specified in the schedule.
+    /// The restored driver continues the run of the one that wrote the
+    /// checkpoint step for step, given moves and a mapper equivalent to the
+    /// original ones. Components not captured by [`Checkpoint`] start out as
+    /// in [`new`](Self::new).
     ///
     /// # Parameters
     ///
-    /// * `max_steps` - The maximum number of Wang-Landau steps to perform
-    pub fn run(&mut self, max_steps: u64) {
-        for _ in 0..max_steps {
-            if self.step() {
-                break;
-            }
-        }
-    }
-
-    /// Returns the current estimate of ln(density of states).
+    /// * `checkpoint` - The state written by [`checkpoint`](Self::checkpoint)
+    /// * `moves` - The move proposal generator
+    /// * `mapper` - The mapper from states to bins
     ///
     /// # Returns
     ///
-    /// A slice containing the ln(g) values for each bin
-    pub fn ln_g(&self) -> &[f64] {
-        &self.ln_g
+    /// The restored driver, or [`WLError::LengthMismatch`] if a per-bin array
+    /// of the checkpoint does not match the mapper's bin count
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use wanglandau::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Coin(bool);
+    /// # impl State for Coin {}
+    /// # struct Flip;
+    /// # impl<R: rand::RngCore> Move<Coin, R> for Flip {
+    /// #     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+    /// #         use rand::Rng;
+    /// #         s.0 = rng.random();
+    /// #     }
+    /// # }
+    /// # struct Mapper;
+    /// # impl Macrospace<Coin> for Mapper {
+    /// #     type Bin = usize;
+    /// #     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+    /// #     fn bins(&self) -> &[usize] { &[0, 1] }
+    /// # }
+    /// let mut driver = WLDriver::new(
+    ///     Coin(false), Flip, Mapper, Params::default(),
+    ///     OneOverT::default(), Fraction, wanglandau::rng::seeded(1),
+    /// );
+    /// driver.run(100_000);
+    ///
+    /// // Serialize `checkpoint` with the `serde` feature, reload it later...
+    /// let checkpoint = driver.checkpoint();
+    /// let mut resumed = WLDriver::from_checkpoint(checkpoint, Flip, Mapper).unwrap();
+    /// resumed.run(1_000_000);
+    /// ```
+    pub fn from_checkpoint(
+        checkpoint: Checkpoint<S, R, Sch, F>,
+        moves: Mv,
+        mapper: Map,
+    ) -> Result<Self, WLError> {
+        let mut drv = Self::new(
+            checkpoint.state,
+            moves,
+            mapper,
+            checkpoint.params,
+            checkpoint.sched,
+            checkpoint.flat,
+            checkpoint.rng,
+        );
+        let n_bins = drv.ln_g.len();
+        // ln(g) of the previous stage is only kept when `kl_stop` is set
+        let kl_bins = match checkpoint.params.kl_stop {
+            Some(_) => n_bins,
+            None => 0,
+        };
+        let freeze_lengths = checkpoint.freeze.as_ref().map_or([n_bins; 3], |freeze| {
+            [
+                freeze.prev_ln_g.len(),
+                freeze.calm.len(),
+                freeze.frozen.len(),
+            ]
+        });
+        let mut lengths = [
+            (n_bins, checkpoint.ln_g.len()),
+            (n_bins, checkpoint.hist.len()),
+            (n_bins, checkpoint.total_hist.len()),
+            (n_bins, checkpoint.first_visit.len()),
+            (n_bins, checkpoint.pinned.len()),
+            (n_bins, checkpoint.bias.len()),
+            (kl_bins, checkpoint.kl_prev.len()),
+            (n_bins, checkpoint.active.as_ref().map_or(n_bins, Vec::len)),
+        ]
+        .into_iter()
+        .chain(freeze_lengths.map(|len| (n_bins, len)));
+        if let Some((expected, found)) = lengths.find(|&(expected, found)| found != expected) {
+            return Err(WLError::LengthMismatch { expected, found });
+        }
+        drv.ln_g = checkpoint.ln_g;
+        drv.hist = checkpoint.hist;
+        drv.total_hist = checkpoint.total_hist;
+        drv.first_visit = checkpoint.first_visit;
+        drv.pinned = checkpoint.pinned;
+        drv.active = checkpoint.active;
+        drv.bias = checkpoint.bias;
+        drv.kl_prev = checkpoint.kl_prev;
+        drv.freeze = checkpoint.freeze;
+        drv.ln_f = checkpoint.ln_f;
+        drv.step = checkpoint.step;
+        drv.seed = checkpoint.seed;
+        Ok(drv)
     }
 
-    /// Returns the current histogram of visited states.
+    /// Creates a driver starting from a random member of a configuration ensemble.
+    ///
+    /// Starting from one of several pre-thermalized configurations reduces
+    /// the bias of a single initial condition. The starting state is drawn
+    /// uniformly from `states` using `rng`, and the ensemble is kept so that
+    /// [`reset_to_random_initial`](Self::reset_to_random_initial) can re-pick
+    /// for multi-start runs. The other parameters are as for
+    /// [`new`](Self::new).
+    ///
+    /// # Parameters
+    ///
+    /// * `states` - The candidate initial states
+    /// * `moves` - The move proposal generator
+    /// * `mapper` - The state-to-bin mapping
+    /// * `params` - The algorithm parameters
+    /// * `sched` - The modification factor update schedule
+    /// * `flat` - The histogram flatness criterion
+    /// * `rng` - The random number generator
     ///
     /// # Returns
     ///
-    /// A slice containing the visit counts for each bin
-    pub fn histogram(&self) -> &[u64] {
-        &self.hist
+    /// A new `WLDriver` whose state is one of `states`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `states` is empty.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_initial_ensemble(
+        states: Vec<S>,
+        moves: Mv,
+        mapper: Map,
+        params: Params,
+        sched: Sch,
+        flat: F,
+        rng: R,
+    ) -> Self {
+        assert!(!states.is_empty(), "initial ensemble is empty");
+        let mut drv = Self::new(states[0].clone(), moves, mapper, params, sched, flat, rng);
+        drv.ensemble = states;
+        drv.reset_to_random_initial();
+        drv
     }
+}
 
-    /// Returns the current modification factor (ln f).
+impl<S, Mv, Map, R, Sch, F, Flt, A> WLDriver<S, Mv, Map, R, Sch, F, Flt, A>
+where
+    S: State,
+    Mv: Move<S, R>,
+    Map: Macrospace<S>,
+    R: CountingRng,
+    Sch: Schedule,
+    F: Flatness,
+    Flt: Float,
+    A: Acceptance,
+{
+    /// Reports the draws of the driver's counting generator.
+    ///
+    /// Available when the generator implements [`CountingRng`], e.g. a
+    /// [`Budgeted`](crate::rng::Budgeted) wrapper. Read the count with
+    /// [`rng_draws`](Self::rng_draws).
     ///
     /// # Returns
     ///
-    /// The current ln_f value
-    pub fn ln_f(&self) -> f64 {
+    /// The driver with draw counting enabled
+    pub fn with_draw_count(mut self) -> Self {
+        self.draw_count = Some(R::draws_so_far);
+        self
+    }
+}
+
+impl<S, Mv, Map, Sch, F> WLDriver<S, Mv, Map, Rng64, Sch, F>
+where
+    S: State,
+    Mv: Move<S, Rng64>,
+    Map: Macrospace<S>,
+    Sch: Schedule,
+    F: Flatness,
+{
+    /// Creates a driver with a PCG-64 generator seeded from `seed`.
+    ///
+    /// This is [`new`](Self::new) with the RNG built by
+    /// [`rng::seeded`](crate::rng::seeded). The driver remembers the seed,
+    /// so it can be reported by [`seed`](Self::seed) for provenance.
+    ///
+    /// # Parameters
+    ///
+    /// * `state` - The initial system state
+    /// * `moves` - The move proposal generator
+    /// * `mapper` - The state-to-bin mapping
+    /// * `params` - The algorithm parameters
+    /// * `sched` - The modification factor update schedule
+    /// * `flat` - The histogram flatness criterion
+    /// * `seed` - The seed of the random number generator
+    ///
+    /// # Returns
+    ///
+    /// A new `WLDriver` instance initialized and ready to run
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_seeded(
+        state: S,
+        moves: Mv,
+        mapper: Map,
+        params: Params,
+        sched: Sch,
+        flat: F,
+        seed: u64,
+    ) -> Self {
+        let mut drv = Self::new(
+            state,
+            moves,
+            mapper,
+            params,
+            sched,
+            flat,
+            crate::rng::seeded(seed),
+        );
+        drv.seed = Some(seed);
+        drv
+    }
+}
+
+impl<S, Mv, Map, R, Sch, F, Flt, A> WLDriver<S, Mv, Map, R, Sch, F, Flt, A>
+where
+    S: State,
+    Mv: Move<S, R>,
+    Map: Macrospace<S>,
+    R: RngCore,
+    Sch: Schedule,
+    F: Flatness,
+    Flt: Float,
+    A: Acceptance,
+{
+    /// Converts the driver to store ln(g) at another floating-point precision.
+    ///
+    /// ln(g) and the external bias are converted (rounding if narrowing), and
+    /// the updates and the ln(g) differences of the acceptance run at the new
+    /// precision; the acceptance probability itself is still evaluated in
+    /// `f64`. The rest of the state carries over unchanged. `f32` halves the memory of
+    /// ln(g) at the cost of stalling refinement once `ln_f` drops below the
+    /// resolution of ln(g), see [`crate::float`].
+    ///
+    /// # Returns
+    ///
+    /// The same driver with ln(g) stored as `G`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use wanglandau::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Coin(bool);
+    /// # impl State for Coin {}
+    /// # struct Flip;
+    /// # impl<R: rand::RngCore> Move<Coin, R> for Flip {
+    /// #     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+    /// #         use rand::Rng;
+    /// #         s.0 = rng.random();
+    /// #     }
+    /// # }
+    /// # struct Mapper;
+    /// # impl Macrospace<Coin> for Mapper {
+    /// #     type Bin = usize;
+    /// #     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+    /// #     fn bins(&self) -> &[usize] { &[0, 1] }
+    /// # }
+    /// let mut driver = WLDriver::new(
+    ///     Coin(false), Flip, Mapper, Params::default(),
+    ///     Geometric { alpha: 0.5, tol: 1e-4 }, Fraction, wanglandau::rng::seeded(1),
+    /// )
+    /// .with_precision::<f32>();
+    ///
+    /// driver.run(1_000_000);
+    /// let ln_g: &[f32] = driver.ln_g();
+    /// ```
+    pub fn with_precision<G: Float>(self) -> WLDriver<S, Mv, Map, R, Sch, F, G, A> {
+        let convert = |v: Vec<Flt>| v.into_iter().map(|x| G::from_f64(x.to_f64())).collect();
+        WLDriver {
+            state: self.state,
+            moves: self.moves,
+            mapper: self.mapper,
+            shape: self.shape,
+            ln_g: convert(self.ln_g),
+            hist: self.hist,
+            ln_f: self.ln_f,
+            params: self.params,
+            rng: self.rng,
+            seed: self.seed,
+            draw_count: self.draw_count,
+            sched: self.sched,
+            flat: self.flat,
+            acceptance: self.acceptance,
+            step: self.step,
+            stage_hists: self.stage_hists,
+            stage_ln_g: self.stage_ln_g,
+            ln_g_history: self.ln_g_history,
+            stage_accept: self.stage_accept,
+            pinned: self.pinned,
+            active: self.active,
+            active_hist: self.active_hist,
+            active_bins: self.active_bins,
+            bin_flatness: self.bin_flatness,
+            tv: self.tv,
+            ensemble: self.ensemble,
+            bias: convert(self.bias),
+            bin_series: self.bin_series,
+            trajectory: self.trajectory,
+            kl_prev: self.kl_prev,
+            total_hist: self.total_hist,
+            first_visit: self.first_visit,
+            on_first_visit: self.on_first_visit,
+            visit_rate: self.visit_rate,
+            audit: self.audit,
+            freeze: self.freeze,
+            tempering_beta: self.tempering_beta,
+            beta_schedule: self.beta_schedule,
+            flatness_schedule: self.flatness_schedule,
+            on_flat: self.on_flat,
+            kernel_deposition: self.kernel_deposition,
+            ln_g_frozen: self.ln_g_frozen,
+        }
+    }
+
+    /// Replaces the acceptance rule for moves between bins.
+    ///
+    /// The default is [`Metropolis`], the standard `min(1, exp(delta))`;
+    /// [`GlauberWL`](crate::acceptance::GlauberWL) selects heat-bath
+    /// acceptance. The rest of the state carries over unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `acceptance` - The new acceptance rule
+    ///
+    /// # Returns
+    ///
+    /// The same driver using `acceptance`
+    pub fn with_acceptance<B: Acceptance>(
+        self,
+        acceptance: B,
+    ) -> WLDriver<S, Mv, Map, R, Sch, F, Flt, B> {
+        WLDriver {
+            state: self.state,
+            moves: self.moves,
+            mapper: self.mapper,
+            shape: self.shape,
+            ln_g: self.ln_g,
+            hist: self.hist,
+            ln_f: self.ln_f,
+            params: self.params,
+            rng: self.rng,
+            seed: self.seed,
+            draw_count: self.draw_count,
+            sched: self.sched,
+            flat: self.flat,
+            acceptance,
+            step: self.step,
+            stage_hists: self.stage_hists,
+            stage_ln_g: self.stage_ln_g,
+            ln_g_history: self.ln_g_history,
+            stage_accept: self.stage_accept,
+            pinned: self.pinned,
+            active: self.active,
+            active_hist: self.active_hist,
+            active_bins: self.active_bins,
+            bin_flatness: self.bin_flatness,
+            tv: self.tv,
+            ensemble: self.ensemble,
+            bias: self.bias,
+            bin_series: self.bin_series,
+            trajectory: self.trajectory,
+            kl_prev: self.kl_prev,
+            total_hist: self.total_hist,
+            first_visit: self.first_visit,
+            on_first_visit: self.on_first_visit,
+            visit_rate: self.visit_rate,
+            audit: self.audit,
+            freeze: self.freeze,
+            tempering_beta: self.tempering_beta,
+            beta_schedule: self.beta_schedule,
+            flatness_schedule: self.flatness_schedule,
+            on_flat: self.on_flat,
+            kernel_deposition: self.kernel_deposition,
+            ln_g_frozen: self.ln_g_frozen,
+        }
+    }
+
+    /// Moves the walker to a configuration drawn uniformly from the initial ensemble.
+    ///
+    /// Only the state is replaced; ln(g), the histogram and the schedule are
+    /// kept. For a driver built with [`new`](Self::new) the ensemble holds
+    /// just the initial state, so this returns the walker to it.
+    pub fn reset_to_random_initial(&mut self) {
+        let pick = self.rng.random_range(0..self.ensemble.len());
+        self.state = self.ensemble[pick].clone();
+    }
+
+    /// Enables recording of the histogram at every flat event.
+    ///
+    /// When enabled, the histogram is cloned just before each reset and the
+    /// sequence is exposed through [`stage_histograms`](Self::stage_histograms).
+    /// This reveals whether early stages were poorly sampled in some bins, but
+    /// it keeps one full histogram per stage in memory, so it is off by default.
+    ///
+    /// # Returns
+    ///
+    /// The driver with stage-histogram recording enabled
+    pub fn with_stage_histograms(mut self) -> Self {
+        self.stage_hists = Some(Vec::new());
+        self
+    }
+
+    /// Enables recording of ln(g) at every flat event.
+    ///
+    /// At each flat event the ln(g) curve is stored together with the ln_f
+    /// that was used during the finished stage, in exactly the form
+    /// [`analysis::weighted_merge`] takes. Like
+    /// [`with_stage_histograms`](Self::with_stage_histograms) this keeps one
+    /// curve per stage in memory, so it is off by default.
+    ///
+    /// # Returns
+    ///
+    /// The driver with stage ln(g) recording enabled
+    pub fn with_stage_ln_g(mut self) -> Self {
+        self.stage_ln_g = Some(Vec::new());
+        self
+    }
+
+    /// Enables recording of every bin's ln(g) at every flat event.
+    ///
+    /// Each flat event appends one row holding ln(g) of all bins, after the
+    /// end-of-stage normalization, so [`ln_g_history`](Self::ln_g_history)
+    /// is a `[stage][bin]` matrix. Following one bin down the rows shows how
+    /// its estimate settled and points out the bins that are slow to
+    /// converge. This is the per-bin complement to
+    /// [`with_stage_histograms`](Self::with_stage_histograms); it keeps one
+    /// value per bin and stage in memory, so it is off by default.
+    ///
+    /// # Returns
+    ///
+    /// The driver with ln(g) history recording enabled
+    pub fn with_ln_g_history(mut self) -> Self {
+        self.ln_g_history = Some(Vec::new());
+        self
+    }
+
+    /// Enables recording of the inter-bin acceptance rate of every stage.
+    ///
+    /// Proposals that stay in their bin see no difference in ln(g) and carry
+    /// no information about the bias, so only proposals into a different bin
+    /// are counted. The counters are reset at each flat event, after the
+    /// rate of the finished stage has been stored. As ln(g) converges the
+    /// rate settles; one that keeps drifting from stage to stage signals a
+    /// run that is not converging.
+    ///
+    /// # Returns
+    ///
+    /// The driver with per-stage acceptance recording enabled
+    pub fn with_stage_acceptance(mut self) -> Self {
+        self.stage_accept = Some(StageAcceptance {
+            proposed: 0,
+            accepted: 0,
+            rates: Vec::new(),
+        });
+        self
+    }
+
+    /// Enables tracking of the recent visit rate of every bin.
+    ///
+    /// Each bin keeps an exponential moving average of its visits per step
+    /// with decay `1 - 1/window`, so it mostly reflects the last `window`
+    /// steps, without storing any history. Unlike the cumulative
+    /// [`total_histogram`](Self::total_histogram), a rate near zero flags a
+    /// bin the walker has stopped reaching, e.g. behind a newly formed
+    /// barrier. Read it with [`recent_visit_rate`](Self::recent_visit_rate).
+    ///
+    /// # Parameters
+    ///
+    /// * `window` - The effective averaging window in steps
+    ///
+    /// # Returns
+    ///
+    /// The driver with visit-rate tracking enabled
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    pub fn with_visit_rate_window(mut self, window: u64) -> Self {
+        assert!(window > 0, "visit-rate window must be positive");
+        let n_bins = self.hist.len();
+        self.visit_rate = Some(VisitRate {
+            decay: 1.0 - 1.0 / window as f64,
+            rates: vec![0.0; n_bins],
+            last: vec![self.step; n_bins],
+        });
+        self
+    }
+
+    /// Enables an audit log of every acceptance decision.
+    ///
+    /// Each proposal, including those of the production and equilibration
+    /// phases, appends an [`AuditEntry`] with the log acceptance ratio and
+    /// the random draw it was compared with, so the decisions can be replayed
+    /// exactly when validating a move against a reference implementation.
+    /// The log grows by one entry per proposal, so enable it only for short
+    /// runs. Read it with [`audit_log`](Self::audit_log).
+    ///
+    /// # Returns
+    ///
+    /// The driver with the audit log enabled
+    pub fn with_audit_log(mut self) -> Self {
+        self.audit = Some(Vec::new());
+        self
+    }
+
+    /// Enables adaptive freezing of bins that converge early.
+    ///
+    /// In wide-range runs some bins, often the low-energy ones, settle long
+    /// before the rest. At every flat event each bin's ln(g) change over the
+    /// finished stage is compared with the mean change over the unfrozen
+    /// bins; once it has stayed below `threshold` for `stages` consecutive
+    /// stages the bin is frozen. A frozen bin keeps its ln(g): visits to it
+    /// still count in the histogram and take part in acceptance, but add
+    /// nothing to any bin. It is also left out of the flatness check, so
+    /// stages end as soon as the unconverged region is flat. Unlike
+    /// [`pin_bin`](Self::pin_bin), the other bins are not lowered to make up
+    /// for the missing update. When
+    /// every bin is frozen each step is a flat event and the schedule runs
+    /// out quickly.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The largest per-stage ln(g) change that counts as
+    ///   converged
+    /// * `stages` - The number of consecutive converged stages before a bin
+    ///   is frozen
+    ///
+    /// # Returns
+    ///
+    /// The driver with local freezing enabled
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stages` is zero.
+    pub fn with_local_freezing(mut self, threshold: f64, stages: usize) -> Self {
+        assert!(stages > 0, "freezing needs at least one stage");
+        let n_bins = self.hist.len();
+        self.freeze = Some(FreezeState {
+            threshold,
+            stages,
+            prev_ln_g: self.ln_g_f64(),
+            calm: vec![0; n_bins],
+            frozen: vec![false; n_bins],
+        });
+        self
+    }
+
+    /// Returns the bins frozen by local freezing.
+    ///
+    /// Freezing is opt-in via
+    /// [`with_local_freezing`](Self::with_local_freezing); without it this is
+    /// empty.
+    ///
+    /// # Returns
+    ///
+    /// The indices of the frozen bins in ascending order
+    pub fn frozen_bins(&self) -> Vec<usize> {
+        (0..self.hist.len())
+            .filter(|&bin| self.is_frozen(bin))
+            .collect()
+    }
+
+    /// Enables the total-variation convergence monitor.
+    ///
+    /// At every flat event the ln(g) of the finished stage is compared with
+    /// that of the previous stage (or with ln(g) at the time this method was
+    /// called, for the first stage) using [`analysis::tv_distance`], and the
+    /// distance is appended to [`tv_history`](Self::tv_history). Once the
+    /// distance falls below `threshold` the step reports convergence, even if
+    /// the schedule has not yet reached its tolerance. Pass `0.0` to only
+    /// record the history.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The distance below which the run counts as converged
+    ///
+    /// # Returns
+    ///
+    /// The driver with the monitor enabled
+    pub fn with_tv_monitor(mut self, threshold: f64) -> Self {
+        self.tv = Some(TvMonitor {
+            threshold,
+            prev_ln_g: self.ln_g_f64(),
+            history: Vec::new(),
+        });
+        self
+    }
+
+    /// Enables recording of the bin-index series during production.
+    ///
+    /// After every frozen-ln(g) step, from [`produce`](Self::produce) or the
+    /// [`Params::production_tail`] of [`run`](Self::run), the walker's bin is
+    /// appended to [`bin_series`](Self::bin_series). Its
+    /// [`integrated_autocorr_time`](Self::integrated_autocorr_time) tells how
+    /// many production steps separate independent samples.
+    ///
+    /// # Returns
+    ///
+    /// The driver with bin-series recording enabled
+    pub fn with_bin_series(mut self) -> Self {
+        self.bin_series = Some(Vec::new());
+        self
+    }
+
+    /// Registers a callback invoked when a bin is visited for the first time.
+    ///
+    /// Inside [`step`](Self::step) the callback receives the flat bin index
+    /// and the current step count exactly once per bin, on its first counted
+    /// visit over the whole run. This is cheaper than polling coverage after
+    /// every step, e.g. to log progress or to trigger a window expansion. The
+    /// same information is kept in [`first_visit_steps`](Self::first_visit_steps).
+    /// See [callbacks](WLDriver#callbacks) for the `Send` bound.
+    ///
+    /// # Parameters
+    ///
+    /// * `callback` - Called with `(bin, step)` on each first visit
+    ///
+    /// # Returns
+    ///
+    /// The driver with the callback installed
+    pub fn on_first_visit(mut self, callback: impl FnMut(usize, u64) + Send + 'static) -> Self {
+        self.on_first_visit = Some(Box::new(callback));
+        self
+    }
+
+    /// Registers a callback invoked at every flat event.
+    ///
+    /// Inside [`step`](Self::step) the callback receives a [`FlatEvent`]
+    /// exactly once per flat event, after the schedule has updated ln_f, so
+    /// `ln_f` is the modification factor of the stage about to start. This
+    /// is the general extension point for acting on stage boundaries, e.g.
+    /// writing a checkpoint, logging progress or adapting move parameters
+    /// kept behind shared state. See [callbacks](WLDriver#callbacks) for the
+    /// `Send` bound.
+    ///
+    /// # Parameters
+    ///
+    /// * `callback` - Called with the stage, ln_f and step of each flat event
+    ///
+    /// # Returns
+    ///
+    /// The driver with the callback installed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use wanglandau::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Coin(bool);
+    /// # impl State for Coin {}
+    /// # struct Flip;
+    /// # impl<R: rand::RngCore> Move<Coin, R> for Flip {
+    /// #     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+    /// #         use rand::Rng;
+    /// #         s.0 = rng.random();
+    /// #     }
+    /// # }
+    /// # struct Mapper;
+    /// # impl Macrospace<Coin> for Mapper {
+    /// #     type Bin = usize;
+    /// #     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+    /// #     fn bins(&self) -> &[usize] { &[0, 1] }
+    /// # }
+    /// let mut driver = WLDriver::new(
+    ///     Coin(false), Flip, Mapper, Params::default(),
+    ///     Geometric { alpha: 0.5, tol: 1e-8 }, Fraction, wanglandau::rng::seeded(1),
+    /// )
+    /// .on_flat(|event| {
+    ///     println!("stage {} done at step {}, ln_f = {}", event.stage, event.step, event.ln_f)
+    /// });
+    /// driver.run(1_000_000);
+    /// ```
+    pub fn on_flat(mut self, callback: impl FnMut(FlatEvent) + Send + 'static) -> Self {
+        self.on_flat = Some((Box::new(callback), 0));
+        self
+    }
+
+    /// Splits every ln_f update between the visited bin and its neighbor.
+    ///
+    /// With hard assignment each visit raises the ln(g) of a single bin, so
+    /// the estimate jumps at bin edges even where the density of states is
+    /// smooth. Kernel deposition uses [`Macrospace::position_in_bin`] to
+    /// spread the update with a linear kernel one bin wide: a walker at
+    /// distance `d` from the center of its bin, in units of the bin width,
+    /// adds `(1 - d) · ln_f` to its bin and `d · ln_f` to the neighbor on
+    /// that side, so the update is split evenly right at an edge. For
+    /// consistency the acceptance sees ln(g) and the external bias
+    /// interpolated with the same kernel at the walker's position, so moves
+    /// within a bin are accepted or rejected as well. The histogram still
+    /// counts each visit in the visited bin only.
+    ///
+    /// The smoothing pays off in the early stages, where single updates are
+    /// large and hard assignment leaves ln(g) ragged. Once ln_f is small the
+    /// kernel also damps the restoring force on bin-to-bin oscillations, and
+    /// the residual roughness can end up above that of hard assignment.
+    /// Kernel deposition applies to one-dimensional macrospaces whose mapper
+    /// reports a position; of the built-in mappers these are
+    /// [`EnergyBins`](crate::macrospace::EnergyBins),
+    /// [`EdgeBins`](crate::macrospace::EdgeBins) and
+    /// [`LinearCombination`](crate::macrospace::LinearCombination). When the
+    /// mapper reports no position, at the ends of the range, and next to
+    /// pinned, inactive or forbidden bins, the whole update goes to the
+    /// visited bin.
+    ///
+    /// # Returns
+    ///
+    /// The driver with kernel deposition enabled
+    pub fn with_kernel_deposition(mut self) -> Self {
+        self.kernel_deposition = true;
+        self
+    }
+
+    /// Installs a schedule for the tempering prefactor of the acceptance.
+    ///
+    /// The acceptance normally sees the full difference
+    /// `ln g(old) - ln g(new)` (plus the external bias). With a schedule, the
+    /// difference is multiplied by `beta_schedule(step)`, evaluated at the
+    /// start of every [`step`](Self::step), before the proposal's log ratio
+    /// is added. A small prefactor flattens the bias and lets the walker
+    /// explore freely early on, and raising it towards one hands control
+    /// back to the Wang-Landau bias, coupling simulated tempering with the
+    /// run. A constant schedule gives fixed-prefactor tempered sampling.
+    /// Frozen-ln(g) phases keep the last value. See
+    /// [callbacks](WLDriver#callbacks) for the `Send` bound.
+    ///
+    /// # Parameters
+    ///
+    /// * `beta_schedule` - Returns the prefactor for a step count
+    ///
+    /// # Returns
+    ///
+    /// The driver with the schedule installed
+    pub fn with_beta_schedule(
+        mut self,
+        beta_schedule: impl FnMut(u64) -> f64 + Send + 'static,
+    ) -> Self {
+        self.beta_schedule = Some(Box::new(beta_schedule));
+        self
+    }
+
+    /// Installs a stage-dependent flatness threshold.
+    ///
+    /// The scalar `params.flatness` is replaced by `threshold(stage)`, where
+    /// `stage` counts the flat events since installation, starting at zero.
+    /// A fixed target can be unreachable for hard systems at late stages;
+    /// since a small ln_f needs less precision per stage, a schedule such as
+    /// `base + (1 - base) * stage / max_stages` with a decreasing threshold
+    /// starts strict and loosens as the run goes on. Per-bin thresholds from
+    /// [`set_bin_flatness`](Self::set_bin_flatness) take precedence. See
+    /// [callbacks](WLDriver#callbacks) for the `Send` bound.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - Returns the flatness threshold for a stage index
+    ///
+    /// # Returns
+    ///
+    /// The driver with the schedule installed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use wanglandau::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Coin(bool);
+    /// # impl State for Coin {}
+    /// # struct Flip;
+    /// # impl<R: rand::RngCore> Move<Coin, R> for Flip {
+    /// #     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+    /// #         use rand::Rng;
+    /// #         s.0 = rng.random();
+    /// #     }
+    /// # }
+    /// # struct Mapper;
+    /// # impl Macrospace<Coin> for Mapper {
+    /// #     type Bin = usize;
+    /// #     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+    /// #     fn bins(&self) -> &[usize] { &[0, 1] }
+    /// # }
+    /// // Loosen from 0.95 to 0.7 over the first 20 stages
+    /// let mut driver = WLDriver::new(
+    ///     Coin(false), Flip, Mapper, Params::default(),
+    ///     Geometric { alpha: 0.5, tol: 1e-8 }, Fraction, wanglandau::rng::seeded(1),
+    /// )
+    /// .with_flatness_schedule(|stage| 0.95 - 0.25 * (stage.min(20) as f64 / 20.0));
+    /// driver.run(1_000_000);
+    /// ```
+    pub fn with_flatness_schedule(
+        mut self,
+        threshold: impl Fn(u32) -> f64 + Send + 'static,
+    ) -> Self {
+        self.flatness_schedule = Some(FlatnessSchedule {
+            threshold: Box::new(threshold),
+            stage: 0,
+        });
+        self
+    }
+
+    /// Returns the flatness threshold of the current stage.
+    ///
+    /// # Returns
+    ///
+    /// The value of the schedule installed with
+    /// [`with_flatness_schedule`](Self::with_flatness_schedule) for the
+    /// current stage, or `params.flatness` without one
+    pub fn effective_flatness(&self) -> f64 {
+        match &self.flatness_schedule {
+            Some(schedule) => (schedule.threshold)(schedule.stage),
+            None => self.params.flatness,
+        }
+    }
+
+    /// Starts recording the walker's trajectory.
+    ///
+    /// From now on a clone of the state is stored after every `every`-th
+    /// Wang-Landau step and exposed through [`trajectory`](Self::trajectory).
+    /// Downsampling bounds the memory used, which grows by one state per
+    /// `every` steps. Calling this again restarts the recording with the new
+    /// interval and discards the states recorded so far.
+    ///
+    /// # Parameters
+    ///
+    /// * `every` - The number of steps between recorded states
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    pub fn record_trajectory(&mut self, every: usize) {
+        assert!(every > 0, "trajectory interval must be positive");
+        self.trajectory = Some(Trajectory {
+            every,
+            since: 0,
+            states: Vec::new(),
+        });
+    }
+
+    /// Performs one Wang-Landau step, consisting of multiple move proposals and histogram updates.
+    ///
+    /// A single step consists of:
+    /// 1. Proposing `sweep_len` moves and applying Wang-Landau acceptance
+    /// 2. Checking histogram flatness
+    /// 3. Updating the modification factor if the histogram is flat
+    ///
+    /// # Returns
+    ///
+    /// `true` if the algorithm has converged (ln_f below tolerance), `false` otherwise
+    pub fn step(&mut self) -> bool {
+        self.step_detailed().converged
+    }
+
+    /// Performs one Wang-Landau step and reports what happened in it.
+    ///
+    /// This is [`step`](Self::step) returning a [`StepOutcome`] instead of
+    /// only the convergence flag, so an external loop learns about flat
+    /// events and the ln_f they produced without polling accessors.
+    ///
+    /// # Returns
+    ///
+    /// Whether the step converged or triggered a flat event, and the ln_f
+    /// after the step
+    pub fn step_detailed(&mut self) -> StepOutcome {
+        if let Some(schedule) = self.beta_schedule.as_mut() {
+            self.tempering_beta = schedule(self.step);
+        }
+        for _ in 0..self.params.sweep_len {
+            let bin_final = self.propose_and_accept(true);
+
+            // --- WL bookkeeping ----------------------------------
+            if self.ln_g_frozen || self.is_frozen(bin_final) {
+                // The histogram still fills below
+            } else if self.pinned[bin_final] {
+                self.lower_unpinned();
+            } else if let Some((neighbor, share)) = self.kernel_neighbor(&self.state, bin_final) {
+                self.ln_g[bin_final] += Flt::from_f64((1.0 - share) * self.ln_f);
+                self.ln_g[neighbor] += Flt::from_f64(share * self.ln_f);
+            } else {
+                self.ln_g[bin_final] += Flt::from_f64(self.ln_f);
+            }
+            self.hist[bin_final] += 1;
+
+            self.total_hist[bin_final] += 1;
+            if self.total_hist[bin_final] == 1 {
+                self.first_visit[bin_final] = Some(self.step);
+                if let Some(callback) = self.on_first_visit.as_mut() {
+                    callback(bin_final, self.step);
+                }
+            }
+            if let Some(rate) = self.visit_rate.as_mut() {
+                rate.visit(bin_final, self.step);
+            }
+        }
+
+        if let Some(traj) = self.trajectory.as_mut() {
+            traj.since += 1;
+            if traj.since == traj.every {
+                traj.since = 0;
+                traj.states.push(self.state.clone());
+            }
+        }
+
+        let flat_event = self.is_flat();
+        let converged = flat_event && !self.ln_g_frozen && self.flat_event();
+        if self.params.check_finite {
+            if let Err(err) = self.check_ln_g_finite() {
+                panic!("{}", err);
+            }
+        }
+        if !converged {
+            self.step += 1;
+        }
+        StepOutcome {
+            converged,
+            flat_event,
+            ln_f: self.ln_f,
+        }
+    }
+
+    /// Proposes one move and applies the Wang-Landau acceptance rule.
+    ///
+    /// Rejected proposals restore the previous state. ln(g) and the
+    /// histogram are left untouched.
+    ///
+    /// # Parameters
+    ///
+    /// * `in_stage` - Whether the proposal belongs to a Wang-Landau stage and
+    ///   counts towards the stage's acceptance rate; `false` for
+    ///   equilibration and production
+    ///
+    /// # Returns
+    ///
+    /// The bin the walker occupies after the proposal
+    fn propose_and_accept(&mut self, in_stage: bool) -> usize {
+        // --- propose move & evaluate bins --------------------
+        let bin_old = self.bin_of(&self.state);
+        let prev_state = self.state.clone();
+
+        let ln_ratio = self.moves.propose_biased(&mut self.state, &mut self.rng);
+        let bin_new = self.bin_of(&self.state);
+
+        // --- WL acceptance -----------------------------------
+        // A masked-out bin or one pinned at ln g = -inf is a forbidden
+        // macrostate: never enter it, always leave it.
+        let neg_inf = Flt::from_f64(f64::NEG_INFINITY);
+        let mut decision = None;
+        let within_bin = bin_new == bin_old && !self.kernel_deposition;
+        let accept = if within_bin && ln_ratio == 0.0 {
+            true
+        } else if within_bin {
+            // ln(g) cancels, but an asymmetric proposal still needs its ratio
+            let draw = self.rng.random::<f64>();
+            decision = Some((ln_ratio, draw));
+            draw < self.acceptance.probability(ln_ratio)
+        } else if self.ln_g[bin_new] == neg_inf || !self.is_active(bin_new) {
+            false
+        } else if self.ln_g[bin_old] == neg_inf || !self.is_active(bin_old) {
+            true
+        } else {
+            let bias_delta = if self.kernel_deposition {
+                self.kernel_ln_g(&prev_state, bin_old) - self.kernel_ln_g(&self.state, bin_new)
+            } else {
+                ((self.ln_g[bin_old] + self.bias[bin_old])
+                    - (self.ln_g[bin_new] + self.bias[bin_new]))
+                    .to_f64()
+            };
+            let delta = self.tempering_beta * bias_delta + ln_ratio;
+            let draw = self.rng.random::<f64>();
+            decision = Some((delta, draw));
+            // NaN compares false, so it is rejected
+            draw < self.acceptance.probability(delta)
+        };
+        if let Some(counts) = self.stage_accept.as_mut().filter(|_| in_stage) {
+            if bin_new != bin_old {
+                counts.proposed += 1;
+                counts.accepted += u64::from(accept);
+            }
+        }
+        if let Some(audit) = self.audit.as_mut() {
+            audit.push(AuditEntry {
+                step: self.step,
+                bin_old,
+                bin_new,
+                delta: decision.map(|(delta, _)| delta),
+                draw: decision.map(|(_, draw)| draw),
+                accepted: accept,
+            });
+        }
+        if accept {
+            bin_new
+        } else {
+            self.state = prev_state;
+            bin_old
+        }
+    }
+
+    /// Returns whether a bin is allowed by the active mask.
+    #[inline]
+    fn is_active(&self, bin: usize) -> bool {
+        self.active.as_ref().is_none_or(|active| active[bin])
+    }
+
+    /// Returns whether a bin has been frozen by local freezing.
+    #[inline]
+    fn is_frozen(&self, bin: usize) -> bool {
+        self.freeze
+            .as_ref()
+            .is_some_and(|freeze| freeze.frozen[bin])
+    }
+
+    /// Checks the histogram of the active, unfrozen bins for flatness.
+    ///
+    /// Once every bin is excluded there is nothing left to flatten, which
+    /// counts as flat.
+    fn is_flat(&mut self) -> bool {
+        if self.active.is_none() && self.freeze.is_none() {
+            return match &self.bin_flatness {
+                Some(flat) => self.flat.is_flat_per_bin(&self.hist, flat),
+                None => self.flat.is_flat(&self.hist, self.effective_flatness()),
+            };
+        }
+        let mut active_hist = std::mem::take(&mut self.active_hist);
+        let mut active_bins = std::mem::take(&mut self.active_bins);
+        active_bins.clear();
+        active_bins.extend(
+            (0..self.hist.len()).filter(|&bin| self.is_active(bin) && !self.is_frozen(bin)),
+        );
+        active_hist.clear();
+        active_hist.extend(active_bins.iter().map(|&bin| self.hist[bin]));
+        let flat = active_hist.is_empty()
+            || match &self.bin_flatness {
+                Some(flat) => {
+                    let flat: Vec<f64> = active_bins.iter().map(|&bin| flat[bin]).collect();
+                    self.flat
+                        .is_flat_per_bin_subset(&active_hist, &active_bins, &flat)
+                }
+                None => {
+                    self.flat
+                        .is_flat_subset(&active_hist, &active_bins, self.effective_flatness())
+                }
+            };
+        self.active_bins = active_bins;
+        self.active_hist = active_hist;
+        flat
+    }
+
+    /// Freezes the bins whose ln(g) has stopped changing.
+    ///
+    /// Changes are measured relative to their mean over the unfrozen bins, so
+    /// uniform shifts of ln(g) do not count.
+    fn update_freeze(&mut self) {
+        let ln_g = self.ln_g_f64();
+        let Some(freeze) = self.freeze.as_mut() else {
+            return;
+        };
+        let candidates: Vec<usize> = (0..ln_g.len())
+            .filter(|&bin| !freeze.frozen[bin] && ln_g[bin].is_finite())
+            .filter(|&bin| self.active.as_ref().is_none_or(|active| active[bin]))
+            .collect();
+        if !candidates.is_empty() {
+            let change = |bin: usize| ln_g[bin] - freeze.prev_ln_g[bin];
+            let mean =
+                candidates.iter().map(|&bin| change(bin)).sum::<f64>() / candidates.len() as f64;
+            for &bin in &candidates {
+                if (change(bin) - mean).abs() < freeze.threshold {
+                    freeze.calm[bin] += 1;
+                } else {
+                    freeze.calm[bin] = 0;
+                }
+                if freeze.calm[bin] >= freeze.stages {
+                    freeze.frozen[bin] = true;
+                }
+            }
+        }
+        freeze.prev_ln_g = ln_g;
+    }
+
+    /// Performs one sweep of `sweep_len` proposals with ln(g) frozen.
+    fn frozen_sweep(&mut self) {
+        for _ in 0..self.params.sweep_len {
+            self.propose_and_accept(false);
+        }
+        if let Some(series) = self.bin_series.as_mut() {
+            series.push(self.mapper.locate(&self.state).to_flat(&self.shape));
+        }
+    }
+
+    /// Maps a state to its flat bin index.
+    #[inline]
+    fn bin_of(&self, state: &S) -> usize {
+        self.mapper.locate(state).to_flat(&self.shape)
+    }
+
+    /// Returns the neighbor sharing a kernel-deposited update, and its share.
+    ///
+    /// The share is the distance of the walker from the bin center, in units
+    /// of the bin width, going to the neighbor on that side. Neighbors that
+    /// are out of range, pinned, frozen, inactive or forbidden get nothing.
+    fn kernel_neighbor(&self, state: &S, bin: usize) -> Option<(usize, f64)> {
+        if !self.kernel_deposition || self.shape.len() != 1 {
+            return None;
+        }
+        let position = self.mapper.position_in_bin(state)?;
+        let neighbor = if position < 0.5 {
+            bin.checked_sub(1)?
+        } else {
+            bin + 1
+        };
+        let usable = neighbor < self.ln_g.len()
+            && !self.pinned[neighbor]
+            && !self.is_frozen(neighbor)
+            && self.is_active(neighbor)
+            && self.ln_g[neighbor].to_f64().is_finite();
+        usable.then(|| (neighbor, (position - 0.5).abs()))
+    }
+
+    /// Returns the ln(g) plus bias seen by a state, interpolated with the
+    /// deposition kernel when kernel deposition is enabled.
+    fn kernel_ln_g(&self, state: &S, bin: usize) -> f64 {
+        let own = (self.ln_g[bin] + self.bias[bin]).to_f64();
+        match self.kernel_neighbor(state, bin) {
+            Some((neighbor, share)) => {
+                let other = (self.ln_g[neighbor] + self.bias[neighbor]).to_f64();
+                own + share * (other - own)
+            }
+            None => own,
+        }
+    }
+
+    /// Lowers the ln(g) of every unpinned, unfrozen bin by `ln_f / k`, where
+    /// `k` is the number of pinned bins that can be visited.
+    ///
+    /// Only differences in ln(g) enter the acceptance, so this is equivalent
+    /// to raising the pinned bins together while keeping their values fixed.
+    /// Their relative values are exact, so the group's visits split as
+    /// intended between its members; a raise of `ln_f / k` per visit to the
+    /// group gives each of them the share of a single unpinned bin.
+    fn lower_unpinned(&mut self) {
+        let neg_inf = Flt::from_f64(f64::NEG_INFINITY);
+        let visitable = (0..self.ln_g.len())
+            .filter(|&bin| self.pinned[bin] && !self.is_frozen(bin))
+            .filter(|&bin| self.ln_g[bin] != neg_inf && self.is_active(bin))
+            .count();
+        let amount = Flt::from_f64(self.ln_f / visitable.max(1) as f64);
+        for bin in 0..self.ln_g.len() {
+            if !self.pinned[bin] && !self.is_frozen(bin) {
+                self.ln_g[bin] -= amount;
+            }
+        }
+    }
+
+    /// Handles a flat histogram: records, resets and updates the schedule.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the schedule reports convergence, `false` otherwise
+    fn flat_event(&mut self) -> bool {
+        if let Some(stages) = self.stage_hists.as_mut() {
+            stages.push(self.hist.clone());
+        }
+        if self.stage_ln_g.is_some() {
+            let stage = (self.ln_g_f64(), self.ln_f);
+            if let Some(stages) = self.stage_ln_g.as_mut() {
+                stages.push(stage);
+            }
+        }
+        if let Some(counts) = self.stage_accept.as_mut() {
+            let rate =
+                (counts.proposed > 0).then(|| counts.accepted as f64 / counts.proposed as f64);
+            counts.rates.push(rate);
+            counts.proposed = 0;
+            counts.accepted = 0;
+        }
+        if let Some(schedule) = self.flatness_schedule.as_mut() {
+            schedule.stage += 1;
+        }
+        self.hist.fill(0);
+
+        let mut tv_converged = false;
+        let mut kl_converged = false;
+        if self.tv.is_some() || self.params.kl_stop.is_some() {
+            let ln_g = self.ln_g_f64();
+            if let Some(tv) = self.tv.as_mut() {
+                let distance = analysis::tv_distance(&tv.prev_ln_g, &ln_g);
+                tv.history.push(distance);
+                tv.prev_ln_g.copy_from_slice(&ln_g);
+                tv_converged = distance < tv.threshold;
+            }
+            if let Some(threshold) = self.params.kl_stop {
+                kl_converged = analysis::kl_divergence(&ln_g, &self.kl_prev) < threshold;
+                self.kl_prev.copy_from_slice(&ln_g);
+            }
+        }
+
+        self.update_freeze();
+        self.normalize_ln_g();
+        if self.ln_g_history.is_some() {
+            let row = self.ln_g_f64();
+            if let Some(history) = self.ln_g_history.as_mut() {
+                history.push(row);
+            }
+        }
+
+        self.sched.on_flat(self.step);
+        let converged = self.sched.update(&mut self.ln_f) || tv_converged || kl_converged;
+        if let Some((callback, stage)) = self.on_flat.as_mut() {
+            *stage += 1;
+            callback(FlatEvent {
+                stage: *stage,
+                ln_f: self.ln_f,
+                step: self.step,
+            });
+        }
+        converged
+    }
+
+    /// Applies [`Params::clamp_ln_g_nonneg`], shifting ln(g) so its minimum is
+    /// not below zero.
+    fn normalize_ln_g(&mut self) {
+        if !self.params.clamp_ln_g_nonneg {
+            return;
+        }
+        // Forbidden bins at -inf stay there and do not set the shift
+        let zero = Flt::from_f64(0.0);
+        let neg_inf = Flt::from_f64(f64::NEG_INFINITY);
+        let min = self
+            .ln_g
+            .iter()
+            .copied()
+            .filter(|&g| g != neg_inf)
+            .fold(zero, |m, g| if g < m { g } else { m });
+        if min < zero {
+            for g in &mut self.ln_g {
+                *g -= min;
+            }
+        }
+    }
+
+    /// Runs the Wang-Landau simulation for up to `max_steps` steps or until convergence.
+    ///
+    /// The simulation will stop early if the modification factor falls below
+    /// the tolerance specified in the schedule. If [`Params::production_tail`]
+    /// is non-zero, convergence is followed by that many extra steps with
+    /// ln(g) and the histogram frozen, so a single call can equilibrate and
+    /// then keep sampling at the final bias. Tail steps count towards
+    /// [`step_count`](Self::step_count).
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The maximum number of Wang-Landau steps to perform
+    pub fn run(&mut self, max_steps: u64) {
+        for _ in 0..max_steps {
+            if self.step() {
+                for _ in 0..self.params.production_tail {
+                    self.frozen_sweep();
+                    self.step += 1;
+                }
+                break;
+            }
+        }
+        self.normalize_ln_g();
+    }
+
+    /// Runs the simulation until convergence or until `duration` has elapsed.
+    ///
+    /// This is [`run`](Self::run) with a wall-time budget instead of a step
+    /// budget. The clock is read only every 1000 steps, so the call can
+    /// overshoot `duration` by up to that many steps. As with `run`,
+    /// convergence is followed by the production tail, which is not subject to
+    /// the time limit.
+    ///
+    /// # Parameters
+    ///
+    /// * `duration` - The wall time to run for
+    ///
+    /// # Returns
+    ///
+    /// A [`RunReport`] with the steps taken, whether the run converged and how
+    /// well the bins were covered
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use wanglandau::prelude::*;
+    /// # #[derive(Clone)] struct S;
+    /// # impl State for S {}
+    /// # struct Stay;
+    /// # impl<R: rand::RngCore> Move<S, R> for Stay { fn propose(&mut self, _: &mut S, _: &mut R) {} }
+    /// # struct One;
+    /// # impl Macrospace<S> for One { type Bin = usize; fn locate(&self, _: &S) -> usize { 0 } fn bins(&self) -> &[usize] { &[0] } }
+    /// # let mut driver = WLDriver::new(S, Stay, One, Params::default(),
+    /// #     Geometric { alpha: 0.5, tol: 1e-8 }, Fraction, wanglandau::rng::seeded(1));
+    /// use std::time::Duration;
+    ///
+    /// let report = driver.run_for(Duration::from_secs(600));
+    /// if !report.converged {
+    ///     println!("stopped at ln_f = {} after {} steps", report.ln_f, report.steps);
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn run_for(&mut self, duration: Duration) -> RunReport {
+        let start = Instant::now();
+        let mut steps = 0;
+        let mut converged = false;
+        loop {
+            if steps % CLOCK_CHECK_INTERVAL == 0 && start.elapsed() >= duration {
+                break;
+            }
+            steps += 1;
+            if self.step() {
+                converged = true;
+                for _ in 0..self.params.production_tail {
+                    self.frozen_sweep();
+                    self.step += 1;
+                }
+                break;
+            }
+        }
+        self.normalize_ln_g();
+        let elapsed = start.elapsed();
+
+        let visits: Vec<u64> = (0..self.total_hist.len())
+            .filter(|&bin| self.is_active(bin))
+            .map(|bin| self.total_hist[bin])
+            .collect();
+        let (coverage, sampling_efficiency) = if visits.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let visited = visits.iter().filter(|&&h| h > 0).count();
+            let mean = visits.iter().sum::<u64>() as f64 / visits.len() as f64;
+            let min = *visits.iter().min().unwrap() as f64;
+            (
+                visited as f64 / visits.len() as f64,
+                if mean > 0.0 { min / mean } else { 0.0 },
+            )
+        };
+        RunReport {
+            steps,
+            elapsed,
+            converged,
+            ln_f: self.ln_f,
+            coverage,
+            sampling_efficiency,
+        }
+    }
+
+    /// Runs like [`run`](Self::run) and records ln(g) every `every` steps.
+    ///
+    /// Each snapshot is shifted so that its smallest finite entry is zero,
+    /// making successive snapshots directly comparable, e.g. as frames of a
+    /// convergence animation. A snapshot is taken after every `every` steps
+    /// of this call, counting the production tail that follows convergence
+    /// as part of the step that converged, and once more at the end unless
+    /// the last periodic snapshot already shows the final ln(g).
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The maximum number of Wang-Landau steps to perform
+    /// * `every` - The number of steps between snapshots
+    ///
+    /// # Returns
+    ///
+    /// The snapshots in order, indexed as `[snapshot][bin]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    pub fn run_recording_ln_g(&mut self, max_steps: u64, every: u64) -> Vec<Vec<f64>> {
+        assert!(every > 0, "snapshot interval must be positive");
+        let mut snapshots = Vec::new();
+        let mut current = false;
+        for steps in 1..=max_steps {
+            let converged = self.step();
+            if converged {
+                for _ in 0..self.params.production_tail {
+                    self.frozen_sweep();
+                    self.step += 1;
+                }
+            }
+            current = steps % every == 0;
+            if current {
+                snapshots.push(self.shifted_ln_g());
+            }
+            if converged {
+                break;
+            }
+        }
+        self.normalize_ln_g();
+        if !current {
+            snapshots.push(self.shifted_ln_g());
+        }
+        snapshots
+    }
+
+    /// Runs like [`run`](Self::run) and evaluates a metric at every flat event.
+    ///
+    /// `metric` is called right after each flat event has reduced ln_f, so
+    /// it sees the ln(g) that ended the stage, e.g.
+    /// [`ln_g_roughness`](Self::ln_g_roughness) or a distance to a reference
+    /// density of states. The resulting series is ready for plotting an error
+    /// against Monte Carlo time.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The maximum number of Wang-Landau steps to perform
+    /// * `metric` - Computes the recorded value from the driver
+    ///
+    /// # Returns
+    ///
+    /// One `(step, metric)` pair per flat event, in order
+    pub fn run_with_metric(
+        &mut self,
+        max_steps: u64,
+        metric: impl Fn(&Self) -> f64,
+    ) -> Vec<(u64, f64)> {
+        let mut series = Vec::new();
+        for _ in 0..max_steps {
+            let outcome = self.step_detailed();
+            if outcome.flat_event {
+                series.push((self.step, metric(self)));
+            }
+            if outcome.converged {
+                for _ in 0..self.params.production_tail {
+                    self.frozen_sweep();
+                    self.step += 1;
+                }
+                break;
+            }
+        }
+        self.normalize_ln_g();
+        series
+    }
+
+    /// Runs several refinement passes and averages their ln(g).
+    ///
+    /// Each pass is a [`run`](Self::run) of up to `steps_per_pass` steps.
+    /// After every pass but the last, ln_f is raised back to `bump_to`, the
+    /// histogram is cleared and the schedule is restarted via
+    /// [`Schedule::reset_progress`], so the next pass refines the current
+    /// ln(g) instead of starting from scratch. Averaging the passes reduces
+    /// the systematic error a single schedule leaves behind. A pass that does
+    /// not converge within its budget still contributes. The driver is left
+    /// with the ln(g) of the final pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `passes` - The number of passes to run
+    /// * `steps_per_pass` - The maximum number of Wang-Landau steps per pass
+    /// * `bump_to` - The ln_f each pass after the first starts from
+    ///
+    /// # Returns
+    ///
+    /// The average over passes of ln(g), each pass shifted so its smallest
+    /// finite entry is zero; forbidden bins stay at `-inf`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `passes` is zero.
+    pub fn run_refinement_passes(
+        &mut self,
+        passes: u32,
+        steps_per_pass: u64,
+        bump_to: f64,
+    ) -> Vec<f64> {
+        assert!(passes > 0, "need at least one refinement pass");
+        let mut sum = vec![0.0; self.ln_g.len()];
+        for pass in 0..passes {
+            self.run(steps_per_pass);
+            for (s, g) in sum.iter_mut().zip(self.shifted_ln_g()) {
+                *s += g;
+            }
+            if pass + 1 < passes {
+                self.ln_f = bump_to;
+                self.hist.fill(0);
+                self.sched.reset_progress(bump_to);
+            }
+        }
+        sum.iter().map(|s| s / passes as f64).collect()
+    }
+
+    /// Restarts the schedule's progress from the current ln_f.
+    ///
+    /// This forwards the current modification factor to
+    /// [`Schedule::reset_progress`], e.g. restarting the clock of
+    /// [`OneOverT`](crate::schedule::OneOverT) at `t = round(1 / ln_f)` so
+    /// its next update continues smoothly from here. ln_f itself is left
+    /// unchanged.
+    pub fn reset_schedule_progress(&mut self) {
+        self.sched.reset_progress(self.ln_f);
+    }
+
+    /// Runs until a bin has been visited `count` times in total.
+    ///
+    /// Rare-event sampling often needs a fixed number of hits on a target
+    /// bin rather than convergence. The count is taken from the cumulative
+    /// [`total_histogram`](Self::total_histogram), so flat-event resets do
+    /// not interfere, and is checked after every step. The run also stops at
+    /// convergence or after `max_steps` steps, whichever comes first.
+    ///
+    /// # Parameters
+    ///
+    /// * `bin` - The flat index of the target bin
+    /// * `count` - The number of cumulative visits to reach
+    /// * `max_steps` - The maximum number of Wang-Landau steps to perform
+    ///
+    /// # Returns
+    ///
+    /// `true` if the target bin reached `count` visits, `false` otherwise
+    pub fn run_until_bin_count(&mut self, bin: usize, count: u64, max_steps: u64) -> bool {
+        for _ in 0..max_steps {
+            if self.total_hist[bin] >= count || self.step() {
+                break;
+            }
+        }
+        self.normalize_ln_g();
+        self.total_hist[bin] >= count
+    }
+
+    /// Shifts ln(g) so that a reference bin takes its known value.
+    ///
+    /// Wang-Landau only determines ln(g) up to an additive constant. When
+    /// the absolute degeneracy of one bin is known, e.g. `g = 2` for the two
+    /// ground states of an Ising model, shifting every bin by the same amount
+    /// fixes that constant physically while preserving all differences.
+    /// Forbidden bins at -inf stay there. Unlike [`pin_bin`](Self::pin_bin)
+    /// this is a one-time shift, so call it after the run.
+    ///
+    /// # Parameters
+    ///
+    /// * `bin` - The flat index of the reference bin
+    /// * `known_ln_g` - The exact ln(g) of the reference bin
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bin` is out of range or its ln(g) is not finite.
+    pub fn normalize_to_reference(&mut self, bin: usize, known_ln_g: f64) {
+        let current = self.ln_g[bin].to_f64();
+        assert!(
+            current.is_finite(),
+            "reference bin {} has ln(g) = {}",
+            bin,
+            current
+        );
+        let shift = Flt::from_f64(known_ln_g - current);
+        for g in &mut self.ln_g {
+            *g += shift;
+        }
+        self.ln_g[bin] = Flt::from_f64(known_ln_g);
+    }
+
+    /// Pins the ln(g) value of a bin.
+    ///
+    /// When the exact ln(g) of some bins is known analytically (e.g. the
+    /// ground-state degeneracy), pinning them anchors the whole estimate. A
+    /// pinned bin keeps `ln_g` constant, since the `+= ln_f` update skips it,
+    /// but it still takes part in acceptance and in the flatness check.
+    ///
+    /// Skipping the update alone would let the other bins drift upwards
+    /// relative to the pins until the walker never leaves them. Instead, a
+    /// visit to a pinned bin lowers all unpinned bins by `ln_f / k`, with `k`
+    /// the number of pinned bins that can be visited. This leaves the
+    /// acceptance as if the pinned bins had been raised together by that
+    /// amount, and since their relative values are held at the known ones,
+    /// each of them ends up with the visits of a single unpinned bin.
+    ///
+    /// Pinning a bin at `f64::NEG_INFINITY` marks it as a forbidden
+    /// macrostate with zero density: moves into it are always rejected and
+    /// moves out of it always accepted. Such a bin never collects visits, so
+    /// pair it with a flatness criterion that ignores it.
+    ///
+    /// # Parameters
+    ///
+    /// * `bin` - The bin to pin
+    /// * `ln_g` - The value to hold the bin's ln(g) at
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bin` is out of range.
+    pub fn pin_bin(&mut self, bin: usize, ln_g: f64) {
+        self.ln_g[bin] = Flt::from_f64(ln_g);
+        self.pinned[bin] = true;
+    }
+
+    /// Returns whether a bin has been pinned with [`pin_bin`](Self::pin_bin).
+    ///
+    /// Bins frozen by [`with_local_freezing`](Self::with_local_freezing) are
+    /// reported by [`frozen_bins`](Self::frozen_bins) instead.
+    ///
+    /// # Parameters
+    ///
+    /// * `bin` - The bin to query
+    ///
+    /// # Returns
+    ///
+    /// `true` if the bin's ln(g) is held fixed, `false` otherwise
+    pub fn is_pinned(&self, bin: usize) -> bool {
+        self.pinned[bin]
+    }
+
+    /// Relaxes the initial configuration before Wang-Landau sampling.
+    ///
+    /// Performs `steps` sweeps of `sweep_len` proposals using the current
+    /// ln(g) as a fixed bias, without touching ln(g), the histogram, the step
+    /// count or the per-stage diagnostics. Only the
+    /// [audit log](Self::with_audit_log) records these proposals, since it
+    /// covers every proposal so that a run can be replayed exactly. Called
+    /// before [`run`](Self::run), when ln(g) is still all zero, every bin
+    /// change is accepted and the walk is effectively at infinite
+    /// temperature, which moves an arbitrary starting configuration to a
+    /// typical one so the first stage starts with clean statistics.
+    ///
+    /// # Parameters
+    ///
+    /// * `steps` - The number of equilibration sweeps to perform
+    pub fn equilibrate(&mut self, steps: u64) {
+        for _ in 0..steps {
+            for _ in 0..self.params.sweep_len {
+                self.propose_and_accept(false);
+            }
+        }
+    }
+
+    /// Runs a production phase with ln(g) frozen.
+    ///
+    /// Each production step performs one sweep of `sweep_len` proposals using
+    /// the current ln(g) as a fixed multicanonical bias, then hands the state
+    /// to `collect`. Neither ln(g), the histogram, nor the step count is
+    /// modified, so this is typically called after [`run`](Self::run) has
+    /// converged.
+    ///
+    /// # Parameters
+    ///
+    /// * `steps` - The number of production steps to perform
+    /// * `collect` - A callback invoked with the state after every step
+    pub fn produce(&mut self, steps: u64, mut collect: impl FnMut(&S)) {
+        for _ in 0..steps {
+            self.frozen_sweep();
+            collect(&self.state);
+        }
+    }
+
+    /// Runs a production phase starting from a given state.
+    ///
+    /// This is [`produce`](Self::produce) after replacing the walker's state
+    /// with `start`, e.g. a representative configuration recorded with
+    /// [`record_trajectory`](Self::record_trajectory), rather than wherever
+    /// the Wang-Landau run happened to leave the walker. The walker stays in
+    /// the new state afterwards.
+    ///
+    /// # Parameters
+    ///
+    /// * `start` - The state to begin production from
+    /// * `steps` - The number of production steps to perform
+    /// * `collect` - A callback invoked with the state after every step
+    pub fn produce_from(&mut self, start: S, steps: u64, collect: impl FnMut(&S)) {
+        self.state = start;
+        self.produce(steps, collect);
+    }
+
+    /// Runs a production phase feeding observables into running accumulators.
+    ///
+    /// This is [`produce`](Self::produce) with each observable evaluated
+    /// after every step and pushed into its paired [`Accumulator`], which
+    /// keeps a numerically stable running mean and variance without storing
+    /// the series.
+    ///
+    /// # Parameters
+    ///
+    /// * `steps` - The number of production steps to perform
+    /// * `observables` - Pairs of observable functions and their accumulators
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use wanglandau::prelude::*;
+    /// # use wanglandau::analysis::Accumulator;
+    /// # #[derive(Clone)]
+    /// # struct Coin(bool);
+    /// # impl State for Coin {}
+    /// # struct Flip;
+    /// # impl<R: rand::RngCore> Move<Coin, R> for Flip {
+    /// #     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+    /// #         use rand::Rng;
+    /// #         s.0 = rng.random();
+    /// #     }
+    /// # }
+    /// # struct Mapper;
+    /// # impl Macrospace<Coin> for Mapper {
+    /// #     type Bin = usize;
+    /// #     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+    /// #     fn bins(&self) -> &[usize] { &[0, 1] }
+    /// # }
+    /// # let mut driver = WLDriver::new(
+    /// #     Coin(false), Flip, Mapper, Params::default(),
+    /// #     Geometric { alpha: 0.5, tol: 1e-8 }, Fraction, wanglandau::rng::seeded(1),
+    /// # );
+    /// driver.run(1_000_000);
+    ///
+    /// let heads: Box<dyn Fn(&Coin) -> f64> = Box::new(|s| s.0 as u8 as f64);
+    /// let mut observables = [(heads, Accumulator::new())];
+    /// driver.produce_with_accumulators(100_000, &mut observables);
+    ///
+    /// println!("fraction of heads: {}", observables[0].1.mean());
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn produce_with_accumulators(
+        &mut self,
+        steps: u64,
+        observables: &mut [(Box<dyn Fn(&S) -> f64>, Accumulator)],
+    ) {
+        self.produce(steps, |state| {
+            for (observable, acc) in observables.iter_mut() {
+                acc.push(observable(state));
+            }
+        });
+    }
+
+    /// Runs a production phase collecting the histogram of a discrete observable.
+    ///
+    /// This is [`produce`](Self::produce) with `f` evaluated after every step
+    /// and its value counted. With ln(g) frozen the walk samples the
+    /// multicanonical ensemble, so the result is the multicanonical
+    /// distribution of the observable, which can be reweighted to any other
+    /// ensemble.
+    ///
+    /// # Parameters
+    ///
+    /// * `steps` - The number of production steps to perform
+    /// * `f` - The observable, mapping a state to a value in `0..n_values`
+    /// * `n_values` - The number of distinct observable values
+    ///
+    /// # Returns
+    ///
+    /// The number of production steps that ended with each observable value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a value of `n_values` or more.
+    pub fn produce_observable_histogram(
+        &mut self,
+        steps: u64,
+        f: impl Fn(&S) -> usize,
+        n_values: usize,
+    ) -> Vec<u64> {
+        let mut counts = vec![0u64; n_values];
+        self.produce(steps, |state| {
+            let value = f(state);
+            assert!(
+                value < n_values,
+                "observable value {} is out of range for {} values",
+                value,
+                n_values
+            );
+            counts[value] += 1;
+        });
+        counts
+    }
+
+    /// Returns the current estimate of ln(density of states).
+    ///
+    /// # Returns
+    ///
+    /// A slice containing the ln(g) values for each bin
+    pub fn ln_g(&self) -> &[Flt] {
+        &self.ln_g
+    }
+
+    /// Copies ln(g) into an `f64` vector for analysis.
+    fn ln_g_f64(&self) -> Vec<f64> {
+        self.ln_g.iter().map(|g| g.to_f64()).collect()
+    }
+
+    /// Returns ln(g) as `f64` shifted so its smallest finite entry is zero.
+    fn shifted_ln_g(&self) -> Vec<f64> {
+        let mut ln_g = self.ln_g_f64();
+        let min = ln_g
+            .iter()
+            .copied()
+            .filter(|g| g.is_finite())
+            .fold(f64::INFINITY, f64::min);
+        if min.is_finite() {
+            for g in &mut ln_g {
+                *g -= min;
+            }
+        }
+        ln_g
+    }
+
+    /// Appends the current ln(g) to a trace as one snapshot line.
+    ///
+    /// Called periodically, e.g. after every flat event, this builds a
+    /// time-indexed record of how ln(g) converges without rewriting earlier
+    /// output. The line format is that of
+    /// [`write_snapshot`](crate::io::write_snapshot); parse a
+    /// trace back with [`read_snapshots`](crate::io::read_snapshots).
+    ///
+    /// # Parameters
+    ///
+    /// * `w` - The destination, typically a file opened in append mode
+    /// * `step_label` - The label written before the values, e.g. the step count
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or any error from the writer
+    pub fn append_ln_g_snapshot(&self, w: &mut impl Write, step_label: u64) -> io::Result<()> {
+        crate::io::write_snapshot(w, step_label, &self.ln_g_f64())
+    }
+
+    /// Returns the free-energy profile implied by the current ln(g).
+    ///
+    /// When the bins are values of a reaction coordinate, ln(g) is the
+    /// negative free energy up to a constant. This returns the potential of
+    /// mean force `F(bin) = -ln_g(bin) / beta`, shifted so that its minimum is
+    /// zero. Forbidden bins with ln(g) = -inf come out as `+inf`.
+    ///
+    /// # Parameters
+    ///
+    /// * `beta` - The inverse temperature `1 / kT`
+    ///
+    /// # Returns
+    ///
+    /// The free energy of each bin, in the energy units of `1 / beta`
+    pub fn free_energy_profile(&self, beta: f64) -> Vec<f64> {
+        let max = self
+            .ln_g
+            .iter()
+            .map(|g| g.to_f64())
+            .fold(f64::NEG_INFINITY, f64::max);
+        self.ln_g
+            .iter()
+            .map(|g| (max - g.to_f64()) / beta)
+            .collect()
+    }
+
+    /// Returns the microcanonical entropy implied by the current ln(g).
+    ///
+    /// ln(g) is the entropy `S(E) / k_B`, so this is `k_b * ln_g`, shifted so
+    /// that a chosen reference bin has a chosen entropy, e.g. `k_B ln 2` for
+    /// the two ground states of an Ising model. Without a reference no shift
+    /// is applied and the offset is whatever ln(g) carries, i.e. arbitrary
+    /// unless a bin of known degeneracy was fixed with
+    /// [`pin_bin`](Self::pin_bin).
+    ///
+    /// # Parameters
+    ///
+    /// * `k_b` - The Boltzmann constant in the desired units, `1.0` for `S / k_B`
+    /// * `reference` - An optional `(bin, entropy)` pair, the entropy in the
+    ///   units of `k_b`
+    ///
+    /// # Returns
+    ///
+    /// The entropy of each bin
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reference bin is out of range or forbidden (ln(g) = -inf).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wanglandau::prelude::*;
+    /// # #[derive(Clone)] struct S;
+    /// # impl State for S {}
+    /// # struct Stay;
+    /// # impl<R: rand::RngCore> Move<S, R> for Stay { fn propose(&mut self, _: &mut S, _: &mut R) {} }
+    /// # struct Two;
+    /// # impl Macrospace<S> for Two { type Bin = usize; fn locate(&self, _: &S) -> usize { 0 } fn bins(&self) -> &[usize] { &[0, 1] } }
+    /// # let mut driver = WLDriver::new(S, Stay, Two, Params::default(),
+    /// #     Geometric { alpha: 0.5, tol: 1e-8 }, Fraction, wanglandau::rng::seeded(1));
+    /// driver.pin_bin(1, 3.0);
+    ///
+    /// // Bin 0 holds a single microstate, so its entropy is zero
+    /// let s = driver.entropy(1.0, Some((0, 0.0)));
+    /// assert_eq!(s, [0.0, 3.0]);
+    /// ```
+    pub fn entropy(&self, k_b: f64, reference: Option<(usize, f64)>) -> Vec<f64> {
+        let shift = match reference {
+            Some((bin, entropy)) => {
+                let g = self.ln_g[bin].to_f64();
+                assert!(g.is_finite(), "reference bin {} is forbidden", bin);
+                entropy - k_b * g
+            }
+            None => 0.0,
+        };
+        self.ln_g.iter().map(|g| k_b * g.to_f64() + shift).collect()
+    }
+
+    /// Returns the entropy of each bin paired with the bin's energy.
+    ///
+    /// This is [`entropy`](Self::entropy) with the energy of every bin
+    /// attached, ready for plotting `S(E)` or differentiating it.
+    ///
+    /// # Parameters
+    ///
+    /// * `k_b` - The Boltzmann constant in the desired units
+    /// * `reference` - An optional `(bin, entropy)` pair, as for [`entropy`](Self::entropy)
+    /// * `bin_energy` - The energy of a bin, e.g. its centre, by flat index
+    ///
+    /// # Returns
+    ///
+    /// `(energy, entropy)` pairs in bin order
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reference bin is out of range or forbidden.
+    pub fn entropy_per_bin_with_energies(
+        &self,
+        k_b: f64,
+        reference: Option<(usize, f64)>,
+        bin_energy: impl Fn(usize) -> f64,
+    ) -> Vec<(f64, f64)> {
+        self.entropy(k_b, reference)
+            .into_iter()
+            .enumerate()
+            .map(|(bin, s)| (bin_energy(bin), s))
+            .collect()
+    }
+
+    /// Returns the log density-of-states ratio `ln(g(a) / g(b))` of two bins.
+    ///
+    /// Unlike ln(g) itself the difference does not depend on the arbitrary
+    /// offset of the estimate. For an error bar from independent runs use
+    /// [`analysis::ln_g_ratio_with_error`].
+    ///
+    /// # Parameters
+    ///
+    /// * `bin_a` - The flat index of the numerator bin
+    /// * `bin_b` - The flat index of the denominator bin
+    ///
+    /// # Returns
+    ///
+    /// `ln_g[bin_a] - ln_g[bin_b]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if either bin is out of range.
+    pub fn ln_g_ratio(&self, bin_a: usize, bin_b: usize) -> f64 {
+        self.ln_g[bin_a].to_f64() - self.ln_g[bin_b].to_f64()
+    }
+
+    /// Returns the roughness of the current ln(g) estimate.
+    ///
+    /// This is [`analysis::roughness`] applied to [`ln_g`](Self::ln_g): the
+    /// mean squared second difference over interior bins. It is a cheap
+    /// quality heuristic for ordered energy bins; a high value flags spikes
+    /// from under-sampling or binning artifacts.
+    ///
+    /// # Returns
+    ///
+    /// The mean squared second difference of ln(g)
+    pub fn ln_g_roughness(&self) -> f64 {
+        analysis::roughness(&self.ln_g_f64())
+    }
+
+    /// Returns the current histogram of visited states.
+    ///
+    /// # Returns
+    ///
+    /// A slice containing the visit counts for each bin
+    pub fn histogram(&self) -> &[u64] {
+        &self.hist
+    }
+
+    /// Returns the bins that are under-sampled in the current stage.
+    ///
+    /// A bin is flagged when its histogram count is below `threshold` times
+    /// the mean count, which with `threshold = params.flatness` is exactly
+    /// the set of bins holding back a [`Fraction`](crate::flatness::Fraction)
+    /// flatness check. Useful for spotting bottlenecks or steering moves
+    /// towards them. Bins excluded by [`set_active_mask`](Self::set_active_mask)
+    /// are never flagged and do not enter the mean.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The fraction of the mean count below which a bin is flagged
+    ///
+    /// # Returns
+    ///
+    /// The flat indices of the under-sampled bins, in increasing order
+    pub fn undersampled_bins(&self, threshold: f64) -> Vec<usize> {
+        let active: Vec<usize> = (0..self.hist.len())
+            .filter(|&bin| self.is_active(bin))
+            .collect();
+        if active.is_empty() {
+            return Vec::new();
+        }
+        let mean =
+            active.iter().map(|&bin| self.hist[bin]).sum::<u64>() as f64 / active.len() as f64;
+        active
+            .into_iter()
+            .filter(|&bin| (self.hist[bin] as f64) < threshold * mean)
+            .collect()
+    }
+
+    /// Returns whether the current stage's visits pile up in a single bin.
+    ///
+    /// A heuristic stall detector: the run is flagged as saturated when more
+    /// than `threshold` of the stage's visits fall in its most visited bin
+    /// while some other bin has not been visited at all, so the walker keeps
+    /// collecting counts that add no new information. Bins excluded by
+    /// [`set_active_mask`](Self::set_active_mask) do not count as unvisited.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The fraction of all visits in the top bin above which
+    ///   the stage counts as saturated
+    ///
+    /// # Returns
+    ///
+    /// `true` if the top bin exceeds `threshold` and coverage is incomplete;
+    /// `false` for an empty histogram
+    pub fn is_saturated(&self, threshold: f64) -> bool {
+        let total = self.hist.iter().sum::<u64>();
+        if total == 0 {
+            return false;
+        }
+        let top = self.hist.iter().copied().max().unwrap_or(0);
+        let incomplete = (0..self.hist.len()).any(|bin| self.is_active(bin) && self.hist[bin] == 0);
+        incomplete && top as f64 > threshold * total as f64
+    }
+
+    /// Returns the least- and most-visited bins of the whole run.
+    ///
+    /// A quick pointer to the sampling bottleneck taken from the cumulative
+    /// [`total_histogram`](Self::total_histogram). Ties go to the lowest bin
+    /// index. Bins excluded by [`set_active_mask`](Self::set_active_mask)
+    /// are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The flat index and cumulative count of the least-visited bin, then
+    /// those of the most-visited bin
+    ///
+    /// # Panics
+    ///
+    /// Panics if every bin is excluded by the active mask.
+    pub fn extremes(&self) -> ((usize, u64), (usize, u64)) {
+        let mut active = (0..self.total_hist.len())
+            .filter(|&bin| self.is_active(bin))
+            .map(|bin| (bin, self.total_hist[bin]));
+        let first = active.next().expect("every bin is inactive");
+        active.fold((first, first), |(least, most), (bin, count)| {
+            (
+                if count < least.1 { (bin, count) } else { least },
+                if count > most.1 { (bin, count) } else { most },
+            )
+        })
+    }
+
+    /// Reports whether the binning looks too fine or too coarse.
+    ///
+    /// A diagnostic over the cumulative histogram of the whole run. The
+    /// binning is called too fine when more than 10% of the active bins were
+    /// never visited, which typically means the bins are narrower than the
+    /// spacing of the attainable macrostates. Otherwise it is called too
+    /// coarse when ln(g) changes by more than 3 between two adjacent visited
+    /// bins, i.e. the density of states varies by a factor of about 20 across
+    /// a single bin. Bins excluded by [`set_active_mask`](Self::set_active_mask)
+    /// are ignored. The report is only meaningful after the walker has had
+    /// time to explore.
+    ///
+    /// # Returns
+    ///
+    /// A [`BinningReport`] with the empty fraction, the occupancy ratio and
+    /// the suggestion
+    pub fn binning_quality(&self) -> BinningReport {
+        let active: Vec<usize> = (0..self.total_hist.len())
+            .filter(|&bin| self.is_active(bin))
+            .collect();
+        let empty = active
+            .iter()
+            .filter(|&&bin| self.total_hist[bin] == 0)
+            .count();
+        let empty_fraction = if active.is_empty() {
+            0.0
+        } else {
+            empty as f64 / active.len() as f64
+        };
+
+        let visited = active
+            .iter()
+            .map(|&bin| self.total_hist[bin])
+            .filter(|&h| h > 0);
+        let occupancy_ratio = visited
+            .clone()
+            .max()
+            .zip(visited.min())
+            .map(|(max, min)| max as f64 / min as f64);
+
+        let ln_g = self.ln_g_f64();
+        let max_step = active
+            .windows(2)
+            .filter(|pair| pair[1] == pair[0] + 1)
+            .filter(|pair| self.total_hist[pair[0]] > 0 && self.total_hist[pair[1]] > 0)
+            .map(|pair| (ln_g[pair[1]] - ln_g[pair[0]]).abs())
+            .filter(|step| step.is_finite())
+            .fold(0.0, f64::max);
+
+        let suggestion = if empty_fraction > TOO_FINE_EMPTY_FRACTION {
+            BinningSuggestion::TooFine
+        } else if max_step > TOO_COARSE_LN_G_STEP {
+            BinningSuggestion::TooCoarse
+        } else {
+            BinningSuggestion::Ok
+        };
+        BinningReport {
+            empty_fraction,
+            occupancy_ratio,
+            suggestion,
+        }
+    }
+
+    /// Pre-loads the visit histogram of the current stage.
+    ///
+    /// Seeding bins with visits makes the flatness check treat them as
+    /// already partly sampled, which steers where the walker has to spend the
+    /// first stage; e.g. leaving a rare-event region at zero while filling
+    /// the rest forces early exploration toward it. Only the histogram is
+    /// touched: ln(g), and therefore the acceptance probabilities, are
+    /// unchanged, so this affects flatness timing but not the bias. The
+    /// histogram is reset as usual at the next flat event.
+    ///
+    /// # Parameters
+    ///
+    /// * `hist` - The visit counts to install, one per bin
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or [`WLError::LengthMismatch`] if `hist` does not
+    /// have one entry per bin
+    pub fn set_histogram(&mut self, hist: &[u64]) -> Result<(), WLError> {
+        if hist.len() != self.hist.len() {
+            return Err(WLError::LengthMismatch {
+                expected: self.hist.len(),
+                found: hist.len(),
+            });
+        }
+        self.hist.copy_from_slice(hist);
+        Ok(())
+    }
+
+    /// Exports ln(g) as a bias for another run.
+    ///
+    /// Together with [`import_bias`](Self::import_bias) this is a lightweight
+    /// alternative to a full checkpoint when only the converged density of
+    /// states is reused, e.g. as the starting bias of a run with a different
+    /// move set.
+    ///
+    /// # Returns
+    ///
+    /// A copy of ln(g) as `f64`
+    pub fn export_bias(&self) -> Vec<f64> {
+        self.ln_g_f64()
+    }
+
+    /// Imports ln(g) exported by another run and starts a fresh refinement.
+    ///
+    /// The values are copied into ln(g), ln_f is reset to `params.ln_f0`,
+    /// the stage histogram is cleared and the schedule's progress is
+    /// restarted from the new ln_f, so the next [`run`](Self::run) refines
+    /// the imported estimate instead of starting from zero. The state and
+    /// the cumulative diagnostics are left untouched.
+    ///
+    /// # Parameters
+    ///
+    /// * `ln_g` - The ln(g) value of each bin, e.g. from
+    ///   [`export_bias`](Self::export_bias)
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or [`WLError::LengthMismatch`] if `ln_g` does not
+    /// have one entry per bin
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use wanglandau::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Coin(bool);
+    /// # impl State for Coin {}
+    /// # struct Flip;
+    /// # impl<R: rand::RngCore> Move<Coin, R> for Flip {
+    /// #     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+    /// #         use rand::Rng;
+    /// #         s.0 = rng.random();
+    /// #     }
+    /// # }
+    /// # struct Mapper;
+    /// # impl Macrospace<Coin> for Mapper {
+    /// #     type Bin = usize;
+    /// #     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+    /// #     fn bins(&self) -> &[usize] { &[0, 1] }
+    /// # }
+    /// # let new_driver = || WLDriver::new(
+    /// #     Coin(false), Flip, Mapper, Params::default(),
+    /// #     Geometric { alpha: 0.5, tol: 1e-8 }, Fraction, wanglandau::rng::seeded(1),
+    /// # );
+    /// let mut pilot = new_driver();
+    /// pilot.run(1_000_000);
+    ///
+    /// let mut refined = new_driver();
+    /// refined.import_bias(&pilot.export_bias()).unwrap();
+    /// refined.run(1_000_000);
+    /// ```
+    pub fn import_bias(&mut self, ln_g: &[f64]) -> Result<(), WLError> {
+        if ln_g.len() != self.ln_g.len() {
+            return Err(WLError::LengthMismatch {
+                expected: self.ln_g.len(),
+                found: ln_g.len(),
+            });
+        }
+        for (g, &v) in self.ln_g.iter_mut().zip(ln_g) {
+            *g = Flt::from_f64(v);
+        }
+        self.ln_f = self.params.ln_f0;
+        self.hist.fill(0);
+        self.sched.reset_progress(self.ln_f);
+        Ok(())
+    }
+
+    /// Restricts sampling to an arbitrary set of active bins.
+    ///
+    /// Bins marked `false` are holes in the reachable range, such as known
+    /// forbidden macrostates: proposals into them are always rejected, a
+    /// walker that starts in one always leaves, and they are left out of the
+    /// histogram passed to the flatness criterion, so they never hold back a
+    /// flat event. Unlike a contiguous window the active set may have any
+    /// shape.
+    ///
+    /// # Parameters
+    ///
+    /// * `mask` - Whether each bin is active, one entry per bin
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or [`WLError::LengthMismatch`] if `mask` does not
+    /// have one entry per bin
+    pub fn set_active_mask(&mut self, mask: Vec<bool>) -> Result<(), WLError> {
+        if mask.len() != self.hist.len() {
+            return Err(WLError::LengthMismatch {
+                expected: self.hist.len(),
+                found: mask.len(),
+            });
+        }
+        let n_active = mask.iter().filter(|&&a| a).count();
+        self.active_hist = Vec::with_capacity(n_active);
+        self.active_bins = Vec::with_capacity(n_active);
+        self.active = Some(mask);
+        Ok(())
+    }
+
+    /// Sets a separate flatness threshold for every bin.
+    ///
+    /// The scalar `params.flatness` is replaced by `flatness[i]` for bin `i`
+    /// and the check is delegated to [`Flatness::is_flat_per_bin`], which by
+    /// default requires `hist[i] >= flatness[i] * mean(hist)`. A tight bound
+    /// on a critical energy window and a loose one elsewhere buys accuracy
+    /// where it matters without slowing every stage down. Inactive and frozen
+    /// bins are skipped together with their thresholds.
+    ///
+    /// # Parameters
+    ///
+    /// * `flatness` - The flatness threshold of each bin
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or [`WLError::LengthMismatch`] if `flatness` does
+    /// not have one entry per bin
+    pub fn set_bin_flatness(&mut self, flatness: Vec<f64>) -> Result<(), WLError> {
+        if flatness.len() != self.hist.len() {
+            return Err(WLError::LengthMismatch {
+                expected: self.hist.len(),
+                found: flatness.len(),
+            });
+        }
+        self.bin_flatness = Some(flatness);
+        Ok(())
+    }
+
+    /// Installs a static external bias potential used during acceptance.
+    ///
+    /// The acceptance probability becomes
+    /// `exp((ln_g_old + bias_old) - (ln_g_new + bias_new))`, while the
+    /// Wang-Landau `+= ln_f` updates only touch ln(g). This couples the walk
+    /// to prior knowledge or another biasing method. Since the histogram is
+    /// flattened with respect to the total bias, the estimate of the density
+    /// of states is `ln_g + bias` (see [`external_bias`](Self::external_bias));
+    /// a lower bias on a bin makes the walker favour it until ln(g)
+    /// compensates.
+    ///
+    /// # Parameters
+    ///
+    /// * `bias` - The bias of each bin
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or [`WLError::LengthMismatch`] if `bias` does not
+    /// have one entry per bin
+    pub fn set_external_bias(&mut self, bias: Vec<f64>) -> Result<(), WLError> {
+        if bias.len() != self.ln_g.len() {
+            return Err(WLError::LengthMismatch {
+                expected: self.ln_g.len(),
+                found: bias.len(),
+            });
+        }
+        self.bias = bias.into_iter().map(Flt::from_f64).collect();
+        Ok(())
+    }
+
+    /// Returns the external bias installed with [`set_external_bias`](Self::set_external_bias).
+    ///
+    /// # Returns
+    ///
+    /// The bias of each bin (all zero by default)
+    pub fn external_bias(&self) -> &[Flt] {
+        &self.bias
+    }
+
+    /// Pauses or resumes the Wang-Landau updates of ln(g).
+    ///
+    /// While frozen, [`step`](Self::step) keeps sampling with the current
+    /// ln(g) as a fixed bias and keeps filling the histogram, but skips the
+    /// `+= ln_f` update. Flatness is still evaluated and reported through
+    /// [`StepOutcome::flat_event`], yet a flat histogram triggers no flat
+    /// event: ln_f, the schedule and the histogram are left alone, so the
+    /// histogram sampled under the frozen bias can be inspected afterwards.
+    /// This lets a run check that the current ln(g) produces a flat
+    /// histogram before trusting it. Unlike the production tail, the visits
+    /// are counted in [`histogram`](Self::histogram) and
+    /// [`total_histogram`](Self::total_histogram).
+    ///
+    /// # Parameters
+    ///
+    /// * `frozen` - Whether ln(g) updates are paused
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wanglandau::prelude::*;
+    /// # #[derive(Clone)] struct S;
+    /// # impl State for S {}
+    /// # struct Stay;
+    /// # impl<R: rand::RngCore> Move<S, R> for Stay { fn propose(&mut self, _: &mut S, _: &mut R) {} }
+    /// # struct One;
+    /// # impl Macrospace<S> for One { type Bin = usize; fn locate(&self, _: &S) -> usize { 0 } fn bins(&self) -> &[usize] { &[0] } }
+    /// # let mut driver = WLDriver::new(S, Stay, One, Params::default(),
+    /// #     Geometric { alpha: 0.5, tol: 1e-8 }, Fraction, wanglandau::rng::seeded(1));
+    /// driver.freeze_ln_g(true);
+    /// let ln_g = driver.ln_g().to_vec();
+    /// let outcome = driver.step_detailed();
+    ///
+    /// assert_eq!(driver.ln_g(), ln_g);
+    /// assert!(outcome.flat_event && driver.histogram()[0] > 0);
+    /// ```
+    pub fn freeze_ln_g(&mut self, frozen: bool) {
+        self.ln_g_frozen = frozen;
+    }
+
+    /// Returns whether ln(g) updates are paused by [`freeze_ln_g`](Self::freeze_ln_g).
+    ///
+    /// # Returns
+    ///
+    /// `true` while ln(g) is frozen
+    pub fn is_ln_g_frozen(&self) -> bool {
+        self.ln_g_frozen
+    }
+
+    /// Returns the visits to each bin accumulated over the whole run.
+    ///
+    /// Unlike [`histogram`](Self::histogram) this is never reset at flat
+    /// events. Steps of the production tail and of
+    /// [`produce`](Self::produce) are not counted, whereas steps taken while
+    /// [`freeze_ln_g`](Self::freeze_ln_g) pauses the updates are.
+    ///
+    /// # Returns
+    ///
+    /// The cumulative visit count of each bin
+    pub fn total_histogram(&self) -> &[u64] {
+        &self.total_hist
+    }
+
+    /// Returns the recent visit rate of every bin.
+    ///
+    /// Tracking is opt-in via
+    /// [`with_visit_rate_window`](Self::with_visit_rate_window); without it
+    /// this is empty. Rates are in visits per step, so they sum to about
+    /// `sweep_len` once the window has filled.
+    ///
+    /// # Returns
+    ///
+    /// The moving-average visit rate of each bin as of the current step
+    pub fn recent_visit_rate(&self) -> Vec<f64> {
+        match &self.visit_rate {
+            Some(rate) => (0..self.hist.len())
+                .map(|bin| rate.rate(bin, self.step))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the driver's random number generator.
+    ///
+    /// Useful to query a wrapper such as [`Budgeted`](crate::rng::Budgeted)
+    /// for the number of draws a run consumed.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the generator
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    /// Returns a snapshot of the driver's internal state for debugging.
+    ///
+    /// Bundles what would otherwise take several accessor calls, e.g. to
+    /// print from inside a monitoring loop with `{:#?}`.
+    ///
+    /// # Returns
+    ///
+    /// A [`DebugState`] holding copies of ln(g) and the histogram together
+    /// with ln_f, the step count, the current bin, the acceptance rate and
+    /// the coverage
+    pub fn debug_state(&self) -> DebugState {
+        let acceptance_rate = self
+            .stage_accept
+            .as_ref()
+            .filter(|counts| counts.proposed > 0)
+            .map(|counts| counts.accepted as f64 / counts.proposed as f64);
+        DebugState {
+            ln_g: self.ln_g_f64(),
+            hist: self.hist.clone(),
+            ln_f: self.ln_f,
+            step: self.step,
+            bin: self.bin_of(&self.state),
+            acceptance_rate,
+            visited_bins: self.total_hist.iter().filter(|&&h| h > 0).count(),
+        }
+    }
+
+    /// Verifies that every ln(g) entry is finite.
+    ///
+    /// This is the check [`Params::check_finite`] runs after every step,
+    /// available for use at any other point. Pinned bins at `-inf` are
+    /// forbidden macrostates rather than errors and pass.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if ln(g) is finite, or [`WLError::NonFinite`] with the first
+    /// bin holding a NaN or infinite value
+    pub fn check_ln_g_finite(&self) -> Result<(), WLError> {
+        let forbidden = Flt::from_f64(f64::NEG_INFINITY);
+        let bad = (0..self.ln_g.len()).find(|&bin| {
+            let g = self.ln_g[bin];
+            let allowed = g.to_f64().is_finite() || (self.pinned[bin] && g == forbidden);
+            !allowed
+        });
+        match bad {
+            Some(bin) => Err(WLError::NonFinite { bin }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the driver's schedule.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the schedule, e.g. to read `Geometric::alpha` or save
+    /// `OneOverT::t` alongside a checkpoint
+    pub fn schedule(&self) -> &Sch {
+        &self.sched
+    }
+
+    /// Returns the driver's schedule for modification.
+    ///
+    /// Changes take effect at the next flat event, e.g. a smaller
+    /// `Geometric::alpha` makes every later reduction of ln_f steeper.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the schedule
+    pub fn schedule_mut(&mut self) -> &mut Sch {
+        &mut self.sched
+    }
+
+    /// Returns the driver's flatness criterion.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the flatness criterion, e.g. to read the explanation
+    /// recorded by [`Explained`](crate::flatness::Explained)
+    pub fn flatness(&self) -> &F {
+        &self.flat
+    }
+
+    /// Captures the dynamic state of the driver.
+    ///
+    /// See [`Checkpoint`] for what is included; restore it with
+    /// [`from_checkpoint`](Self::from_checkpoint). ln(g) and the external
+    /// bias are stored as `f64` whatever the driver's precision.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the state, ln(g), histograms, counters, parameters,
+    /// generator, schedule and flatness criterion
+    pub fn checkpoint(&self) -> Checkpoint<S, R, Sch, F>
+    where
+        R: Clone,
+        Sch: Clone,
+        F: Clone,
+    {
+        Checkpoint {
+            state: self.state.clone(),
+            ln_g: self.ln_g_f64(),
+            hist: self.hist.clone(),
+            total_hist: self.total_hist.clone(),
+            first_visit: self.first_visit.clone(),
+            pinned: self.pinned.clone(),
+            active: self.active.clone(),
+            bias: self.bias.iter().map(|b| b.to_f64()).collect(),
+            kl_prev: self.kl_prev.clone(),
+            freeze: self.freeze.clone(),
+            ln_f: self.ln_f,
+            step: self.step,
+            params: self.params,
+            seed: self.seed,
+            rng: self.rng.clone(),
+            sched: self.sched.clone(),
+            flat: self.flat.clone(),
+        }
+    }
+
+    /// Returns the tempering prefactor used by the most recent step.
+    ///
+    /// This is `1.0` unless a schedule was installed with
+    /// [`with_beta_schedule`](Self::with_beta_schedule).
+    ///
+    /// # Returns
+    ///
+    /// The prefactor applied to the ln(g) difference in the acceptance
+    pub fn tempering_beta(&self) -> f64 {
+        self.tempering_beta
+    }
+
+    /// Returns the recorded acceptance decisions.
+    ///
+    /// Recording is opt-in via [`with_audit_log`](Self::with_audit_log);
+    /// without it this is empty.
+    ///
+    /// # Returns
+    ///
+    /// One entry per proposal in the order they were made
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        self.audit.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the step at which each bin was first visited.
+    ///
+    /// # Returns
+    ///
+    /// For each bin, the step count of its first visit, or `None` if the bin
+    /// has not been visited yet
+    pub fn first_visit_steps(&self) -> &[Option<u64>] {
+        &self.first_visit
+    }
+
+    /// Returns the histograms recorded at each flat event.
+    ///
+    /// Recording is opt-in via [`with_stage_histograms`](Self::with_stage_histograms);
+    /// without it this slice is always empty.
+    ///
+    /// # Returns
+    ///
+    /// One histogram per flat event, in the order the stages completed
+    pub fn stage_histograms(&self) -> &[Vec<u64>] {
+        self.stage_hists.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the total-variation distances recorded at each flat event.
+    ///
+    /// Recording is opt-in via [`with_tv_monitor`](Self::with_tv_monitor);
+    /// without it this slice is always empty.
+    ///
+    /// # Returns
+    ///
+    /// One distance between consecutive-stage ln(g) curves per flat event
+    pub fn tv_history(&self) -> &[f64] {
+        self.tv.as_ref().map_or(&[], |tv| &tv.history)
+    }
+
+    /// Returns the bin-index series recorded during production.
+    ///
+    /// Recording is opt-in via [`with_bin_series`](Self::with_bin_series);
+    /// without it this slice is always empty.
+    ///
+    /// # Returns
+    ///
+    /// The walker's bin after each frozen-ln(g) step
+    pub fn bin_series(&self) -> &[usize] {
+        self.bin_series.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the normalized autocorrelation function of the bin-index series.
+    ///
+    /// See [`analysis::autocorrelation`]. Lags are evaluated up to the window
+    /// chosen by [`integrated_autocorr_time`](Self::integrated_autocorr_time).
+    ///
+    /// # Returns
+    ///
+    /// `ρ(0), ρ(1), ...` of the recorded bin indices
+    pub fn bin_autocorrelation(&self) -> Vec<f64> {
+        let series = self.bin_series_f64();
+        let (_, window) = analysis::autocorr_window(&series);
+        analysis::autocorrelation(&series, window)
+    }
+
+    /// Returns the integrated autocorrelation time of the bin-index series.
+    ///
+    /// See [`analysis::integrated_autocorr_time`].
+    ///
+    /// # Returns
+    ///
+    /// The number of production steps between effectively independent samples
+    pub fn integrated_autocorr_time(&self) -> f64 {
+        analysis::integrated_autocorr_time(&self.bin_series_f64())
+    }
+
+    /// Converts the recorded bin series to floating point for analysis.
+    fn bin_series_f64(&self) -> Vec<f64> {
+        self.bin_series().iter().map(|&b| b as f64).collect()
+    }
+
+    /// Returns the states recorded since [`record_trajectory`](Self::record_trajectory).
+    ///
+    /// # Returns
+    ///
+    /// One state per recording interval, oldest first; empty if recording
+    /// was never enabled
+    pub fn trajectory(&self) -> &[S] {
+        self.trajectory.as_ref().map_or(&[], |t| &t.states)
+    }
+
+    /// Returns the ln(g) curves recorded at each flat event.
+    ///
+    /// Recording is opt-in via [`with_stage_ln_g`](Self::with_stage_ln_g);
+    /// without it this slice is always empty.
+    ///
+    /// # Returns
+    ///
+    /// One `(ln_g, ln_f)` pair per flat event, in the order the stages completed
+    pub fn stage_ln_g(&self) -> &[(Vec<f64>, f64)] {
+        self.stage_ln_g.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the ln(g) of every bin recorded at each flat event.
+    ///
+    /// Recording is opt-in via [`with_ln_g_history`](Self::with_ln_g_history);
+    /// without it this slice is always empty.
+    ///
+    /// # Returns
+    ///
+    /// One row per flat event, in the order the stages completed, holding
+    /// ln(g) of every bin
+    pub fn ln_g_history(&self) -> &[Vec<f64>] {
+        self.ln_g_history.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the inter-bin acceptance rate of each finished stage.
+    ///
+    /// Recording is opt-in via
+    /// [`with_stage_acceptance`](Self::with_stage_acceptance); without it
+    /// this slice is always empty. A stage counts every Wang-Landau proposal
+    /// since the previous flat event, or since recording was enabled for the
+    /// first. Proposals made by [`equilibrate`](Self::equilibrate) or during
+    /// production are not counted.
+    ///
+    /// # Returns
+    ///
+    /// One rate per flat event, in the order the stages completed; `None`
+    /// for a stage without any proposal into a different bin
+    pub fn stage_acceptance(&self) -> &[Option<f64>] {
+        self.stage_accept
+            .as_ref()
+            .map_or(&[], |counts| &counts.rates)
+    }
+
+    /// Returns how many random draws the driver's generator has made.
+    ///
+    /// Counting is opt-in via [`with_draw_count`](Self::with_draw_count),
+    /// which needs a generator implementing [`CountingRng`]. Every
+    /// proposal's draws and every random acceptance decision are included.
+    ///
+    /// # Returns
+    ///
+    /// The generator's draw count, or `None` if counting is not enabled
+    pub fn rng_draws(&self) -> Option<u64> {
+        self.draw_count.map(|draws| draws(&self.rng))
+    }
+
+    /// Returns the seed the driver's RNG was created from.
+    ///
+    /// # Returns
+    ///
+    /// The seed passed to [`new_seeded`](WLDriver::new_seeded), or `None`
+    /// if the RNG was supplied by the caller
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns the current modification factor (ln f).
+    ///
+    /// # Returns
+    ///
+    /// The current ln_f value
+    pub fn ln_f(&self) -> f64 {
         self.ln_f
     }
 
+    /// Estimates how many more flat events are needed to converge.
+    ///
+    /// This asks the schedule via [`Schedule::remaining_updates`] with the
+    /// current ln_f; e.g. for [`Geometric`](crate::schedule::Geometric) it is
+    /// `ceil(ln(tol / ln_f) / ln(alpha))`. Useful for progress reporting,
+    /// since each stage usually takes longer than the previous one the count
+    /// says more about progress than about wall time.
+    ///
+    /// # Returns
+    ///
+    /// The number of remaining stages, or `None` if the schedule cannot tell
+    pub fn estimate_remaining_stages(&self) -> Option<u32> {
+        self.sched.remaining_updates(self.ln_f)
+    }
+
+    /// Returns the extent of each bin dimension reported by the mapper.
+    ///
+    /// ln(g) and the histogram are stored as flat row-major arrays over this
+    /// shape; use [`BinIndex::from_flat`] to recover structured bins.
+    ///
+    /// # Returns
+    ///
+    /// The number of bins along each dimension
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
     /// Returns the number of Wang-Landau steps performed so far.
     ///
     /// # Returns
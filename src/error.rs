@@ -0,0 +1,50 @@
+//! # Error types
+//!
+//! Fallible driver operations report a [`WLError`] describing what was wrong
+//! with the input, instead of panicking deep inside the sampling loop.
+
+use std::fmt;
+
+/// Errors reported by the Wang-Landau driver.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WLError {
+    /// A per-bin array did not match the number of bins
+    LengthMismatch {
+        /// The number of bins the driver holds
+        expected: usize,
+        /// The length that was supplied
+        found: usize,
+    },
+
+    /// A mapper's bins were not the flat indices `0..n` in order
+    NonContiguousBins {
+        /// The position in `bins()` of the first offending bin
+        position: usize,
+        /// The flat index of the bin at that position
+        found: usize,
+    },
+
+    /// An ln(g) entry became NaN or infinite
+    NonFinite {
+        /// The first bin holding a non-finite value
+        bin: usize,
+    },
+}
+
+impl fmt::Display for WLError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WLError::LengthMismatch { expected, found } => {
+                write!(f, "expected {} bins, found {}", expected, found)
+            }
+            WLError::NonContiguousBins { position, found } => write!(
+                f,
+                "bin at position {} has flat index {}; bins must be 0..n in order",
+                position, found
+            ),
+            WLError::NonFinite { bin } => write!(f, "ln(g) of bin {} is not finite", bin),
+        }
+    }
+}
+
+impl std::error::Error for WLError {}
@@ -10,8 +10,13 @@
 //!   of the mean visit count
 //! - [`RMS`]: Checks if the relative standard deviation is below a threshold
 //!
+//! The [`Explained`] decorator wraps any criterion and records why it rejected
+//! a histogram.
+//!
 //! Custom criteria can be implemented by implementing the [`Flatness`] trait.
 
+use std::cell::RefCell;
+
 use crate::traits::Flatness;
 
 /// Considers a histogram flat when `min(H) ≥ flat × mean(H)`.
@@ -35,6 +40,7 @@ use crate::traits::Flatness;
 /// // So this would return true
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fraction;
 
 impl Flatness for Fraction {
@@ -71,6 +77,7 @@ impl Flatness for Fraction {
 /// // This is less than 0.1, so would return true
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RMS;
 
 impl Flatness for RMS {
@@ -97,3 +104,159 @@ impl Flatness for RMS {
         rel_std_dev <= 1.0 - flat
     }
 }
+
+/// Wraps a flatness criterion and records why it returned `false`.
+///
+/// The verdict of the inner criterion is passed through unchanged. Whenever it
+/// rejects a histogram, the least-visited bin is reported, e.g.
+/// `"least-visited bin 37: 0 visits, min/mean 0.000, threshold 0.8"`, which
+/// shows which bins are starving a run that never flattens. The message only
+/// states the facts, so it holds whatever rule the inner criterion applies;
+/// under per-bin thresholds it names the bin furthest below its own. The
+/// explanation is cleared when a
+/// histogram is accepted. Since [`Flatness::is_flat`] takes `&self`, the
+/// explanation is kept in a [`RefCell`].
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::flatness::Explained;
+/// use wanglandau::prelude::*;
+///
+/// let flatness = Explained::new(Fraction);
+/// assert!(!flatness.is_flat(&[10, 12, 0, 11], 0.8));
+///
+/// let reason = flatness.explanation().unwrap();
+/// assert!(reason.starts_with("least-visited bin 2: 0 visits"));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Explained<F> {
+    /// The wrapped criterion
+    inner: F,
+
+    /// Reason for the most recent rejection
+    reason: RefCell<Option<String>>,
+}
+
+impl<F: Flatness> Explained<F> {
+    /// Wraps a flatness criterion.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The criterion whose verdicts are explained
+    ///
+    /// # Returns
+    ///
+    /// A new `Explained` decorator with no recorded explanation
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            reason: RefCell::new(None),
+        }
+    }
+
+    /// Returns why the most recent check failed.
+    ///
+    /// # Returns
+    ///
+    /// The explanation of the last rejection, or `None` if the last check
+    /// passed or no check has run yet
+    pub fn explanation(&self) -> Option<String> {
+        self.reason.borrow().clone()
+    }
+
+    /// Returns the wrapped criterion.
+    ///
+    /// # Returns
+    ///
+    /// The inner criterion, discarding any explanation
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+impl<F> Explained<F> {
+    /// Records why `hist` was rejected, naming entry `i` as bin `label(i)`.
+    fn explain(&self, verdict: bool, hist: &[u64], flat: f64, label: impl Fn(usize) -> usize) {
+        let reason = if verdict {
+            None
+        } else if hist.is_empty() {
+            Some("histogram has no bins".to_string())
+        } else {
+            let (bin, &visits) = hist.iter().enumerate().min_by_key(|&(_, h)| *h).unwrap();
+            let avg = hist.iter().sum::<u64>() as f64 / hist.len() as f64;
+            let ratio = if avg > 0.0 { visits as f64 / avg } else { 0.0 };
+            Some(format!(
+                "least-visited bin {}: {} visits, min/mean {:.3}, threshold {}",
+                label(bin),
+                visits,
+                ratio,
+                flat
+            ))
+        };
+        *self.reason.borrow_mut() = reason;
+    }
+
+    /// Records why `hist` was rejected under per-bin thresholds, naming entry
+    /// `i` as bin `label(i)`.
+    fn explain_per_bin(
+        &self,
+        verdict: bool,
+        hist: &[u64],
+        flatness: &[f64],
+        label: impl Fn(usize) -> usize,
+    ) {
+        let reason = if verdict {
+            None
+        } else if hist.is_empty() {
+            Some("histogram has no bins".to_string())
+        } else {
+            // Report the bin that falls furthest short of its own threshold
+            let avg = hist.iter().sum::<u64>() as f64 / hist.len() as f64;
+            let ratio = |h: u64| if avg > 0.0 { h as f64 / avg } else { 0.0 };
+            let (bin, (&visits, &flat)) = hist
+                .iter()
+                .zip(flatness)
+                .enumerate()
+                .min_by(|(_, (&a, &fa)), (_, (&b, &fb))| {
+                    (ratio(a) - fa).total_cmp(&(ratio(b) - fb))
+                })
+                .unwrap();
+            Some(format!(
+                "worst bin {}: {} visits, visits/mean {:.3}, threshold {}",
+                label(bin),
+                visits,
+                ratio(visits),
+                flat
+            ))
+        };
+        *self.reason.borrow_mut() = reason;
+    }
+}
+
+impl<F: Flatness> Flatness for Explained<F> {
+    fn is_flat(&self, hist: &[u64], flat: f64) -> bool {
+        let verdict = self.inner.is_flat(hist, flat);
+        self.explain(verdict, hist, flat, |bin| bin);
+        verdict
+    }
+
+    fn is_flat_per_bin(&self, hist: &[u64], flatness: &[f64]) -> bool {
+        let verdict = self.inner.is_flat_per_bin(hist, flatness);
+        self.explain_per_bin(verdict, hist, flatness, |bin| bin);
+        verdict
+    }
+
+    fn is_flat_subset(&self, hist: &[u64], bins: &[usize], flat: f64) -> bool {
+        let verdict = self.inner.is_flat_subset(hist, bins, flat);
+        self.explain(verdict, hist, flat, |i| bins[i]);
+        verdict
+    }
+
+    fn is_flat_per_bin_subset(&self, hist: &[u64], bins: &[usize], flatness: &[f64]) -> bool {
+        let verdict = self.inner.is_flat_per_bin_subset(hist, bins, flatness);
+        self.explain_per_bin(verdict, hist, flatness, |i| bins[i]);
+        verdict
+    }
+}
@@ -0,0 +1,61 @@
+//! # Floating-point precision of ln(g)
+//!
+//! The driver stores ln(g) in a type implementing [`Float`], selected by the
+//! last type parameter of [`WLDriver`](crate::driver::WLDriver) (`f64` by
+//! default). Implementations are provided for `f32` and `f64`.
+//!
+//! `f32` halves the memory of ln(g), which matters for huge bin counts, but
+//! it only resolves about 7 significant digits: once ln(g) grows into the
+//! thousands an `ln_f` below roughly `1e-4` is lost to rounding when added,
+//! and refinement silently stalls. Prefer `f64` unless memory is the
+//! bottleneck, and keep the schedule's tolerance loose with `f32`.
+//!
+//! The ln(g) updates and the difference `ln g(old) - ln g(new)` of each
+//! acceptance are computed at the chosen precision. The difference is then
+//! widened to `f64`, where the [`Acceptance`](crate::traits::Acceptance)
+//! rule turns it into a probability.
+
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+/// Floating-point type usable for ln(g) storage and arithmetic.
+pub trait Float:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + AddAssign
+    + SubAssign
+    + 'static
+{
+    /// Converts from `f64`, rounding if necessary.
+    fn from_f64(x: f64) -> Self;
+
+    /// Converts to `f64` exactly.
+    fn to_f64(self) -> f64;
+}
+
+impl Float for f64 {
+    #[inline]
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Float for f32 {
+    #[inline]
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
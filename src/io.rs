@@ -0,0 +1,297 @@
+//! # Reading and writing results
+//!
+//! This module stores the outputs of a run, ln(g) and the visit histogram, in
+//! a compact little-endian binary format suited to large bin counts:
+//!
+//! | Bytes   | Content                                  |
+//! |---------|------------------------------------------|
+//! | 8       | Magic bytes `WLANDAU\0`                  |
+//! | 4       | Format version (`u32`, currently 1)      |
+//! | 8       | Bin count `n` (`u64`)                    |
+//! | 8 · n   | ln(g) values (`f64`)                     |
+//! | 8 · n   | Histogram counts (`u64`)                 |
+//!
+//! - [`write_binary`]: Writes ln(g) and the histogram
+//! - [`read_binary`]: Reads them back, validating the header
+//!
+//! For tracing ln(g) over a long run it also provides an append-friendly
+//! text format with one snapshot per line: a step label followed by the
+//! ln(g) values, all separated by single spaces. Values are printed in the
+//! shortest form that parses back to the same `f64`.
+//!
+//! - [`write_snapshot`]: Appends one snapshot line
+//! - [`read_snapshots`]: Parses a trace of snapshot lines
+//!
+//! For exchange with other Wang-Landau tools it reads and writes the common
+//! two-column `.dos` text format: a `#` comment header followed by one
+//! `energy ln_g` line per bin.
+//!
+//! - [`write_dos`]: Writes energies and ln(g) as two columns
+//! - [`read_dos`]: Parses a two-column file, skipping comments
+
+use std::io::{self, BufRead, Read, Write};
+
+/// Magic bytes that open every binary file
+const MAGIC: &[u8; 8] = b"WLANDAU\0";
+
+/// Current version of the binary format
+const VERSION: u32 = 1;
+
+/// Writes ln(g) and the histogram in the binary format.
+///
+/// Values are written one at a time, so wrap files in a
+/// [`BufWriter`](std::io::BufWriter).
+///
+/// # Parameters
+///
+/// * `w` - The destination
+/// * `ln_g` - The ln(g) value of each bin
+/// * `hist` - The visit count of each bin
+///
+/// # Returns
+///
+/// `Ok(())` on success, or an error of kind `InvalidInput` if the arrays
+/// differ in length, or any error from the writer
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::io::{read_binary, write_binary};
+///
+/// let mut buf = Vec::new();
+/// write_binary(&mut buf, &[0.0, 1.5], &[10, 12]).unwrap();
+///
+/// let (ln_g, hist) = read_binary(buf.as_slice()).unwrap();
+/// assert_eq!(ln_g, [0.0, 1.5]);
+/// assert_eq!(hist, [10, 12]);
+/// ```
+pub fn write_binary(mut w: impl Write, ln_g: &[f64], hist: &[u64]) -> io::Result<()> {
+    if ln_g.len() != hist.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "ln_g has {} bins but the histogram has {}",
+                ln_g.len(),
+                hist.len()
+            ),
+        ));
+    }
+
+    w.write_all(MAGIC)?;
+    w.write_all(&VERSION.to_le_bytes())?;
+    w.write_all(&(ln_g.len() as u64).to_le_bytes())?;
+    for g in ln_g {
+        w.write_all(&g.to_le_bytes())?;
+    }
+    for h in hist {
+        w.write_all(&h.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Reads ln(g) and the histogram written by [`write_binary`].
+///
+/// # Parameters
+///
+/// * `r` - The source
+///
+/// # Returns
+///
+/// The ln(g) values and histogram counts, or an error of kind `InvalidData`
+/// if the magic bytes or version do not match, or any error from the reader
+/// (e.g. `UnexpectedEof` for a truncated file)
+pub fn read_binary(mut r: impl Read) -> io::Result<(Vec<f64>, Vec<u64>)> {
+    let mut magic = [0u8; 8];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("not a wanglandau binary file"));
+    }
+
+    let mut word = [0u8; 4];
+    r.read_exact(&mut word)?;
+    let version = u32::from_le_bytes(word);
+    if version != VERSION {
+        return Err(invalid(&format!("unsupported format version {}", version)));
+    }
+
+    let n = read_u64(&mut r)?;
+    let n = usize::try_from(n).map_err(|_| invalid("bin count too large"))?;
+
+    let mut ln_g = Vec::new();
+    for _ in 0..n {
+        ln_g.push(f64::from_bits(read_u64(&mut r)?));
+    }
+    let mut hist = Vec::new();
+    for _ in 0..n {
+        hist.push(read_u64(&mut r)?);
+    }
+    Ok((ln_g, hist))
+}
+
+/// Reads one little-endian `u64`.
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Builds an `InvalidData` error.
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Appends one ln(g) snapshot line to a trace.
+///
+/// # Parameters
+///
+/// * `w` - The destination, typically a file opened in append mode
+/// * `step_label` - The label written before the values, e.g. the step count
+/// * `ln_g` - The ln(g) value of each bin
+///
+/// # Returns
+///
+/// `Ok(())` on success, or any error from the writer
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::io::{read_snapshots, write_snapshot};
+///
+/// let mut trace = Vec::new();
+/// write_snapshot(&mut trace, 100, &[0.0, 1.25]).unwrap();
+/// assert_eq!(trace, b"100 0 1.25\n");
+///
+/// let snapshots = read_snapshots(trace.as_slice()).unwrap();
+/// assert_eq!(snapshots, [(100, vec![0.0, 1.25])]);
+/// ```
+pub fn write_snapshot(mut w: impl Write, step_label: u64, ln_g: &[f64]) -> io::Result<()> {
+    let mut line = step_label.to_string();
+    for g in ln_g {
+        line.push(' ');
+        line.push_str(&g.to_string());
+    }
+    line.push('\n');
+    w.write_all(line.as_bytes())
+}
+
+/// Reads a trace of snapshot lines written by [`write_snapshot`].
+///
+/// Empty lines are skipped.
+///
+/// # Parameters
+///
+/// * `r` - The source
+///
+/// # Returns
+///
+/// The step label and ln(g) values of each snapshot in file order, or an
+/// error of kind `InvalidData` if a line does not parse, or any error from
+/// the reader
+pub fn read_snapshots(r: impl BufRead) -> io::Result<Vec<(u64, Vec<f64>)>> {
+    let mut snapshots = Vec::new();
+    for line in r.lines() {
+        let line = line?;
+        let mut fields = line.split_whitespace();
+        let Some(label) = fields.next() else {
+            continue;
+        };
+        let label = label
+            .parse()
+            .map_err(|_| invalid(&format!("bad step label {:?}", label)))?;
+        let ln_g = fields
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| invalid(&format!("bad ln(g) value {:?}", v)))
+            })
+            .collect::<io::Result<_>>()?;
+        snapshots.push((label, ln_g));
+    }
+    Ok(snapshots)
+}
+
+/// Writes energies and ln(g) in the two-column `.dos` text format.
+///
+/// The first line is the comment `# energy ln_g`; each following line holds
+/// one bin's energy and ln(g), separated by a single space and printed in
+/// the shortest form that parses back to the same `f64`. Forbidden bins are
+/// written as `-inf`.
+///
+/// # Parameters
+///
+/// * `w` - The destination
+/// * `energies` - The energy of each bin
+/// * `ln_g` - The ln(g) value of each bin
+///
+/// # Returns
+///
+/// `Ok(())` on success, or an error of kind `InvalidInput` if the arrays
+/// differ in length, or any error from the writer
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::io::{read_dos, write_dos};
+///
+/// let mut buf = Vec::new();
+/// write_dos(&mut buf, &[-2.0, 0.0], &[0.0, 1.5]).unwrap();
+/// assert_eq!(buf, b"# energy ln_g\n-2 0\n0 1.5\n");
+///
+/// let (energies, ln_g) = read_dos(buf.as_slice()).unwrap();
+/// assert_eq!(energies, [-2.0, 0.0]);
+/// assert_eq!(ln_g, [0.0, 1.5]);
+/// ```
+pub fn write_dos(mut w: impl Write, energies: &[f64], ln_g: &[f64]) -> io::Result<()> {
+    if energies.len() != ln_g.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} energies but ln_g has {} bins",
+                energies.len(),
+                ln_g.len()
+            ),
+        ));
+    }
+
+    let mut text = String::from("# energy ln_g\n");
+    for (e, g) in energies.iter().zip(ln_g) {
+        text.push_str(&format!("{} {}\n", e, g));
+    }
+    w.write_all(text.as_bytes())
+}
+
+/// Reads a two-column `.dos` file such as one written by [`write_dos`].
+///
+/// Lines that are empty or start with `#` are skipped, so headers written by
+/// other tools are accepted as well.
+///
+/// # Parameters
+///
+/// * `r` - The source
+///
+/// # Returns
+///
+/// The energies and ln(g) values in file order, or an error of kind
+/// `InvalidData` if a line does not hold exactly two numbers, or any error
+/// from the reader
+pub fn read_dos(r: impl BufRead) -> io::Result<(Vec<f64>, Vec<f64>)> {
+    let mut energies = Vec::new();
+    let mut ln_g = Vec::new();
+    for line in r.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [e, g] = fields[..] else {
+            return Err(invalid(&format!("expected two columns in {:?}", line)));
+        };
+        let parse = |v: &str| {
+            v.parse::<f64>()
+                .map_err(|_| invalid(&format!("bad number {:?}", v)))
+        };
+        energies.push(parse(e)?);
+        ln_g.push(parse(g)?);
+    }
+    Ok((energies, ln_g))
+}
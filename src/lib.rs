@@ -75,17 +75,31 @@
 //! let ln_g = driver.ln_g();
 //! ```
 
+pub mod acceptance;
+pub mod analysis;
+pub mod array;
+pub mod batch;
 pub mod driver;
+pub mod error;
 pub mod flatness;
+pub mod float;
+pub mod io;
+pub mod macrospace;
+pub mod moves;
+pub mod parallel;
 pub mod rng;
 pub mod schedule;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod thermo;
 pub mod traits;
 
 /// Commonly used items, exported for convenience.
 pub mod prelude {
+    pub use crate::acceptance::{GlauberWL, Metropolis};
     pub use crate::driver::{Params, WLDriver};
     pub use crate::flatness::{Fraction, RMS};
     pub use crate::rng::Rng64;
-    pub use crate::schedule::{Geometric, OneOverT};
+    pub use crate::schedule::{AdaptiveGeometric, Geometric, OneOverT};
     pub use crate::traits::*;
 }
@@ -0,0 +1,839 @@
+//! # Reusable state-to-bin mappings
+//!
+//! This module provides implementations of the [`Macrospace`] trait for common
+//! binning schemes:
+//!
+//! - [`EnergyBins`]: Bins an energy function on a fixed-width grid, clamping
+//!   out-of-range energies and counting how often that happens
+//! - [`EdgeBins`]: Bins an energy function between arbitrary sorted edges
+//! - [`quantile_edges`]: Edges with equal occupancy over a pilot sample, for
+//!   [`EdgeBins::from_quantiles`]
+//! - [`Periodic`]: Wraps out-of-range bin indices around for periodic
+//!   coordinates such as angles
+//! - [`EnumBins`]: One bin per variant of an enum-valued macrostate
+//! - [`LinearCombination`]: Bins a weighted sum of several order parameters
+//!   on a uniform grid
+//! - [`scan_energy_range`]: Discovers the reachable energy range with a free
+//!   random walk, which [`EnergyBins::from_scan`] turns into a grid
+//!
+//! Custom mappings can be implemented by implementing the [`Macrospace`] trait.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use rand::RngCore;
+
+use crate::traits::{Macrospace, Move, State};
+
+/// Bins states by energy on a grid of fixed width anchored at the origin.
+///
+/// Bin edges sit at integer multiples of `width`, so a state of energy `e`
+/// falls in grid cell `floor(e / width)`. The first bin is the cell containing
+/// `lower` and there are `n_bins` consecutive bins. With `lower = 0` this is
+/// exactly the common hand-rolled `(e / width).floor()` mapping.
+///
+/// Energies below the first or above the last bin are clamped to it. Because
+/// silently clamping hides a badly chosen energy window, every clamped call to
+/// [`locate`](Macrospace::locate) is counted and exposed through
+/// [`n_clamped_low`](Self::n_clamped_low) and
+/// [`n_clamped_high`](Self::n_clamped_high).
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::macrospace::EnergyBins;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Oscillator(f64);
+/// impl State for Oscillator {}
+///
+/// // 0 ≤ E < 10 in bins of width 0.1
+/// let bins = EnergyBins::new(|s: &Oscillator| 0.5 * s.0 * s.0, 0.1, 0.0, 100);
+///
+/// assert_eq!(bins.locate(&Oscillator(1.0)), 5);
+/// assert_eq!(bins.locate(&Oscillator(10.0)), 99);
+/// assert_eq!(bins.n_clamped_high(), 1);
+/// ```
+pub struct EnergyBins<S, E> {
+    /// Energy of a state
+    energy: E,
+
+    /// Width of each bin
+    width: f64,
+
+    /// Grid cell of the first bin
+    first_cell: i64,
+
+    /// Bin indices 0..n_bins
+    bins: Vec<usize>,
+
+    /// Calls that clamped to the first bin
+    clamped_low: Cell<u64>,
+
+    /// Calls that clamped to the last bin
+    clamped_high: Cell<u64>,
+
+    _state: PhantomData<fn(&S)>,
+}
+
+impl<S, E> EnergyBins<S, E>
+where
+    E: Fn(&S) -> f64,
+{
+    /// Creates an energy binning.
+    ///
+    /// # Parameters
+    ///
+    /// * `energy` - The energy of a state
+    /// * `width` - The width of each bin
+    /// * `lower` - An energy inside the first bin
+    /// * `n_bins` - The number of bins
+    ///
+    /// # Returns
+    ///
+    /// A new `EnergyBins` with zeroed clamp counters
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not positive or `n_bins` is zero.
+    pub fn new(energy: E, width: f64, lower: f64, n_bins: usize) -> Self {
+        assert!(width > 0.0, "bin width must be positive");
+        assert!(n_bins > 0, "at least one bin is required");
+        Self {
+            energy,
+            width,
+            first_cell: (lower / width).floor() as i64,
+            bins: (0..n_bins).collect(),
+            clamped_low: Cell::new(0),
+            clamped_high: Cell::new(0),
+            _state: PhantomData,
+        }
+    }
+
+    /// Creates an energy binning covering the range found by a scan.
+    ///
+    /// Runs [`scan_energy_range`] from `start` and lays a grid of the given
+    /// width over the discovered range, padded by one bin on each side so
+    /// energies the short scan just missed are not clamped. This is meant for
+    /// exploratory runs where the energy window is not known in advance.
+    ///
+    /// # Parameters
+    ///
+    /// * `energy` - The energy of a state
+    /// * `width` - The width of each bin
+    /// * `start` - The state the scan starts from
+    /// * `moves` - The moves used for the scan
+    /// * `rng` - The random number generator driving the moves
+    /// * `n_steps` - The number of moves in the scan
+    ///
+    /// # Returns
+    ///
+    /// A new `EnergyBins` spanning the padded range
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not positive or a scanned energy is not finite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wanglandau::macrospace::EnergyBins;
+    /// use wanglandau::prelude::*;
+    ///
+    /// #[derive(Clone)]
+    /// struct Level(u8);
+    /// impl State for Level {}
+    ///
+    /// struct Hop;
+    /// impl<R: rand::RngCore> Move<Level, R> for Hop {
+    ///     fn propose(&mut self, s: &mut Level, rng: &mut R) {
+    ///         use rand::Rng;
+    ///         s.0 = rng.random_range(0..=4);
+    ///     }
+    /// }
+    ///
+    /// // Energies 0, 1, ..., 4 plus one padding bin on each side
+    /// let energy = |s: &Level| s.0 as f64;
+    /// let mut rng = wanglandau::rng::seeded(1);
+    /// let bins = EnergyBins::from_scan(energy, 1.0, Level(0), &mut Hop, &mut rng, 1000);
+    /// assert_eq!(bins.bins().len(), 7);
+    /// assert_eq!(bins.locate(&Level(0)), 1);
+    /// ```
+    pub fn from_scan<Mv, R>(
+        energy: E,
+        width: f64,
+        start: S,
+        moves: &mut Mv,
+        rng: &mut R,
+        n_steps: u64,
+    ) -> Self
+    where
+        S: State,
+        Mv: Move<S, R>,
+        R: RngCore,
+    {
+        assert!(width > 0.0, "bin width must be positive");
+        let (min, max) = scan_energy_range(&energy, start, moves, rng, n_steps);
+        let first_cell = (min / width).floor() as i64 - 1;
+        let last_cell = (max / width).floor() as i64 + 1;
+        let n_bins = (last_cell - first_cell + 1) as usize;
+        Self::new(energy, width, (first_cell as f64 + 0.5) * width, n_bins)
+    }
+
+    /// Returns how many located states fell below the first bin.
+    ///
+    /// # Returns
+    ///
+    /// The number of calls clamped to the first bin
+    pub fn n_clamped_low(&self) -> u64 {
+        self.clamped_low.get()
+    }
+
+    /// Returns how many located states fell above the last bin.
+    ///
+    /// # Returns
+    ///
+    /// The number of calls clamped to the last bin
+    pub fn n_clamped_high(&self) -> u64 {
+        self.clamped_high.get()
+    }
+
+    /// Resets both clamp counters to zero.
+    pub fn reset_clamp_counters(&self) {
+        self.clamped_low.set(0);
+        self.clamped_high.set(0);
+    }
+}
+
+impl<S, E> Macrospace<S> for EnergyBins<S, E>
+where
+    S: State,
+    E: Fn(&S) -> f64,
+{
+    type Bin = usize;
+
+    fn locate(&self, s: &S) -> usize {
+        let cell = ((self.energy)(s) / self.width).floor() as i64;
+        let idx = cell - self.first_cell;
+        let last = self.bins.len() - 1;
+        if idx < 0 {
+            self.clamped_low.set(self.clamped_low.get() + 1);
+            0
+        } else if idx as usize > last {
+            self.clamped_high.set(self.clamped_high.get() + 1);
+            last
+        } else {
+            idx as usize
+        }
+    }
+
+    fn bins(&self) -> &[usize] {
+        &self.bins
+    }
+
+    fn position_in_bin(&self, s: &S) -> Option<f64> {
+        let x = (self.energy)(s) / self.width;
+        let idx = x.floor() as i64 - self.first_cell;
+        if idx < 0 || idx as usize >= self.bins.len() {
+            return None;
+        }
+        Some(x - x.floor())
+    }
+}
+
+/// Finds the range of energies reachable from a state by a free random walk.
+///
+/// Every proposal of `moves` is accepted, so the walk explores the state
+/// space without any energy bias, roughly like sampling at infinite
+/// temperature. The extremes it meets over `n_steps` moves, including the
+/// starting state, bound the energies a Wang-Landau run is likely to visit;
+/// a longer scan finds rarer extremes.
+///
+/// # Parameters
+///
+/// * `energy` - The energy of a state
+/// * `start` - The state the walk starts from
+/// * `moves` - The moves used for the walk
+/// * `rng` - The random number generator driving the moves
+/// * `n_steps` - The number of moves to make
+///
+/// # Returns
+///
+/// The smallest and largest energy seen, as `(min, max)`
+///
+/// # Panics
+///
+/// Panics if an energy met during the walk is not finite.
+pub fn scan_energy_range<S, Mv, R>(
+    energy: impl Fn(&S) -> f64,
+    start: S,
+    moves: &mut Mv,
+    rng: &mut R,
+    n_steps: u64,
+) -> (f64, f64)
+where
+    S: State,
+    Mv: Move<S, R>,
+    R: RngCore,
+{
+    let mut state = start;
+    let mut range = (f64::INFINITY, f64::NEG_INFINITY);
+    for step in 0..=n_steps {
+        if step > 0 {
+            moves.propose(&mut state, rng);
+        }
+        let e = energy(&state);
+        assert!(e.is_finite(), "scanned energy {} is not finite", e);
+        range = (range.0.min(e), range.1.max(e));
+    }
+    range
+}
+
+/// Bins states by energy between arbitrary sorted bin edges.
+///
+/// `edges` of length `n + 1` define `n` bins, bin `i` covering
+/// `edges[i] ≤ e < edges[i + 1]`, so widths can be fine near a transition and
+/// coarse elsewhere. [`locate`](Macrospace::locate) finds the bin by binary
+/// search (`partition_point`) in `O(log n)`.
+///
+/// As with [`EnergyBins`], energies below the first edge or at or above the
+/// last are clamped into the first or last bin and counted.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::macrospace::EdgeBins;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Level(f64);
+/// impl State for Level {}
+///
+/// // Bins [0, 1), [1, 1.5), [1.5, 4)
+/// let bins = EdgeBins::new(|s: &Level| s.0, vec![0.0, 1.0, 1.5, 4.0]);
+///
+/// assert_eq!(bins.locate(&Level(1.2)), 1);
+/// assert_eq!(bins.locate(&Level(3.0)), 2);
+/// ```
+pub struct EdgeBins<S, E> {
+    /// Energy of a state
+    energy: E,
+
+    /// Sorted bin edges
+    edges: Vec<f64>,
+
+    /// Bin indices 0..edges.len() - 1
+    bins: Vec<usize>,
+
+    /// Calls that clamped to the first bin
+    clamped_low: Cell<u64>,
+
+    /// Calls that clamped to the last bin
+    clamped_high: Cell<u64>,
+
+    _state: PhantomData<fn(&S)>,
+}
+
+impl<S, E> EdgeBins<S, E>
+where
+    E: Fn(&S) -> f64,
+{
+    /// Creates a binning over sorted edges.
+    ///
+    /// # Parameters
+    ///
+    /// * `energy` - The energy of a state
+    /// * `edges` - The bin edges, strictly increasing
+    ///
+    /// # Returns
+    ///
+    /// A new `EdgeBins` with `edges.len() - 1` bins
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than two edges or they are not strictly
+    /// increasing.
+    pub fn new(energy: E, edges: Vec<f64>) -> Self {
+        assert!(edges.len() >= 2, "at least two edges are required");
+        assert!(
+            edges.windows(2).all(|w| w[0] < w[1]),
+            "edges must be strictly increasing"
+        );
+        Self {
+            energy,
+            bins: (0..edges.len() - 1).collect(),
+            edges,
+            clamped_low: Cell::new(0),
+            clamped_high: Cell::new(0),
+            _state: PhantomData,
+        }
+    }
+
+    /// Creates a binning whose edges are the quantiles of a pilot sample.
+    ///
+    /// This is `EdgeBins::new(energy, quantile_edges(energies, n))`; see
+    /// [`quantile_edges`] for how the edges are placed.
+    ///
+    /// # Parameters
+    ///
+    /// * `energy` - The energy of a state
+    /// * `energies` - The pilot sample of energies
+    /// * `n` - The requested number of bins
+    ///
+    /// # Returns
+    ///
+    /// A new `EdgeBins` with up to `n` bins of roughly equal pilot occupancy
+    ///
+    /// # Panics
+    ///
+    /// Panics if `energies` is empty, `n` is zero or an energy is not finite.
+    pub fn from_quantiles(energy: E, energies: &[f64], n: usize) -> Self {
+        Self::new(energy, quantile_edges(energies, n))
+    }
+
+    /// Returns the bin edges.
+    ///
+    /// # Returns
+    ///
+    /// The sorted edges, one more than the number of bins
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    /// Returns how many located states fell below the first edge.
+    ///
+    /// # Returns
+    ///
+    /// The number of calls clamped to the first bin
+    pub fn n_clamped_low(&self) -> u64 {
+        self.clamped_low.get()
+    }
+
+    /// Returns how many located states fell at or above the last edge.
+    ///
+    /// # Returns
+    ///
+    /// The number of calls clamped to the last bin
+    pub fn n_clamped_high(&self) -> u64 {
+        self.clamped_high.get()
+    }
+}
+
+impl<S, E> Macrospace<S> for EdgeBins<S, E>
+where
+    S: State,
+    E: Fn(&S) -> f64,
+{
+    type Bin = usize;
+
+    fn locate(&self, s: &S) -> usize {
+        let e = (self.energy)(s);
+        // Number of edges at or below e; NaN compares false and lands low
+        let above = self.edges.partition_point(|&edge| edge <= e);
+        if above == 0 {
+            self.clamped_low.set(self.clamped_low.get() + 1);
+            0
+        } else if above == self.edges.len() {
+            self.clamped_high.set(self.clamped_high.get() + 1);
+            self.bins.len() - 1
+        } else {
+            above - 1
+        }
+    }
+
+    fn bins(&self) -> &[usize] {
+        &self.bins
+    }
+
+    fn position_in_bin(&self, s: &S) -> Option<f64> {
+        let e = (self.energy)(s);
+        let above = self.edges.partition_point(|&edge| edge <= e);
+        if above == 0 || above == self.edges.len() {
+            return None;
+        }
+        let (lower, upper) = (self.edges[above - 1], self.edges[above]);
+        Some((e - lower) / (upper - lower))
+    }
+}
+
+/// Places bin edges at the quantiles of a sample of energies.
+///
+/// Edge `k` of `n` bins is the `k/n` quantile of `energies`, so each bin
+/// holds about the same share of the sample. Taking the sample from a short
+/// pilot run and binning the production run with these edges concentrates
+/// resolution where the walker spends its time. The first edge is the
+/// smallest energy and the last lies just above the largest, so the whole
+/// sample falls inside the bins. Quantiles that coincide, as they do for
+/// discrete energies, are merged, which yields fewer than `n` bins.
+///
+/// # Parameters
+///
+/// * `energies` - The pilot sample of energies
+/// * `n` - The requested number of bins
+///
+/// # Returns
+///
+/// Strictly increasing edges, at most `n + 1` of them
+///
+/// # Panics
+///
+/// Panics if `energies` is empty, `n` is zero or an energy is not finite.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::macrospace::quantile_edges;
+///
+/// let edges = quantile_edges(&[0.0, 1.0, 2.0, 3.0, 10.0, 20.0], 3);
+/// assert_eq!(edges.len(), 4);
+/// assert_eq!(&edges[..3], &[0.0, 2.0, 10.0]);
+/// assert!(edges[3] > 20.0);
+/// ```
+pub fn quantile_edges(energies: &[f64], n: usize) -> Vec<f64> {
+    assert!(!energies.is_empty(), "the pilot sample is empty");
+    assert!(n > 0, "at least one bin is required");
+    assert!(
+        energies.iter().all(|e| e.is_finite()),
+        "pilot energies must be finite"
+    );
+    let mut sorted = energies.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let m = sorted.len();
+    let mut edges: Vec<f64> = (0..n).map(|k| sorted[k * m / n]).collect();
+    edges.push(sorted[m - 1].next_up());
+    edges.dedup();
+    edges
+}
+
+/// Bins a periodic coordinate, wrapping out-of-range indices modulo `n_bins`.
+///
+/// For an angular collective variable the first and last bins are
+/// neighbours, so clamping an index that falls off one end (as
+/// [`EnergyBins`] does) would pile the walker into the wrong edge bin. Here
+/// the wrapped function returns an unbounded integer index and
+/// [`locate`](Macrospace::locate) reduces it with `rem_euclid`: index
+/// `n_bins` maps to bin `0` and index `-1` to bin `n_bins - 1`. The index
+/// carries no position within a bin, so
+/// [`with_kernel_deposition`](crate::driver::WLDriver::with_kernel_deposition)
+/// falls back to hard assignment.
+///
+/// # Example
+///
+/// ```
+/// use std::f64::consts::TAU;
+/// use wanglandau::macrospace::Periodic;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Dihedral(f64);
+/// impl State for Dihedral {}
+///
+/// // 36 bins of 10° over the full turn
+/// let width = TAU / 36.0;
+/// let bins = Periodic::new(move |s: &Dihedral| (s.0 / width).floor() as i64, 36);
+///
+/// assert_eq!(bins.locate(&Dihedral(0.01)), 0);
+/// assert_eq!(bins.locate(&Dihedral(-0.01)), 35);
+/// assert_eq!(bins.locate(&Dihedral(TAU + 0.01)), 0);
+/// ```
+pub struct Periodic<S, I> {
+    /// Unwrapped bin index of a state
+    index: I,
+
+    /// Bin indices 0..n_bins
+    bins: Vec<usize>,
+
+    _state: PhantomData<fn(&S)>,
+}
+
+impl<S, I> Periodic<S, I>
+where
+    I: Fn(&S) -> i64,
+{
+    /// Creates a periodic binning.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The unwrapped bin index of a state, any `i64`
+    /// * `n_bins` - The number of bins in one period
+    ///
+    /// # Returns
+    ///
+    /// A new `Periodic` with `n_bins` bins
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_bins` is zero.
+    pub fn new(index: I, n_bins: usize) -> Self {
+        assert!(n_bins > 0, "at least one bin is required");
+        Self {
+            index,
+            bins: (0..n_bins).collect(),
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<S, I> Macrospace<S> for Periodic<S, I>
+where
+    S: State,
+    I: Fn(&S) -> i64,
+{
+    type Bin = usize;
+
+    fn locate(&self, s: &S) -> usize {
+        (self.index)(s).rem_euclid(self.bins.len() as i64) as usize
+    }
+
+    fn bins(&self) -> &[usize] {
+        &self.bins
+    }
+}
+
+/// Bins states by an enum-valued macrostate, one bin per variant.
+///
+/// For discrete systems whose macrostate is one of a few phases, the wrapped
+/// closure maps a state to its variant and [`locate`](Macrospace::locate)
+/// returns the variant's index via `Into<usize>`. The `TryFrom<usize>`
+/// conversion maps bins back to variants with [`variant`](Self::variant) and
+/// lets [`new`](Self::new) check that the variant count is right.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::macrospace::EnumBins;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Phase {
+///     Solid,
+///     Liquid,
+/// }
+///
+/// impl From<Phase> for usize {
+///     fn from(p: Phase) -> usize {
+///         p as usize
+///     }
+/// }
+///
+/// impl TryFrom<usize> for Phase {
+///     type Error = ();
+///     fn try_from(i: usize) -> Result<Self, ()> {
+///         match i {
+///             0 => Ok(Phase::Solid),
+///             1 => Ok(Phase::Liquid),
+///             _ => Err(()),
+///         }
+///     }
+/// }
+///
+/// #[derive(Clone)]
+/// struct Water(f64);
+/// impl State for Water {}
+///
+/// let bins = EnumBins::new(
+///     |s: &Water| if s.0 < 0.0 { Phase::Solid } else { Phase::Liquid },
+///     2,
+/// );
+///
+/// assert_eq!(bins.locate(&Water(20.0)), 1);
+/// assert_eq!(bins.variant(0), Some(Phase::Solid));
+/// ```
+pub struct EnumBins<E, S, F> {
+    /// Variant of a state
+    variant: F,
+
+    /// Bin indices 0..n_variants
+    bins: Vec<usize>,
+
+    _marker: PhantomData<fn(&S) -> E>,
+}
+
+impl<E, S, F> EnumBins<E, S, F>
+where
+    E: Into<usize> + TryFrom<usize>,
+    F: Fn(&S) -> E,
+{
+    /// Creates an enum binning.
+    ///
+    /// # Parameters
+    ///
+    /// * `variant` - The variant of a state
+    /// * `n_variants` - The number of variants, whose indices must be
+    ///   `0..n_variants`
+    ///
+    /// # Returns
+    ///
+    /// A new `EnumBins` with one bin per variant
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_variants` is zero or if `E::try_from` does not accept
+    /// exactly the indices `0..n_variants`.
+    pub fn new(variant: F, n_variants: usize) -> Self {
+        assert!(n_variants > 0, "at least one variant is required");
+        assert!(
+            (0..n_variants).all(|i| E::try_from(i).is_ok()),
+            "not every index below {} is a variant",
+            n_variants
+        );
+        assert!(
+            E::try_from(n_variants).is_err(),
+            "the enum has more than {} variants",
+            n_variants
+        );
+        Self {
+            variant,
+            bins: (0..n_variants).collect(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the variant of a bin.
+    ///
+    /// # Parameters
+    ///
+    /// * `bin` - The bin index
+    ///
+    /// # Returns
+    ///
+    /// The variant whose index is `bin`, or `None` if `bin` is out of range
+    pub fn variant(&self, bin: usize) -> Option<E> {
+        E::try_from(bin).ok().filter(|_| bin < self.bins.len())
+    }
+}
+
+impl<E, S, F> Macrospace<S> for EnumBins<E, S, F>
+where
+    S: State,
+    E: Into<usize> + TryFrom<usize>,
+    F: Fn(&S) -> E,
+{
+    type Bin = usize;
+
+    fn locate(&self, s: &S) -> usize {
+        (self.variant)(s).into()
+    }
+
+    fn bins(&self) -> &[usize] {
+        &self.bins
+    }
+}
+
+/// A boxed function of a state, one term of a [`LinearCombination`]
+pub type Term<S> = Box<dyn Fn(&S) -> f64>;
+
+/// Bins a weighted sum of several order parameters on a uniform grid.
+///
+/// A one-dimensional reaction coordinate is sometimes a combination such as
+/// `0.7 · energy + 0.3 · magnetization`. Each term pairs a weight with a
+/// function of the state; their weighted sum is binned exactly as an
+/// [`EnergyBins`] bins its energy, including the clamping and its counters.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::macrospace::LinearCombination;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Spins {
+///     energy: f64,
+///     magnetization: f64,
+/// }
+/// impl State for Spins {}
+///
+/// let bins = LinearCombination::new(
+///     vec![
+///         (0.7, Box::new(|s: &Spins| s.energy)),
+///         (0.3, Box::new(|s: &Spins| s.magnetization)),
+///     ],
+///     1.0,
+///     0.0,
+///     10,
+/// );
+///
+/// let s = Spins { energy: 5.0, magnetization: 2.0 };
+/// assert!((bins.coordinate(&s) - 4.1).abs() < 1e-12);
+/// assert_eq!(bins.locate(&s), 4);
+/// ```
+pub struct LinearCombination<S> {
+    /// Uniform binning of the combined coordinate
+    grid: EnergyBins<S, Term<S>>,
+}
+
+impl<S: 'static> LinearCombination<S> {
+    /// Creates a binning of a weighted sum of terms.
+    ///
+    /// # Parameters
+    ///
+    /// * `terms` - The `(weight, function)` pairs summed into the coordinate
+    /// * `width` - The width of each bin
+    /// * `lower` - A coordinate value inside the first bin
+    /// * `n_bins` - The number of bins
+    ///
+    /// # Returns
+    ///
+    /// A new `LinearCombination` with zeroed clamp counters
+    ///
+    /// # Panics
+    ///
+    /// Panics if `terms` is empty, `width` is not positive or `n_bins` is
+    /// zero.
+    pub fn new(terms: Vec<(f64, Term<S>)>, width: f64, lower: f64, n_bins: usize) -> Self {
+        assert!(!terms.is_empty(), "at least one term is required");
+        let combined: Term<S> = Box::new(move |s| terms.iter().map(|(w, f)| w * f(s)).sum());
+        Self {
+            grid: EnergyBins::new(combined, width, lower, n_bins),
+        }
+    }
+
+    /// Evaluates the combined coordinate of a state.
+    ///
+    /// # Parameters
+    ///
+    /// * `s` - The state
+    ///
+    /// # Returns
+    ///
+    /// The weighted sum of the terms
+    pub fn coordinate(&self, s: &S) -> f64 {
+        (self.grid.energy)(s)
+    }
+
+    /// Returns how many located states fell below the first bin.
+    ///
+    /// # Returns
+    ///
+    /// The number of calls clamped to the first bin
+    pub fn n_clamped_low(&self) -> u64 {
+        self.grid.n_clamped_low()
+    }
+
+    /// Returns how many located states fell above the last bin.
+    ///
+    /// # Returns
+    ///
+    /// The number of calls clamped to the last bin
+    pub fn n_clamped_high(&self) -> u64 {
+        self.grid.n_clamped_high()
+    }
+}
+
+impl<S: State> Macrospace<S> for LinearCombination<S> {
+    type Bin = usize;
+
+    fn locate(&self, s: &S) -> usize {
+        self.grid.locate(s)
+    }
+
+    fn bins(&self) -> &[usize] {
+        self.grid.bins()
+    }
+
+    fn position_in_bin(&self, s: &S) -> Option<f64> {
+        self.grid.position_in_bin(s)
+    }
+}
@@ -0,0 +1,543 @@
+//! # Reusable move proposals
+//!
+//! This module provides implementations of the [`Move`] trait for common
+//! classes of systems:
+//!
+//! - [`NeighborMove`]: Hops to a uniformly random neighbor on a graph
+//! - [`BinScaledDisplace`]: Displaces a continuous coordinate by a step size
+//!   chosen per bin
+//! - [`Sequence`]: Applies several moves in turn as one composite proposal
+//! - [`Cluster`]: Wraps a user closure that builds and flips a cluster
+//! - [`RandomPermutationSweep`]: Visits every site once per sweep in a
+//!   freshly shuffled order
+//!
+//! Custom moves can be implemented by implementing the [`Move`] trait.
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::traits::{Move, State};
+
+/// A state that sits on a node of a graph.
+///
+/// Implement this for states sampled by [`NeighborMove`].
+pub trait GraphNode: State {
+    /// Returns the node the state currently occupies.
+    fn node(&self) -> usize;
+
+    /// Moves the state to another node.
+    ///
+    /// # Parameters
+    ///
+    /// * `node` - The node to move to
+    fn set_node(&mut self, node: usize);
+}
+
+/// Proposes a transition to a uniformly random neighbor of the current node.
+///
+/// The graph is given as a precomputed adjacency list. On irregular graphs
+/// the proposal is not symmetric: from a node of degree `d` each neighbor is
+/// chosen with probability `1/d`. The move therefore reports the log proposal
+/// ratio `ln(deg(old) / deg(new))` through [`Move::propose_biased`], which the
+/// driver folds into the acceptance. Nodes without neighbors stay put.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::moves::{GraphNode, NeighborMove};
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Walker(usize);
+/// impl State for Walker {}
+/// impl GraphNode for Walker {
+///     fn node(&self) -> usize { self.0 }
+///     fn set_node(&mut self, node: usize) { self.0 = node; }
+/// }
+///
+/// // A path 0 - 1 - 2
+/// let mut hop = NeighborMove::new(vec![vec![1], vec![0, 2], vec![1]]);
+/// let mut walker = Walker(0);
+/// let mut rng = wanglandau::rng::seeded(1);
+///
+/// let ln_ratio = hop.propose_biased(&mut walker, &mut rng);
+/// assert_eq!(walker.node(), 1);
+/// assert!((ln_ratio - 0.5f64.ln()).abs() < 1e-12); // deg(0) / deg(1) = 1/2
+/// ```
+#[derive(Debug, Clone)]
+pub struct NeighborMove {
+    /// Neighbors of each node
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl NeighborMove {
+    /// Creates a neighbor move over the given adjacency list.
+    ///
+    /// # Parameters
+    ///
+    /// * `adjacency` - The neighbors of each node, indexed by node
+    ///
+    /// # Returns
+    ///
+    /// A new `NeighborMove`
+    pub fn new(adjacency: Vec<Vec<usize>>) -> Self {
+        Self { adjacency }
+    }
+
+    /// Returns the degree of a node.
+    ///
+    /// # Parameters
+    ///
+    /// * `node` - The node to query
+    ///
+    /// # Returns
+    ///
+    /// The number of neighbors of `node`
+    pub fn degree(&self, node: usize) -> usize {
+        self.adjacency[node].len()
+    }
+}
+
+impl<S: GraphNode, R: RngCore> Move<S, R> for NeighborMove {
+    fn propose(&mut self, state: &mut S, rng: &mut R) {
+        self.propose_biased(state, rng);
+    }
+
+    fn propose_biased(&mut self, state: &mut S, rng: &mut R) -> f64 {
+        let neighbors = &self.adjacency[state.node()];
+        if neighbors.is_empty() {
+            return 0.0;
+        }
+
+        let next = neighbors[rng.random_range(0..neighbors.len())];
+        let ratio = neighbors.len() as f64 / self.adjacency[next].len() as f64;
+        state.set_node(next);
+        ratio.ln()
+    }
+}
+
+/// A state described by a single continuous coordinate.
+///
+/// Implement this for states sampled by [`BinScaledDisplace`].
+pub trait Coordinate: State {
+    /// Returns the current value of the coordinate.
+    fn coordinate(&self) -> f64;
+
+    /// Sets the coordinate.
+    ///
+    /// # Parameters
+    ///
+    /// * `x` - The new value of the coordinate
+    fn set_coordinate(&mut self, x: f64);
+}
+
+/// Displaces a coordinate uniformly by up to a step size chosen per bin.
+///
+/// A single fixed step is rarely right across a whole energy range: for a
+/// harmonic oscillator binned in energy, the bins are wide in position near
+/// the minimum and narrow far from it. Here the walker's current bin,
+/// computed with the supplied `bin` function (usually the driver's mapper
+/// logic), selects the half-width of a uniform displacement.
+///
+/// Because the step size depends on where the walker starts, the proposal is
+/// not symmetric. The move reports `ln(s_old / s_new)` through
+/// [`Move::propose_biased`], and a proposal whose reverse step would be out of
+/// reach of the new bin's scale returns `-inf` so that it is rejected.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::moves::{BinScaledDisplace, Coordinate};
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Position(f64);
+/// impl State for Position {}
+/// impl Coordinate for Position {
+///     fn coordinate(&self) -> f64 { self.0 }
+///     fn set_coordinate(&mut self, x: f64) { self.0 = x; }
+/// }
+///
+/// // Large steps below x = 1, small steps above
+/// let bin = |s: &Position| (s.0.abs() >= 1.0) as usize;
+/// let mut displace = BinScaledDisplace::new(bin, vec![0.5, 0.1]);
+///
+/// let mut state = Position(2.0);
+/// let mut rng = wanglandau::rng::seeded(1);
+/// let ln_ratio = displace.propose_biased(&mut state, &mut rng);
+/// assert!((state.0 - 2.0).abs() <= 0.1);
+/// assert_eq!(ln_ratio, 0.0); // same bin, same scale
+/// ```
+#[derive(Debug, Clone)]
+pub struct BinScaledDisplace<B> {
+    /// Bin of a state
+    bin: B,
+
+    /// Maximum displacement in each bin
+    scales: Vec<f64>,
+}
+
+impl<B> BinScaledDisplace<B> {
+    /// Creates a displacement move with per-bin step sizes.
+    ///
+    /// # Parameters
+    ///
+    /// * `bin` - The bin of a state, indexing into `scales`
+    /// * `scales` - The maximum displacement in each bin
+    ///
+    /// # Returns
+    ///
+    /// A new `BinScaledDisplace`
+    ///
+    /// # Panics
+    ///
+    /// Panics if any scale is not positive and finite.
+    pub fn new(bin: B, scales: Vec<f64>) -> Self {
+        assert!(
+            scales.iter().all(|&s| s > 0.0 && s.is_finite()),
+            "scales must be positive and finite"
+        );
+        Self { bin, scales }
+    }
+
+    /// Returns the maximum displacement in a bin.
+    ///
+    /// # Parameters
+    ///
+    /// * `bin` - The bin to query
+    ///
+    /// # Returns
+    ///
+    /// The half-width of the uniform displacement from `bin`
+    pub fn scale(&self, bin: usize) -> f64 {
+        self.scales[bin]
+    }
+}
+
+impl<S, R, B> Move<S, R> for BinScaledDisplace<B>
+where
+    S: Coordinate,
+    R: RngCore,
+    B: Fn(&S) -> usize,
+{
+    fn propose(&mut self, state: &mut S, rng: &mut R) {
+        self.propose_biased(state, rng);
+    }
+
+    fn propose_biased(&mut self, state: &mut S, rng: &mut R) -> f64 {
+        let x = state.coordinate();
+        let s_old = self.scales[(self.bin)(state)];
+        let y = x + rng.random_range(-s_old..=s_old);
+        state.set_coordinate(y);
+
+        let s_new = self.scales[(self.bin)(state)];
+        if (y - x).abs() > s_new {
+            // The reverse move could never be proposed
+            return f64::NEG_INFINITY;
+        }
+        (s_old / s_new).ln()
+    }
+}
+
+/// Applies several moves in turn as one composite proposal.
+///
+/// Some schemes change a state in stages each step, e.g. displace a molecule
+/// and then rotate it. Each sub-move is applied to the result of the
+/// previous one, and the driver accepts or rejects the final state as a
+/// whole: it keeps a copy of the state from before the proposal, so a
+/// rejection reverts every sub-move at once. The log proposal ratios of the
+/// sub-moves add up.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::moves::Sequence;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Counter(u32);
+/// impl State for Counter {}
+///
+/// struct Increment;
+/// impl<R: rand::RngCore> Move<Counter, R> for Increment {
+///     fn propose(&mut self, s: &mut Counter, _rng: &mut R) {
+///         s.0 += 1;
+///     }
+/// }
+///
+/// let mut twice = Sequence::new().then(Increment).then(Increment);
+/// let mut state = Counter(0);
+/// twice.propose(&mut state, &mut wanglandau::rng::seeded(1));
+/// assert_eq!(state.0, 2);
+/// ```
+pub struct Sequence<S, R> {
+    /// The sub-moves in the order they are applied
+    moves: Vec<Box<dyn Move<S, R>>>,
+}
+
+impl<S: State, R: RngCore> Sequence<S, R> {
+    /// Creates an empty sequence, which leaves the state unchanged.
+    ///
+    /// # Returns
+    ///
+    /// A new `Sequence` without sub-moves
+    pub fn new() -> Self {
+        Self { moves: Vec::new() }
+    }
+
+    /// Appends a sub-move to the sequence.
+    ///
+    /// # Parameters
+    ///
+    /// * `step` - The move to apply after the current ones
+    ///
+    /// # Returns
+    ///
+    /// The sequence with `step` appended
+    pub fn then(mut self, step: impl Move<S, R> + 'static) -> Self {
+        self.moves.push(Box::new(step));
+        self
+    }
+
+    /// Returns the number of sub-moves.
+    ///
+    /// # Returns
+    ///
+    /// The number of moves applied per proposal
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Returns whether the sequence has no sub-moves.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a proposal leaves the state unchanged
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+}
+
+impl<S: State, R: RngCore> Default for Sequence<S, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: State, R: RngCore> Move<S, R> for Sequence<S, R> {
+    fn propose(&mut self, state: &mut S, rng: &mut R) {
+        self.propose_biased(state, rng);
+    }
+
+    fn propose_biased(&mut self, state: &mut S, rng: &mut R) -> f64 {
+        self.moves
+            .iter_mut()
+            .map(|step| step.propose_biased(state, rng))
+            .sum()
+    }
+}
+
+/// A boxed closure that flips a cluster and returns the log proposal ratio.
+type ClusterUpdate<S, R> = Box<dyn FnMut(&mut S, &mut R) -> f64>;
+
+/// A cluster update, such as a Wolff or Swendsen-Wang flip, supplied as a
+/// closure.
+///
+/// Near a critical point single-site moves decorrelate slowly, while a
+/// cluster move flips many correlated sites at once. Building the cluster is
+/// system-specific, so this type only fits the closure into the driver's
+/// propose-and-revert contract: the closure mutates the state in place, and
+/// the driver keeps a copy of the state from before the proposal, so a
+/// rejected cluster flip is reverted as a whole without any undo logic.
+///
+/// In the canonical ensemble a cluster flip is usually accepted outright,
+/// but under Wang-Landau sampling the flip still changes the bin and must
+/// pass the ln(g) acceptance to keep detailed balance with respect to
+/// `1/g`. [`new`](Self::new) therefore returns the closure's log proposal
+/// ratio to the acceptance like any other [`Move::propose_biased`].
+/// [`always_accepted`](Self::always_accepted) is for pure cluster moves that
+/// need no acceptance test, e.g. a global spin flip between bins known to
+/// have equal ln(g); it reports an infinite log ratio, so every acceptance
+/// rule accepts it. Moves into forbidden bins are still rejected.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::moves::Cluster;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Spins(Vec<bool>);
+/// impl State for Spins {}
+///
+/// // Flip the cluster of the first two spins as a unit
+/// let mut flip = Cluster::new(|s: &mut Spins, _rng: &mut Rng64| {
+///     s.0[0] = !s.0[0];
+///     s.0[1] = !s.0[1];
+///     0.0
+/// });
+/// let mut state = Spins(vec![true, true, false]);
+/// let ln_ratio = flip.propose_biased(&mut state, &mut wanglandau::rng::seeded(1));
+/// assert_eq!(state.0, [false, false, false]);
+/// assert_eq!(ln_ratio, 0.0);
+/// ```
+pub struct Cluster<S, R> {
+    /// Builds and flips a cluster, returning the log proposal ratio
+    update: ClusterUpdate<S, R>,
+}
+
+impl<S: State, R: RngCore> Cluster<S, R> {
+    /// Creates a cluster move that passes through the Wang-Landau acceptance.
+    ///
+    /// # Parameters
+    ///
+    /// * `update` - Builds a cluster, flips it in place and returns
+    ///   `ln[q(new → old) / q(old → new)]`, which is `0.0` for the usual
+    ///   symmetric cluster constructions
+    ///
+    /// # Returns
+    ///
+    /// A new `Cluster` move
+    pub fn new(update: impl FnMut(&mut S, &mut R) -> f64 + 'static) -> Self {
+        Self {
+            update: Box::new(update),
+        }
+    }
+
+    /// Creates a pure cluster move that skips the acceptance test.
+    ///
+    /// Only use this when accepting every flip preserves detailed balance
+    /// with respect to `1/g`, e.g. when the flip maps each bin onto one with
+    /// the same density of states; otherwise ln(g) will not converge to the
+    /// true density of states.
+    ///
+    /// # Parameters
+    ///
+    /// * `update` - Builds a cluster and flips it in place
+    ///
+    /// # Returns
+    ///
+    /// A new `Cluster` move reporting an infinite log proposal ratio
+    pub fn always_accepted(mut update: impl FnMut(&mut S, &mut R) + 'static) -> Self {
+        Self::new(move |state, rng| {
+            update(state, rng);
+            f64::INFINITY
+        })
+    }
+}
+
+impl<S: State, R: RngCore> Move<S, R> for Cluster<S, R> {
+    fn propose(&mut self, state: &mut S, rng: &mut R) {
+        (self.update)(state, rng);
+    }
+
+    fn propose_biased(&mut self, state: &mut S, rng: &mut R) -> f64 {
+        (self.update)(state, rng)
+    }
+}
+
+/// Proposes site updates in a random permutation of the sites each sweep.
+///
+/// Picking a site uniformly at random for every proposal visits some sites
+/// several times per sweep and others not at all. This move instead shuffles
+/// the `n_sites` site indices at the start of every sweep and hands them to
+/// the site update one at a time, so each site is touched exactly once per
+/// sweep in an order that changes from sweep to sweep. Each call to
+/// [`propose`](Move::propose) is one site update, accepted or rejected by the
+/// driver on its own.
+///
+/// Set [`Params::sweep_len`](crate::driver::Params::sweep_len) to `n_sites`
+/// so that one Wang-Landau step is one complete sweep. The move keeps its
+/// position within the permutation between calls, so with any other
+/// `sweep_len` a sweep simply spans step boundaries.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::moves::RandomPermutationSweep;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Spins(Vec<bool>);
+/// impl State for Spins {}
+///
+/// let mut sweep = RandomPermutationSweep::new(4, |s: &mut Spins, site, _rng: &mut Rng64| {
+///     s.0[site] = !s.0[site];
+/// });
+/// let mut state = Spins(vec![false; 4]);
+/// let mut rng = wanglandau::rng::seeded(1);
+/// for _ in 0..4 {
+///     sweep.propose(&mut state, &mut rng);
+/// }
+/// assert!(state.0.iter().all(|&up| up)); // every site flipped once
+/// ```
+#[derive(Debug, Clone)]
+pub struct RandomPermutationSweep<F> {
+    /// Proposes an update of one site
+    update: F,
+
+    /// Site order of the current sweep
+    order: Vec<usize>,
+
+    /// Position of the next site in `order`
+    cursor: usize,
+
+    /// Number of sweeps started so far
+    sweeps: u64,
+}
+
+impl<F> RandomPermutationSweep<F> {
+    /// Creates a permutation sweep over `n_sites` sites.
+    ///
+    /// # Parameters
+    ///
+    /// * `n_sites` - The number of sites
+    /// * `update` - Proposes an update of the given site in place
+    ///
+    /// # Returns
+    ///
+    /// A new `RandomPermutationSweep` that shuffles on its first proposal
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_sites` is zero.
+    pub fn new<S, R>(n_sites: usize, update: F) -> Self
+    where
+        F: FnMut(&mut S, usize, &mut R),
+    {
+        assert!(n_sites > 0, "a sweep needs at least one site");
+        Self {
+            update,
+            order: (0..n_sites).collect(),
+            cursor: n_sites,
+            sweeps: 0,
+        }
+    }
+
+    /// Returns the number of sweeps started so far.
+    ///
+    /// # Returns
+    ///
+    /// How many times the site order has been shuffled
+    pub fn sweeps(&self) -> u64 {
+        self.sweeps
+    }
+}
+
+impl<S, R, F> Move<S, R> for RandomPermutationSweep<F>
+where
+    S: State,
+    R: RngCore,
+    F: FnMut(&mut S, usize, &mut R),
+{
+    fn propose(&mut self, state: &mut S, rng: &mut R) {
+        if self.cursor == self.order.len() {
+            self.order.shuffle(rng);
+            self.cursor = 0;
+            self.sweeps += 1;
+        }
+        let site = self.order[self.cursor];
+        self.cursor += 1;
+        (self.update)(state, site, rng);
+    }
+}
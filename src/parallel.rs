@@ -0,0 +1,476 @@
+//! # Multi-threaded walkers
+//!
+//! This module provides [`SharedWalker`], which runs several Wang-Landau
+//! walkers on separate threads, all updating one shared ln(g) and histogram.
+//! This is the most common parallel Wang-Landau scheme: the walkers jointly
+//! flatten a single histogram, so each stage needs roughly `1/N` of the
+//! wall time of a single walker.
+//!
+//! ln(g) and the histogram are stored as atomics, so walkers update them
+//! concurrently without locks. A walker may read an ln(g) value that another
+//! thread is about to update; like every shared-ln(g) scheme this makes the
+//! acceptance very slightly stale, which does not affect convergence. For
+//! reproducible tests, [`SharedWalker::run_serial`] interleaves the same
+//! walkers deterministically on the calling thread.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Barrier;
+use std::thread;
+
+use rand::RngCore;
+
+use crate::acceptance::accept;
+use crate::driver::Params;
+use crate::rng::Rng64;
+use crate::traits::{BinIndex, Flatness, Macrospace, Move, Schedule, State};
+
+/// One walker: its configuration, move generator and random stream.
+struct Walker<S, Mv, R> {
+    /// Current state
+    state: S,
+
+    /// Flat index of the current bin
+    bin: usize,
+
+    /// Move proposal generator
+    moves: Mv,
+
+    /// Random number generator
+    rng: R,
+}
+
+/// ln(g) and histogram shared by all walkers.
+struct SharedDos {
+    /// ln(density of states), stored as `f64` bits
+    ln_g: Vec<AtomicU64>,
+
+    /// Histogram of visited states in the current stage
+    hist: Vec<AtomicU64>,
+}
+
+impl SharedDos {
+    /// Adds `x` to the ln(g) of a bin.
+    #[inline]
+    fn add_ln_g(&self, bin: usize, x: f64) {
+        let _ = self.ln_g[bin].fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            Some((f64::from_bits(bits) + x).to_bits())
+        });
+    }
+
+    /// Loads the ln(g) of a bin.
+    #[inline]
+    fn ln_g(&self, bin: usize) -> f64 {
+        f64::from_bits(self.ln_g[bin].load(Ordering::Relaxed))
+    }
+
+    /// Copies the histogram.
+    fn histogram(&self) -> Vec<u64> {
+        self.hist
+            .iter()
+            .map(|h| h.load(Ordering::Relaxed))
+            .collect()
+    }
+}
+
+/// Several Wang-Landau walkers on separate threads sharing one ln(g).
+///
+/// [`run`](Self::run) spawns one thread per walker. In each step every walker
+/// performs `sweep_len` proposals concurrently, updating the shared ln(g) and
+/// histogram after each; the threads then meet at a barrier, the calling
+/// thread checks the combined histogram for flatness and advances the
+/// schedule, and the next step starts.
+///
+/// Every walker owns a clone of the move generator and its own RNG, while the
+/// mapper is shared by reference. Pins, external biases and the other
+/// diagnostics of [`WLDriver`](crate::driver::WLDriver) are not supported.
+///
+/// # Expected speedup
+///
+/// Proposals run fully in parallel, so with `N` threads a stage completes in
+/// close to `1/N` of the single-walker wall time. Two costs eat into this:
+/// the barrier at the end of each step, which is amortized by choosing a
+/// large [`Params::sweep_len`] (thousands of proposals), and contention on
+/// the atomics, which grows when few bins are shared by many threads. Expect
+/// near-linear speedup for expensive moves over many bins and little or none
+/// for toy models such as a die.
+///
+/// # Example
+///
+/// ```no_run
+/// use wanglandau::parallel::SharedWalker;
+/// use wanglandau::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Coin(bool);
+/// impl State for Coin {}
+///
+/// #[derive(Clone)]
+/// struct Flip;
+/// impl<R: rand::RngCore> Move<Coin, R> for Flip {
+///     fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+///         use rand::Rng;
+///         s.0 = rng.random();
+///     }
+/// }
+///
+/// struct CoinMapper;
+/// impl Macrospace<Coin> for CoinMapper {
+///     type Bin = usize;
+///     fn locate(&self, s: &Coin) -> usize { s.0 as usize }
+///     fn bins(&self) -> &[usize] { &[0, 1] }
+/// }
+///
+/// let params = Params { sweep_len: 10_000, ..Default::default() };
+/// let mut walkers = SharedWalker::new(
+///     vec![Coin(false); 4],
+///     Flip,
+///     CoinMapper,
+///     params,
+///     Geometric { alpha: 0.5, tol: 1e-8 },
+///     Fraction,
+///     (0..4).map(wanglandau::rng::seeded).collect(),
+/// );
+/// walkers.run(1_000_000);
+/// println!("{:?}", walkers.ln_g());
+/// ```
+#[allow(clippy::type_complexity)]
+pub struct SharedWalker<
+    S,
+    Mv,
+    Map,
+    R = Rng64,
+    Sch = crate::schedule::Geometric,
+    F = crate::flatness::Fraction,
+> where
+    S: State + Send,
+    Mv: Move<S, R> + Clone + Send,
+    Map: Macrospace<S> + Sync,
+    R: RngCore + Send,
+    Sch: Schedule,
+    F: Flatness,
+{
+    /// The walkers, one per thread
+    walkers: Vec<Walker<S, Mv, R>>,
+
+    /// Maps states to macroscopic bins
+    mapper: Map,
+
+    /// Extent of each bin dimension, used to flatten bins
+    shape: Vec<usize>,
+
+    /// ln(g) and histogram shared by every walker
+    dos: SharedDos,
+
+    /// Current modification factor (ln f)
+    ln_f: f64,
+
+    /// Algorithm parameters
+    params: Params,
+
+    /// Modification factor update schedule
+    sched: Sch,
+
+    /// Histogram flatness criterion
+    flat: F,
+
+    /// Current step count
+    step: u64,
+
+    /// Whether the schedule has reported convergence
+    converged: bool,
+}
+
+impl<S, Mv, Map, R, Sch, F> SharedWalker<S, Mv, Map, R, Sch, F>
+where
+    S: State + Send,
+    Mv: Move<S, R> + Clone + Send,
+    Map: Macrospace<S> + Sync,
+    R: RngCore + Send,
+    Sch: Schedule,
+    F: Flatness,
+{
+    /// Creates a set of walkers sharing one ln(g).
+    ///
+    /// # Parameters
+    ///
+    /// * `states` - The initial state of each walker
+    /// * `moves` - The move proposal generator, cloned for every walker
+    /// * `mapper` - The state-to-bin mapping
+    /// * `params` - The algorithm parameters
+    /// * `sched` - The modification factor update schedule
+    /// * `flat` - The histogram flatness criterion
+    /// * `rngs` - One independent random number generator per walker
+    ///
+    /// # Returns
+    ///
+    /// A new `SharedWalker` with zeroed ln(g) and histogram
+    ///
+    /// # Panics
+    ///
+    /// Panics if `states` is empty or `rngs` has a different length.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        states: Vec<S>,
+        moves: Mv,
+        mapper: Map,
+        params: Params,
+        sched: Sch,
+        flat: F,
+        rngs: Vec<R>,
+    ) -> Self {
+        assert!(!states.is_empty(), "at least one walker is required");
+        assert_eq!(states.len(), rngs.len(), "need one RNG per walker");
+        let shape = mapper.shape();
+        let n_bins = shape.iter().product();
+        let walkers = states
+            .into_iter()
+            .zip(rngs)
+            .map(|(state, rng)| Walker {
+                bin: mapper.locate(&state).to_flat(&shape),
+                state,
+                moves: moves.clone(),
+                rng,
+            })
+            .collect();
+        Self {
+            walkers,
+            mapper,
+            shape,
+            dos: SharedDos {
+                ln_g: (0..n_bins)
+                    .map(|_| AtomicU64::new(0f64.to_bits()))
+                    .collect(),
+                hist: (0..n_bins).map(|_| AtomicU64::new(0)).collect(),
+            },
+            ln_f: params.ln_f0,
+            params,
+            sched,
+            flat,
+            step: 0,
+            converged: false,
+        }
+    }
+
+    /// Runs the walkers on one thread each for up to `max_steps` steps or
+    /// until convergence.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The maximum number of steps to perform
+    pub fn run(&mut self, max_steps: u64) {
+        let Self {
+            walkers,
+            mapper,
+            shape,
+            dos,
+            ..
+        } = self;
+        let (mapper, shape, dos) = (&*mapper, shape.as_slice(), &*dos);
+        let sweep_len = self.params.sweep_len;
+
+        let ln_f = AtomicU64::new(self.ln_f.to_bits());
+        let stop = AtomicBool::new(false);
+        let barrier = Barrier::new(walkers.len() + 1);
+
+        thread::scope(|scope| {
+            for walker in walkers.iter_mut() {
+                let (ln_f, stop, barrier) = (&ln_f, &stop, &barrier);
+                scope.spawn(move || loop {
+                    barrier.wait();
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let ln_f = f64::from_bits(ln_f.load(Ordering::Relaxed));
+                    sweep(walker, mapper, shape, dos, ln_f, sweep_len);
+                    barrier.wait();
+                });
+            }
+
+            for _ in 0..max_steps {
+                if self.converged {
+                    break;
+                }
+                barrier.wait();
+                barrier.wait();
+                Self::end_step(
+                    dos,
+                    &self.flat,
+                    &mut self.sched,
+                    &self.params,
+                    &mut self.ln_f,
+                    &mut self.step,
+                    &mut self.converged,
+                );
+                ln_f.store(self.ln_f.to_bits(), Ordering::Relaxed);
+            }
+            stop.store(true, Ordering::Relaxed);
+            barrier.wait();
+        });
+    }
+
+    /// Runs the same algorithm deterministically on the calling thread.
+    ///
+    /// Within each step the walkers perform their sweeps one after another
+    /// in index order. With seeded RNGs the result is reproducible, which
+    /// makes this the fallback for testing and debugging; the outcome differs
+    /// from a threaded [`run`](Self::run), where sweeps interleave.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The maximum number of steps to perform
+    pub fn run_serial(&mut self, max_steps: u64) {
+        for _ in 0..max_steps {
+            if self.converged {
+                break;
+            }
+            for walker in self.walkers.iter_mut() {
+                sweep(
+                    walker,
+                    &self.mapper,
+                    &self.shape,
+                    &self.dos,
+                    self.ln_f,
+                    self.params.sweep_len,
+                );
+            }
+            Self::end_step(
+                &self.dos,
+                &self.flat,
+                &mut self.sched,
+                &self.params,
+                &mut self.ln_f,
+                &mut self.step,
+                &mut self.converged,
+            );
+        }
+    }
+
+    /// Checks the shared histogram after a step and advances the schedule.
+    fn end_step(
+        dos: &SharedDos,
+        flat: &F,
+        sched: &mut Sch,
+        params: &Params,
+        ln_f: &mut f64,
+        step: &mut u64,
+        converged: &mut bool,
+    ) {
+        if flat.is_flat(&dos.histogram(), params.flatness) {
+            for h in &dos.hist {
+                h.store(0, Ordering::Relaxed);
+            }
+            sched.on_flat(*step);
+            if sched.update(ln_f) {
+                *converged = true;
+                return;
+            }
+        }
+        *step += 1;
+    }
+
+    /// Returns a copy of the shared ln(g) estimate.
+    ///
+    /// # Returns
+    ///
+    /// The ln(g) value of each bin
+    pub fn ln_g(&self) -> Vec<f64> {
+        (0..self.dos.ln_g.len()).map(|b| self.dos.ln_g(b)).collect()
+    }
+
+    /// Returns a copy of the shared histogram.
+    ///
+    /// # Returns
+    ///
+    /// The visit counts of all walkers in the current stage
+    pub fn histogram(&self) -> Vec<u64> {
+        self.dos.histogram()
+    }
+
+    /// Returns the current modification factor (ln f).
+    ///
+    /// # Returns
+    ///
+    /// The current ln_f value
+    pub fn ln_f(&self) -> f64 {
+        self.ln_f
+    }
+
+    /// Returns whether the schedule has reported convergence.
+    ///
+    /// # Returns
+    ///
+    /// `true` once ln_f has dropped below the schedule's tolerance
+    pub fn is_converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Returns the number of walkers.
+    ///
+    /// # Returns
+    ///
+    /// The number of walkers, and threads used by [`run`](Self::run)
+    pub fn n_walkers(&self) -> usize {
+        self.walkers.len()
+    }
+
+    /// Returns the current state of a walker.
+    ///
+    /// # Parameters
+    ///
+    /// * `walker` - The walker index
+    ///
+    /// # Returns
+    ///
+    /// A reference to the walker's state
+    pub fn state(&self, walker: usize) -> &S {
+        &self.walkers[walker].state
+    }
+
+    /// Returns the number of steps performed so far.
+    ///
+    /// # Returns
+    ///
+    /// The current step count
+    pub fn step_count(&self) -> u64 {
+        self.step
+    }
+}
+
+/// Performs `sweep_len` proposals for one walker against the shared ln(g).
+fn sweep<S, Mv, Map, R>(
+    walker: &mut Walker<S, Mv, R>,
+    mapper: &Map,
+    shape: &[usize],
+    dos: &SharedDos,
+    ln_f: f64,
+    sweep_len: usize,
+) where
+    S: State,
+    Mv: Move<S, R>,
+    Map: Macrospace<S>,
+    R: RngCore,
+{
+    for _ in 0..sweep_len {
+        let prev_state = walker.state.clone();
+        let ln_ratio = walker
+            .moves
+            .propose_biased(&mut walker.state, &mut walker.rng);
+        let bin_new = mapper.locate(&walker.state).to_flat(shape);
+
+        let same_bin = bin_new == walker.bin;
+        // ln(g) is only read for moves between bins
+        let (ln_old, ln_new) = if same_bin {
+            (0.0, 0.0)
+        } else {
+            (dos.ln_g(walker.bin), dos.ln_g(bin_new))
+        };
+        if accept(ln_old, ln_new, ln_ratio, same_bin, &mut walker.rng) {
+            walker.bin = bin_new;
+        } else {
+            walker.state = prev_state;
+        }
+
+        dos.add_ln_g(walker.bin, ln_f);
+        dos.hist[walker.bin].fetch_add(1, Ordering::Relaxed);
+    }
+}
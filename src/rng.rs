@@ -7,7 +7,9 @@
 //! The PCG-64 algorithm is used as the default RNG due to its excellent
 //! statistical properties and performance.
 
-use rand::SeedableRng;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rand::{RngCore, SeedableRng};
 
 /// Default random number generator used by the Wang-Landau driver.
 ///
@@ -33,7 +35,7 @@ pub type Rng64 = rand_pcg::Pcg64;
 /// ```
 /// use wanglandau::prelude::*;
 /// use wanglandau::rng::seeded;
-/// use rand::SeedableRng;
+/// use rand::{RngCore, SeedableRng};
 ///
 /// // Create a seeded RNG for reproducible results
 /// let rng = seeded(42);
@@ -44,3 +46,478 @@ pub type Rng64 = rand_pcg::Pcg64;
 pub fn seeded(seed: u64) -> Rng64 {
     Rng64::seed_from_u64(seed)
 }
+
+/// Creates a PCG-64 random number generator seeded from a string label.
+///
+/// The label is hashed with 64-bit FNV-1a (offset basis
+/// `0xcbf29ce484222325`, prime `0x100000001b3`) over its UTF-8 bytes, and the
+/// hash is used as the seed for [`seeded`]. FNV-1a is a fixed, documented
+/// function, so a label maps to the same stream on every platform and crate
+/// version. This keeps run provenance human-readable while staying
+/// reproducible.
+///
+/// # Parameters
+///
+/// * `label` - A human-readable run label
+///
+/// # Returns
+///
+/// A PCG-64 random number generator seeded from the label
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::rng::seeded_from_str;
+/// use rand::Rng;
+///
+/// let mut a = seeded_from_str("run-ising-L32-T2.5");
+/// let mut b = seeded_from_str("run-ising-L32-T2.5");
+/// assert_eq!(a.random::<u64>(), b.random::<u64>());
+/// ```
+pub fn seeded_from_str(label: &str) -> Rng64 {
+    seeded(fnv1a64(label.as_bytes()))
+}
+
+/// 64-bit FNV-1a hash.
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(PRIME)
+    })
+}
+
+/// Hands out distinct seeds for an ensemble of walkers.
+///
+/// Seed `i` is the SplitMix64 output for position `i` of the stream started
+/// at the base seed: the state `base + (i + 1) · 0x9e3779b97f4a7c15` is
+/// passed through the SplitMix64 finalizer. The increment is odd and the
+/// finalizer is a bijection on `u64`, so the seeds of different indices
+/// never collide, and they are a fixed function of the base seed on every
+/// platform. Processes sharing a base seed stay independent as long as they
+/// use disjoint index ranges, e.g. one range per node.
+///
+/// Within a process the registry can be shared between threads:
+/// [`next_seed`](Self::next_seed) and [`batch`](Self::batch) reserve indices
+/// from an atomic cursor, so concurrent callers never receive the same seed.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::rng::{seeded, SeedRegistry};
+///
+/// let registry = SeedRegistry::new(2025);
+/// let seeds = registry.batch(4);
+/// assert_eq!(seeds[2], registry.seed(2));
+///
+/// // The next reservation continues after the batch
+/// assert_eq!(registry.next_seed(), registry.seed(4));
+///
+/// let rngs: Vec<_> = seeds.into_iter().map(seeded).collect();
+/// # assert_eq!(rngs.len(), 4);
+/// ```
+#[derive(Debug)]
+pub struct SeedRegistry {
+    /// The seed the stream of seeds is derived from
+    base: u64,
+
+    /// The next index handed out by `next_seed` and `batch`
+    cursor: AtomicU64,
+}
+
+impl SeedRegistry {
+    /// The SplitMix64 increment, 2⁶⁴ divided by the golden ratio
+    const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    /// Creates a registry deriving its seeds from a base seed.
+    ///
+    /// # Parameters
+    ///
+    /// * `base` - The base seed
+    ///
+    /// # Returns
+    ///
+    /// A registry whose cursor starts at index zero
+    pub fn new(base: u64) -> Self {
+        Self {
+            base,
+            cursor: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the seed of an index, without reserving it.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The position in the stream of seeds
+    ///
+    /// # Returns
+    ///
+    /// The seed derived from the base seed and `index`
+    pub fn seed(&self, index: u64) -> u64 {
+        let mut z = self
+            .base
+            .wrapping_add(index.wrapping_add(1).wrapping_mul(Self::GAMMA));
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Reserves the next index and returns its seed.
+    ///
+    /// # Returns
+    ///
+    /// A seed no other reservation from this registry returns
+    pub fn next_seed(&self) -> u64 {
+        self.seed(self.cursor.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Reserves the next `n` indices and returns their seeds.
+    ///
+    /// # Parameters
+    ///
+    /// * `n` - The number of seeds
+    ///
+    /// # Returns
+    ///
+    /// `n` distinct seeds, in index order
+    pub fn batch(&self, n: usize) -> Vec<u64> {
+        let start = self.cursor.fetch_add(n as u64, Ordering::Relaxed);
+        (start..start + n as u64).map(|i| self.seed(i)).collect()
+    }
+}
+
+/// A random number generator that keeps count of its draws.
+///
+/// Implemented by the wrappers in this module, so that reproducibility
+/// tooling can ask any of them how much randomness a run consumed, e.g. via
+/// [`WLDriver::rng_draws`](crate::driver::WLDriver::rng_draws).
+///
+/// # Example
+///
+/// ```
+/// use rand::RngCore;
+/// use wanglandau::rng::{seeded, Budgeted, CountingRng};
+///
+/// let mut rng = Budgeted::new(seeded(3));
+/// rng.next_u64();
+/// assert_eq!(rng.draws_so_far(), 1);
+/// ```
+pub trait CountingRng: RngCore {
+    /// Returns the number of draws made so far.
+    ///
+    /// # Returns
+    ///
+    /// The number of values drawn, as counted by the implementation
+    fn draws_so_far(&self) -> u64;
+}
+
+/// A scripted "random" number generator for tests.
+///
+/// Yields a user-supplied sequence of `u64` values in order, which makes the
+/// driver's behavior fully scriptable: tests can force specific move
+/// proposals and acceptance decisions. [`next_u32`](RngCore::next_u32) takes
+/// the upper half of the next value, and [`fill_bytes`](RngCore::fill_bytes)
+/// consumes values as little-endian bytes.
+///
+/// Use [`from_unit_f64`](Self::from_unit_f64) to script uniform draws: rand's
+/// `random::<f64>()` returns the given values (truncated to 53 bits). At the
+/// end of the sequence the generator either wraps around (the default) or,
+/// with [`strict`](Self::strict), panics so a test notices unexpected draws.
+///
+/// # Example
+///
+/// ```
+/// use rand::Rng;
+/// use wanglandau::rng::DeterministicSequence;
+///
+/// let mut rng = DeterministicSequence::from_unit_f64(&[0.25, 0.75]);
+/// assert_eq!(rng.random::<f64>(), 0.25);
+/// assert_eq!(rng.random::<f64>(), 0.75);
+/// assert_eq!(rng.random::<f64>(), 0.25); // wrapped around
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeterministicSequence {
+    /// The scripted values
+    values: Vec<u64>,
+
+    /// Index of the next value
+    pos: usize,
+
+    /// Whether to panic instead of wrapping at the end
+    strict: bool,
+}
+
+impl DeterministicSequence {
+    /// Creates a generator yielding `values` in order.
+    ///
+    /// # Parameters
+    ///
+    /// * `values` - The raw values returned by [`next_u64`](RngCore::next_u64)
+    ///
+    /// # Returns
+    ///
+    /// A generator that wraps around at the end of `values`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` is empty.
+    pub fn new(values: Vec<u64>) -> Self {
+        assert!(!values.is_empty(), "the sequence needs at least one value");
+        Self {
+            values,
+            pos: 0,
+            strict: false,
+        }
+    }
+
+    /// Creates a generator whose uniform `f64` draws are the given values.
+    ///
+    /// Each value in `[0, 1)` is encoded so that rand's standard `f64`
+    /// sampling, which uses the top 53 bits of a `u64`, returns it.
+    ///
+    /// # Parameters
+    ///
+    /// * `draws` - The uniform draws to produce, each in `[0, 1)`
+    ///
+    /// # Returns
+    ///
+    /// A generator that wraps around at the end of `draws`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `draws` is empty or a value lies outside `[0, 1)`.
+    pub fn from_unit_f64(draws: &[f64]) -> Self {
+        let values = draws
+            .iter()
+            .map(|&x| {
+                assert!((0.0..1.0).contains(&x), "draw {} is not in [0, 1)", x);
+                ((x * (1u64 << 53) as f64) as u64) << 11
+            })
+            .collect();
+        Self::new(values)
+    }
+
+    /// Makes the generator panic once the sequence is exhausted.
+    ///
+    /// # Returns
+    ///
+    /// The generator in strict mode
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Returns how many values have been consumed.
+    ///
+    /// # Returns
+    ///
+    /// The number of `u64` values drawn so far
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+impl CountingRng for DeterministicSequence {
+    fn draws_so_far(&self) -> u64 {
+        self.pos as u64
+    }
+}
+
+impl RngCore for DeterministicSequence {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let len = self.values.len();
+        if self.strict && self.pos >= len {
+            panic!("deterministic sequence exhausted after {} values", len);
+        }
+        let value = self.values[self.pos % len];
+        self.pos += 1;
+        value
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// What a [`Budgeted`] generator does once its cap is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnExhausted {
+    /// Panic on the first draw past the cap
+    Panic,
+
+    /// Return this fixed value for every draw past the cap
+    Saturate(u64),
+}
+
+/// Wraps a generator, counting its draws and optionally capping them.
+///
+/// Each call to [`next_u32`](RngCore::next_u32) or
+/// [`next_u64`](RngCore::next_u64) counts as one draw, and
+/// [`fill_bytes`](RngCore::fill_bytes) counts one draw per started 8-byte
+/// word. Draws are passed to the inner generator until the cap is reached;
+/// after that the generator panics or returns a fixed value, as selected by
+/// [`OnExhausted`]. Giving two benchmark runs the same saturating cap makes
+/// them consume exactly the same randomness, however long each takes to
+/// converge.
+///
+/// # Example
+///
+/// ```
+/// use rand::RngCore;
+/// use wanglandau::rng::{seeded, Budgeted, OnExhausted};
+///
+/// let mut rng = Budgeted::new(seeded(1)).with_cap(2, OnExhausted::Saturate(7));
+/// rng.next_u64();
+/// rng.next_u32();
+/// assert!(rng.is_exhausted());
+/// assert_eq!(rng.next_u64(), 7);
+/// assert_eq!(rng.draws(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Budgeted<R> {
+    /// The wrapped generator
+    inner: R,
+
+    /// Draws requested so far
+    draws: u64,
+
+    /// Maximum number of draws passed to `inner`
+    cap: Option<u64>,
+
+    /// Behavior past the cap
+    on_exhausted: OnExhausted,
+}
+
+impl<R: RngCore> Budgeted<R> {
+    /// Wraps a generator without a cap, only counting draws.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The generator to delegate to
+    ///
+    /// # Returns
+    ///
+    /// A counting wrapper around `inner`
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            draws: 0,
+            cap: None,
+            on_exhausted: OnExhausted::Panic,
+        }
+    }
+
+    /// Caps the number of draws passed to the inner generator.
+    ///
+    /// # Parameters
+    ///
+    /// * `cap` - The maximum number of draws
+    /// * `on_exhausted` - What to do with draws past the cap
+    ///
+    /// # Returns
+    ///
+    /// The wrapper with the cap set
+    pub fn with_cap(mut self, cap: u64, on_exhausted: OnExhausted) -> Self {
+        self.cap = Some(cap);
+        self.on_exhausted = on_exhausted;
+        self
+    }
+
+    /// Returns the number of draws requested so far.
+    ///
+    /// # Returns
+    ///
+    /// The draw count, including any past the cap
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    /// Returns the number of draws left before the cap.
+    ///
+    /// # Returns
+    ///
+    /// The remaining budget, or `None` without a cap
+    pub fn remaining(&self) -> Option<u64> {
+        self.cap.map(|cap| cap.saturating_sub(self.draws))
+    }
+
+    /// Returns whether the budget has been used up.
+    ///
+    /// # Returns
+    ///
+    /// `true` once the draw count has reached the cap
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == Some(0)
+    }
+
+    /// Unwraps the inner generator.
+    ///
+    /// # Returns
+    ///
+    /// The wrapped generator in its current state
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Counts one draw and returns whether it is within the budget.
+    fn spend(&mut self) -> bool {
+        let within = self.cap.is_none_or(|cap| self.draws < cap);
+        self.draws += 1;
+        if !within && self.on_exhausted == OnExhausted::Panic {
+            panic!("random draw budget of {} exhausted", self.cap.unwrap());
+        }
+        within
+    }
+
+    /// The value returned for a draw past the cap.
+    fn saturated(&self) -> u64 {
+        match self.on_exhausted {
+            OnExhausted::Saturate(value) => value,
+            OnExhausted::Panic => unreachable!("panicking budgets never saturate"),
+        }
+    }
+}
+
+impl<R: RngCore> CountingRng for Budgeted<R> {
+    fn draws_so_far(&self) -> u64 {
+        self.draws
+    }
+}
+
+impl<R: RngCore> RngCore for Budgeted<R> {
+    fn next_u32(&mut self) -> u32 {
+        if self.spend() {
+            self.inner.next_u32()
+        } else {
+            (self.saturated() >> 32) as u32
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        if self.spend() {
+            self.inner.next_u64()
+        } else {
+            self.saturated()
+        }
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            if self.spend() {
+                self.inner.fill_bytes(chunk);
+            } else {
+                let bytes = self.saturated().to_le_bytes();
+                chunk.copy_from_slice(&bytes[..chunk.len()]);
+            }
+        }
+    }
+}
@@ -4,10 +4,15 @@
 //! controlling how the modification factor (`ln_f`) changes during
 //! Wang-Landau sampling.
 //!
-//! Two common schedules are provided:
+//! The following schedules are provided:
 //!
 //! - [`Geometric`]: Reduces ln_f by a constant factor (e.g., ln_f *= 0.5)
+//! - [`AdaptiveGeometric`]: A geometric schedule whose factor tracks stage durations
 //! - [`OneOverT`]: Uses the Belardinelli-Pereyra 1/t schedule
+//! - [`Then`]: Runs one schedule until ln_f drops below a threshold, then another
+//! - [`Tabulated`]: Steps through a precomputed sequence of ln_f values
+//! - [`BudgetGeometric`]: Spreads a fixed number of geometric reductions
+//!   over a total step budget
 //!
 //! Custom schedules can be implemented by implementing the [`Schedule`] trait.
 
@@ -41,6 +46,7 @@ use crate::traits::Schedule;
 /// // converged will be true when ln_f < 1e-8
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Geometric {
     /// Factor by which ln_f is multiplied (typically 0.5)
     pub alpha: f64,
@@ -54,6 +60,185 @@ impl Schedule for Geometric {
         *ln_f *= self.alpha;
         *ln_f < self.tol
     }
+
+    fn tolerance(&self) -> Option<f64> {
+        Some(self.tol)
+    }
+
+    fn remaining_updates(&self, ln_f: f64) -> Option<u32> {
+        geometric_remaining(ln_f, self.alpha, self.tol)
+    }
+}
+
+/// Number of multiplications by `alpha` needed to take `ln_f` below `tol`.
+///
+/// This is `ceil(ln(tol / ln_f) / ln(alpha))`, bumped by one when `ln_f`
+/// would land exactly on `tol`, since convergence requires `ln_f < tol`.
+fn geometric_remaining(ln_f: f64, alpha: f64, tol: f64) -> Option<u32> {
+    let valid = alpha > 0.0 && alpha < 1.0 && tol > 0.0 && ln_f > 0.0;
+    if !valid {
+        return None;
+    }
+    if ln_f < tol {
+        return Some(0);
+    }
+    let mut k = ((tol / ln_f).ln() / alpha.ln()).ceil().max(1.0) as u32;
+    if ln_f * alpha.powi(k as i32) >= tol {
+        k += 1;
+    }
+    Some(k)
+}
+
+/// A geometric schedule whose factor adapts to how long each stage takes.
+///
+/// If a stage takes much longer to flatten than the previous one, the
+/// modification factor was probably reduced too aggressively. This schedule
+/// watches stage durations through [`Schedule::on_flat`] and nudges `alpha`:
+///
+/// - when a stage is longer than the previous one, `alpha` moves a fraction
+///   `rate` of the way towards `alpha_max` (slower reduction);
+/// - when a stage is shorter, `alpha` moves a fraction `rate` of the way
+///   towards `alpha_min` (faster reduction);
+/// - stages of equal length leave `alpha` unchanged.
+///
+/// Every adjustment is a convex combination with one of the bounds, and
+/// `alpha` is clamped into `[alpha_min, alpha_max]` before each use, so it
+/// stays within them even if the public fields were set by hand. The first
+/// stage only establishes a reference duration.
+///
+/// # Panics
+///
+/// [`on_flat`](Schedule::on_flat) and [`update`](Schedule::update) panic
+/// unless `0 < alpha_min ≤ alpha_max < 1` and `0 ≤ rate ≤ 1`.
+///
+/// # Fields
+///
+/// * `alpha` - The current factor by which ln_f is multiplied
+/// * `tol` - The convergence tolerance for ln_f
+/// * `rate` - The fraction of the distance to a bound covered per adjustment
+/// * `alpha_min` - The lower bound for `alpha`
+/// * `alpha_max` - The upper bound for `alpha`
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::prelude::*;
+///
+/// let mut ln_f = 1.0;
+/// let mut schedule = AdaptiveGeometric::new(0.5, 1e-8);
+///
+/// // First stage took 100 steps, the second one 400
+/// schedule.on_flat(100);
+/// schedule.update(&mut ln_f);
+/// schedule.on_flat(500);
+/// schedule.update(&mut ln_f);
+///
+/// // The longer stage made the schedule back off
+/// assert!(schedule.alpha > 0.5);
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveGeometric {
+    /// Current factor by which ln_f is multiplied
+    pub alpha: f64,
+
+    /// Convergence tolerance for ln_f
+    pub tol: f64,
+
+    /// Fraction of the distance to a bound covered per adjustment
+    pub rate: f64,
+
+    /// Lower bound for alpha
+    pub alpha_min: f64,
+
+    /// Upper bound for alpha
+    pub alpha_max: f64,
+
+    /// Step of the previous flat event
+    last_flat: u64,
+
+    /// Duration of the previous stage, once known
+    last_len: Option<u64>,
+}
+
+impl AdaptiveGeometric {
+    /// Creates an adaptive schedule with default adaptation settings.
+    ///
+    /// The defaults are `rate = 0.1`, `alpha_min = 0.1` and `alpha_max = 0.95`.
+    /// The initial `alpha` is clamped into those bounds.
+    ///
+    /// # Parameters
+    ///
+    /// * `alpha` - The initial reduction factor
+    /// * `tol` - The convergence tolerance for ln_f
+    ///
+    /// # Returns
+    ///
+    /// A new `AdaptiveGeometric` schedule
+    pub fn new(alpha: f64, tol: f64) -> Self {
+        let (alpha_min, alpha_max) = (0.1, 0.95);
+        Self {
+            alpha: alpha.clamp(alpha_min, alpha_max),
+            tol,
+            rate: 0.1,
+            alpha_min,
+            alpha_max,
+            last_flat: 0,
+            last_len: None,
+        }
+    }
+
+    /// Checks the adaptation settings and clamps `alpha` into its bounds.
+    fn clamp_alpha(&mut self) {
+        assert!(
+            0.0 < self.alpha_min && self.alpha_min <= self.alpha_max && self.alpha_max < 1.0,
+            "alpha bounds [{}, {}] must satisfy 0 < alpha_min <= alpha_max < 1",
+            self.alpha_min,
+            self.alpha_max
+        );
+        assert!(
+            (0.0..=1.0).contains(&self.rate),
+            "adaptation rate {} must lie in [0, 1]",
+            self.rate
+        );
+        self.alpha = self.alpha.clamp(self.alpha_min, self.alpha_max);
+    }
+}
+
+impl Schedule for AdaptiveGeometric {
+    fn update(&mut self, ln_f: &mut f64) -> bool {
+        self.clamp_alpha();
+        *ln_f *= self.alpha;
+        *ln_f < self.tol
+    }
+
+    fn on_flat(&mut self, step: u64) {
+        self.clamp_alpha();
+        let len = step.saturating_sub(self.last_flat);
+        if let Some(prev) = self.last_len {
+            if len > prev {
+                self.alpha += self.rate * (self.alpha_max - self.alpha);
+            } else if len < prev {
+                self.alpha -= self.rate * (self.alpha - self.alpha_min);
+            }
+        }
+        self.last_len = Some(len);
+        self.last_flat = step;
+    }
+
+    fn tolerance(&self) -> Option<f64> {
+        Some(self.tol)
+    }
+
+    fn remaining_updates(&self, ln_f: f64) -> Option<u32> {
+        // An estimate: assumes alpha stays at its current value
+        geometric_remaining(ln_f, self.alpha, self.tol)
+    }
+
+    fn reset_progress(&mut self, _ln_f: f64) {
+        // Forget the previous stage so the next one sets a fresh baseline
+        self.last_len = None;
+    }
 }
 
 /// A 1/t schedule for ln_f, following the Belardinelli-Pereyra algorithm.
@@ -88,6 +273,7 @@ impl Schedule for Geometric {
 /// // converged will be true when ln_f < tol (default 1e-8)
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OneOverT {
     /// Internal time step counter
     t: u64,
@@ -108,4 +294,330 @@ impl Schedule for OneOverT {
         *ln_f = 1.0 / self.t as f64;
         *ln_f < self.tol
     }
+
+    fn tolerance(&self) -> Option<f64> {
+        Some(self.tol)
+    }
+
+    fn remaining_updates(&self, _ln_f: f64) -> Option<u32> {
+        let valid = self.tol > 0.0;
+        if !valid {
+            return None;
+        }
+        // Convergence at the first t with 1/t < tol, checked in floating
+        // point exactly as `update` does
+        let mut target = (1.0 / self.tol).floor().max(1.0) as u64;
+        while target > 1 && 1.0 / ((target - 1) as f64) < self.tol {
+            target -= 1;
+        }
+        while 1.0 / (target as f64) >= self.tol {
+            target += 1;
+        }
+        u32::try_from(target.saturating_sub(self.t)).ok()
+    }
+
+    fn reset_progress(&mut self, ln_f: f64) {
+        // Restart the clock where 1/t equals the current ln_f
+        if ln_f > 0.0 {
+            self.t = (1.0 / ln_f).round().max(1.0) as u64;
+        }
+    }
+}
+
+/// Chains two schedules, switching once ln_f falls below a threshold.
+///
+/// Updates are delegated to `first` until an update leaves ln_f below
+/// `switch_at`; from then on every update goes to `second`. The switch is a
+/// one-time transition and is never undone. Convergence is decided by
+/// `second` alone: the convergence flag of `first` is ignored, so its
+/// tolerance only needs to be no larger than `switch_at`.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::prelude::*;
+/// use wanglandau::schedule::Then;
+///
+/// // Halve ln_f down to 0.1, then refine slowly
+/// let mut schedule = Then::new(
+///     Geometric { alpha: 0.5, tol: 0.0 },
+///     Geometric { alpha: 0.9, tol: 1e-8 },
+///     0.1,
+/// );
+///
+/// let mut ln_f = 0.15;
+/// schedule.update(&mut ln_f);
+/// assert_eq!(ln_f, 0.075); // first schedule, now below 0.1
+/// assert!(schedule.switched());
+///
+/// schedule.update(&mut ln_f);
+/// assert!((ln_f - 0.0675).abs() < 1e-15); // second schedule
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Then<A, B> {
+    /// Schedule used while ln_f is at or above `switch_at`
+    pub first: A,
+
+    /// Schedule used after the switch
+    pub second: B,
+
+    /// ln_f below which control passes to `second`
+    pub switch_at: f64,
+
+    /// Whether control has passed to `second`
+    switched: bool,
+}
+
+impl<A: Schedule, B: Schedule> Then<A, B> {
+    /// Creates a chained schedule.
+    ///
+    /// # Parameters
+    ///
+    /// * `first` - The schedule used until the switch
+    /// * `second` - The schedule used after the switch
+    /// * `switch_at` - The ln_f below which control passes to `second`
+    ///
+    /// # Returns
+    ///
+    /// A new `Then` delegating to `first`
+    pub fn new(first: A, second: B, switch_at: f64) -> Self {
+        Self {
+            first,
+            second,
+            switch_at,
+            switched: false,
+        }
+    }
+
+    /// Returns whether control has passed to the second schedule.
+    ///
+    /// # Returns
+    ///
+    /// `true` once an update has left ln_f below `switch_at`
+    pub fn switched(&self) -> bool {
+        self.switched
+    }
+}
+
+impl<A: Schedule, B: Schedule> Schedule for Then<A, B> {
+    fn tolerance(&self) -> Option<f64> {
+        self.second.tolerance()
+    }
+
+    fn remaining_updates(&self, ln_f: f64) -> Option<u32> {
+        // Unknown until the switch, then the second schedule's estimate
+        if self.switched {
+            self.second.remaining_updates(ln_f)
+        } else {
+            None
+        }
+    }
+
+    fn update(&mut self, ln_f: &mut f64) -> bool {
+        if self.switched {
+            return self.second.update(ln_f);
+        }
+        self.first.update(ln_f);
+        if *ln_f < self.switch_at {
+            self.switched = true;
+        }
+        false
+    }
+
+    fn reset_progress(&mut self, ln_f: f64) {
+        if self.switched {
+            self.second.reset_progress(ln_f);
+        } else {
+            self.first.reset_progress(ln_f);
+        }
+    }
+
+    fn on_flat(&mut self, step: u64) {
+        if self.switched {
+            self.second.on_flat(step);
+        } else {
+            self.first.on_flat(step);
+        }
+    }
+}
+
+/// A schedule that steps through a precomputed table of ln_f values.
+///
+/// Each update sets ln_f to the next entry of `values`, so a published run
+/// can be reproduced exactly. The update that takes the last entry reports
+/// convergence; further updates leave ln_f unchanged and keep reporting it.
+///
+/// # Fields
+///
+/// * `values` - The ln_f values, in the order they are applied
+/// * `idx` - The index of the next value to apply
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::schedule::Tabulated;
+/// use wanglandau::prelude::*;
+///
+/// let mut schedule = Tabulated::new(vec![0.5, 0.1]);
+/// let mut ln_f = 1.0;
+///
+/// assert!(!schedule.update(&mut ln_f));
+/// assert_eq!(ln_f, 0.5);
+/// assert!(schedule.update(&mut ln_f)); // table exhausted
+/// assert_eq!(ln_f, 0.1);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tabulated {
+    /// ln_f values in the order they are applied
+    pub values: Vec<f64>,
+
+    /// Index of the next value to apply
+    pub idx: usize,
+}
+
+impl Tabulated {
+    /// Creates a schedule over a table of ln_f values.
+    ///
+    /// # Parameters
+    ///
+    /// * `values` - The ln_f values, in the order they are applied
+    ///
+    /// # Returns
+    ///
+    /// A new `Tabulated` positioned at the first value
+    pub fn new(values: Vec<f64>) -> Self {
+        Self { values, idx: 0 }
+    }
+}
+
+impl Schedule for Tabulated {
+    fn update(&mut self, ln_f: &mut f64) -> bool {
+        if let Some(&next) = self.values.get(self.idx) {
+            *ln_f = next;
+            self.idx += 1;
+        }
+        self.idx >= self.values.len()
+    }
+
+    fn remaining_updates(&self, _ln_f: f64) -> Option<u32> {
+        Some(self.values.len().saturating_sub(self.idx) as u32)
+    }
+}
+
+/// A geometric schedule that spends a fixed total number of steps.
+///
+/// The budget of `total_steps` is divided into `stages` planned stages, and
+/// stage `k` is due to end at step `total_steps · (k / stages)^loading`. At
+/// each flat event the schedule multiplies ln_f by `alpha` once for every
+/// stage boundary the run has passed, using the step count the driver
+/// reports through [`Schedule::on_flat`]. A flat event that comes before the
+/// next boundary leaves ln_f unchanged, so the walker keeps refining at the
+/// current ln_f instead of racing ahead. After the last boundary the run is
+/// converged, at the first flat event at or after `total_steps`, which makes
+/// runs of different methods directly comparable at equal compute.
+///
+/// With `loading = 1` the boundaries are evenly spaced. A larger value
+/// front-loads the reductions, spending most of the budget at small ln_f;
+/// a smaller one back-loads them.
+///
+/// # Fields
+///
+/// * `total_steps` - The step budget of the whole run
+/// * `stages` - The number of planned stages, i.e. of reductions of ln_f
+/// * `alpha` - The factor by which ln_f is multiplied per stage
+/// * `loading` - The exponent shaping where the stage boundaries fall
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::prelude::*;
+/// use wanglandau::schedule::BudgetGeometric;
+///
+/// let mut schedule = BudgetGeometric::new(1000, 4);
+/// let mut ln_f = 1.0;
+///
+/// // Flat at step 100: no boundary passed yet
+/// schedule.on_flat(100);
+/// assert!(!schedule.update(&mut ln_f));
+/// assert_eq!(ln_f, 1.0);
+///
+/// // Flat at step 600: the boundaries at 250 and 500 are passed
+/// schedule.on_flat(600);
+/// assert!(!schedule.update(&mut ln_f));
+/// assert_eq!(ln_f, 0.25);
+///
+/// // Flat at step 1002: the budget is spent
+/// schedule.on_flat(1002);
+/// assert!(schedule.update(&mut ln_f));
+/// assert_eq!(ln_f, 0.0625);
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BudgetGeometric {
+    /// Step budget of the whole run
+    pub total_steps: u64,
+
+    /// Number of planned stages
+    pub stages: u32,
+
+    /// Factor by which ln_f is multiplied per stage
+    pub alpha: f64,
+
+    /// Exponent shaping the stage boundaries (1 for even spacing)
+    pub loading: f64,
+
+    /// Step of the most recent flat event
+    step: u64,
+
+    /// Reductions applied so far
+    done: u32,
+}
+
+impl BudgetGeometric {
+    /// Creates a budgeted schedule with `alpha = 0.5` and even spacing.
+    ///
+    /// # Parameters
+    ///
+    /// * `total_steps` - The step budget of the whole run
+    /// * `stages` - The number of planned stages
+    ///
+    /// # Returns
+    ///
+    /// A new `BudgetGeometric` schedule
+    pub fn new(total_steps: u64, stages: u32) -> Self {
+        Self {
+            total_steps,
+            stages,
+            alpha: 0.5,
+            loading: 1.0,
+            step: 0,
+            done: 0,
+        }
+    }
+
+    /// Returns the number of stage boundaries passed by `step`.
+    fn due(&self, step: u64) -> u32 {
+        if step >= self.total_steps {
+            return self.stages;
+        }
+        let progress = (step as f64 / self.total_steps as f64).powf(1.0 / self.loading);
+        ((self.stages as f64 * progress).floor() as u32).min(self.stages)
+    }
+}
+
+impl Schedule for BudgetGeometric {
+    fn update(&mut self, ln_f: &mut f64) -> bool {
+        let due = self.due(self.step);
+        while self.done < due {
+            *ln_f *= self.alpha;
+            self.done += 1;
+        }
+        self.done >= self.stages
+    }
+
+    fn on_flat(&mut self, step: u64) {
+        self.step = step;
+    }
 }
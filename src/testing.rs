@@ -0,0 +1,76 @@
+//! # Test-support helpers
+//!
+//! Available with the `testing` feature. These helpers capture patterns that
+//! tests of a model repeat, such as running a driver and checking that it
+//! converged, and fail with a message describing the final state of the run.
+
+use crate::driver::WLDriver;
+use crate::float::Float;
+use crate::traits::{Acceptance, Flatness, Macrospace, Move, Schedule, State};
+
+use rand::RngCore;
+
+/// Runs a driver and panics unless ln_f ends up below `tol`.
+///
+/// The panic message reports the final ln_f, the step count and the
+/// coverage, i.e. how many bins were ever visited, which usually tells a
+/// too-small step budget from an unreachable region.
+///
+/// # Parameters
+///
+/// * `driver` - The driver to run
+/// * `max_steps` - The step budget passed to [`WLDriver::run`]
+/// * `tol` - The ln_f the run must end below
+///
+/// # Panics
+///
+/// Panics if ln_f is not below `tol` after the run.
+///
+/// # Example
+///
+/// ```no_run
+/// # use wanglandau::prelude::*;
+/// # #[derive(Clone)] struct S;
+/// # impl State for S {}
+/// # struct Stay;
+/// # impl<R: rand::RngCore> Move<S, R> for Stay { fn propose(&mut self, _: &mut S, _: &mut R) {} }
+/// # struct One;
+/// # impl Macrospace<S> for One { type Bin = usize; fn locate(&self, _: &S) -> usize { 0 } fn bins(&self) -> &[usize] { &[0] } }
+/// # let mut driver = WLDriver::new(S, Stay, One, Params::default(),
+/// #     Geometric { alpha: 0.5, tol: 1e-6 }, Fraction, wanglandau::rng::seeded(1));
+/// wanglandau::testing::assert_converges(&mut driver, 1_000_000, 1e-6);
+/// ```
+#[track_caller]
+pub fn assert_converges<S, Mv, Map, R, Sch, F, Flt, A>(
+    driver: &mut WLDriver<S, Mv, Map, R, Sch, F, Flt, A>,
+    max_steps: u64,
+    tol: f64,
+) where
+    S: State,
+    Mv: Move<S, R>,
+    Map: Macrospace<S>,
+    R: RngCore,
+    Sch: Schedule,
+    F: Flatness,
+    Flt: Float,
+    A: Acceptance,
+{
+    driver.run(max_steps);
+    let ln_f = driver.ln_f();
+    if ln_f < tol {
+        return;
+    }
+
+    let total = driver.total_histogram();
+    let visited = total.iter().filter(|&&h| h > 0).count();
+    panic!(
+        "driver did not converge: ln_f = {:e} is not below {:e} after {} steps \
+         (budget {}); {} of {} bins visited",
+        ln_f,
+        tol,
+        driver.step_count(),
+        max_steps,
+        visited,
+        total.len()
+    );
+}
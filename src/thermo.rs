@@ -0,0 +1,279 @@
+//! # Thermodynamics from ln(g)
+//!
+//! This module turns a converged density of states into canonical averages by
+//! reweighting: at inverse temperature β each bin contributes with weight
+//! `g(E) · exp(-β E)`. It also provides microcanonical quantities read off
+//! ln(g) directly. Energies are in units where `k_B = 1`.
+//!
+//! - [`heat_capacity`]: The heat capacity `C = β² (⟨E²⟩ - ⟨E⟩²)` at one β
+//! - [`heat_capacity_peak`]: Locates the maximum of C over a range of β
+//! - [`canonical_distribution`]: The probability of each bin at one β
+//! - [`phase_free_energy_difference`]: The free-energy difference of two
+//!   phases split at a bin
+//! - [`microcanonical_beta`]: The slope `d ln g / dE` between adjacent bins
+
+use crate::analysis::logsumexp;
+
+/// Computes the canonical heat capacity at inverse temperature `beta`.
+///
+/// The weights are shifted by their maximum before exponentiating, so ln(g)
+/// values in the thousands are handled without overflow. Bins with
+/// ln(g) = -inf carry no weight.
+///
+/// # Parameters
+///
+/// * `ln_g` - The ln(g) value of each bin
+/// * `energies` - The energy of each bin
+/// * `beta` - The inverse temperature
+///
+/// # Returns
+///
+/// The heat capacity per system, `β² (⟨E²⟩ - ⟨E⟩²)`
+///
+/// # Panics
+///
+/// Panics if `ln_g` and `energies` differ in length.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::thermo::heat_capacity;
+///
+/// // Two levels at E = 0 and E = 1 with one state each
+/// let c = heat_capacity(&[0.0, 0.0], &[0.0, 1.0], 1.0);
+/// let x = (-1f64).exp();
+/// assert!((c - x / (1.0 + x).powi(2)).abs() < 1e-12);
+/// ```
+pub fn heat_capacity(ln_g: &[f64], energies: &[f64], beta: f64) -> f64 {
+    assert_eq!(
+        ln_g.len(),
+        energies.len(),
+        "ln_g and energies differ in length"
+    );
+    let max = ln_g
+        .iter()
+        .zip(energies)
+        .map(|(&g, &e)| g - beta * e)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let (mut z, mut e1, mut e2) = (0.0, 0.0, 0.0);
+    for (&g, &e) in ln_g.iter().zip(energies) {
+        let w = (g - beta * e - max).exp();
+        z += w;
+        e1 += w * e;
+        e2 += w * e * e;
+    }
+    let mean = e1 / z;
+    beta * beta * (e2 / z - mean * mean).max(0.0)
+}
+
+/// Locates the inverse temperature where the heat capacity peaks.
+///
+/// C is evaluated on `n` evenly spaced values of β spanning `beta_range`,
+/// ends included. If the largest value lies strictly inside the grid, the
+/// peak position is refined by a parabola through it and its two neighbors
+/// and C is re-evaluated there.
+///
+/// # Parameters
+///
+/// * `ln_g` - The ln(g) value of each bin
+/// * `energies` - The energy of each bin
+/// * `beta_range` - The scanned range `(beta_min, beta_max)`
+/// * `n` - The number of grid points
+///
+/// # Returns
+///
+/// The `(beta, C)` of the maximum
+///
+/// # Panics
+///
+/// Panics if `n < 2` or `ln_g` and `energies` differ in length.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::thermo::heat_capacity_peak;
+///
+/// // The Schottky peak of a two-level system sits at β ≈ 2.3994
+/// let (beta, _) = heat_capacity_peak(&[0.0, 0.0], &[0.0, 1.0], (0.5, 5.0), 40);
+/// assert!((beta - 2.3994).abs() < 1e-2);
+/// ```
+pub fn heat_capacity_peak(
+    ln_g: &[f64],
+    energies: &[f64],
+    beta_range: (f64, f64),
+    n: usize,
+) -> (f64, f64) {
+    assert!(n >= 2, "need at least two grid points, got {}", n);
+    let h = (beta_range.1 - beta_range.0) / (n - 1) as f64;
+    let c: Vec<f64> = (0..n)
+        .map(|i| heat_capacity(ln_g, energies, beta_range.0 + i as f64 * h))
+        .collect();
+    let peak = (0..n).fold(0, |best, i| if c[i] > c[best] { i } else { best });
+    let beta = beta_range.0 + peak as f64 * h;
+    if peak == 0 || peak == n - 1 {
+        return (beta, c[peak]);
+    }
+
+    // Vertex of the parabola through the peak and its neighbors
+    let (left, mid, right) = (c[peak - 1], c[peak], c[peak + 1]);
+    let curvature = left - 2.0 * mid + right;
+    if curvature >= 0.0 {
+        return (beta, mid);
+    }
+    let refined = beta + 0.5 * h * (left - right) / curvature;
+    (refined, heat_capacity(ln_g, energies, refined))
+}
+
+/// Computes the canonical probability of each bin at inverse temperature `beta`.
+///
+/// Returns `pᵢ = gᵢ exp(-β Eᵢ) / Z`, the energy histogram a canonical
+/// simulation at β would produce, e.g. to validate ln(g) against a standard
+/// Metropolis run. The exponents are shifted by their maximum before
+/// exponentiating, as in [`heat_capacity`], so large ln(g) values do not
+/// overflow. Bins with ln(g) = -inf get probability zero.
+///
+/// # Parameters
+///
+/// * `ln_g` - The ln(g) value of each bin
+/// * `energies` - The energy of each bin
+/// * `beta` - The inverse temperature
+///
+/// # Returns
+///
+/// The probability of each bin, summing to one
+///
+/// # Panics
+///
+/// Panics if `ln_g` and `energies` differ in length.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::thermo::canonical_distribution;
+///
+/// // Two levels at E = 0 and E = 1 with one state each
+/// let p = canonical_distribution(&[0.0, 0.0], &[0.0, 1.0], 1.0);
+/// let x = (-1f64).exp();
+/// assert!((p[1] - x / (1.0 + x)).abs() < 1e-12);
+/// ```
+pub fn canonical_distribution(ln_g: &[f64], energies: &[f64], beta: f64) -> Vec<f64> {
+    assert_eq!(
+        ln_g.len(),
+        energies.len(),
+        "ln_g and energies differ in length"
+    );
+    let exponents: Vec<f64> = ln_g
+        .iter()
+        .zip(energies)
+        .map(|(&g, &e)| g - beta * e)
+        .collect();
+    let max = exponents.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    let weights: Vec<f64> = exponents.iter().map(|x| (x - max).exp()).collect();
+    let z: f64 = weights.iter().sum();
+    weights.iter().map(|w| w / z).collect()
+}
+
+/// Computes the free-energy difference between two phases split at a bin.
+///
+/// The bins below `split_bin` form the low phase and the rest the high
+/// phase, e.g. ordered and disordered states of a system with a first-order
+/// transition. Each phase's partition function `Z = Σ gᵢ exp(-β Eᵢ)` is
+/// summed in log space with [`logsumexp`], and the difference
+/// `F_high - F_low = -ln(Z_high / Z_low) / β` is returned. It is negative
+/// where the high phase is more stable and crosses zero at phase
+/// coexistence.
+///
+/// # Parameters
+///
+/// * `ln_g` - The ln(g) value of each bin
+/// * `energies` - The energy of each bin
+/// * `beta` - The inverse temperature, non-zero
+/// * `split_bin` - The first bin of the high phase
+///
+/// # Returns
+///
+/// The free-energy difference `F_high - F_low`
+///
+/// # Panics
+///
+/// Panics if `ln_g` and `energies` differ in length, or `split_bin` leaves
+/// either phase without bins.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::thermo::phase_free_energy_difference;
+///
+/// // One state at E = 0 against two at E = 1
+/// let df = phase_free_energy_difference(&[0.0, 2f64.ln()], &[0.0, 1.0], 1.0, 1);
+/// assert!((df - (1.0 - 2f64.ln())).abs() < 1e-12);
+/// ```
+pub fn phase_free_energy_difference(
+    ln_g: &[f64],
+    energies: &[f64],
+    beta: f64,
+    split_bin: usize,
+) -> f64 {
+    assert_eq!(
+        ln_g.len(),
+        energies.len(),
+        "ln_g and energies differ in length"
+    );
+    assert!(
+        split_bin > 0 && split_bin < ln_g.len(),
+        "split bin {} leaves a phase of {} bins empty",
+        split_bin,
+        ln_g.len()
+    );
+    let exponents: Vec<f64> = ln_g
+        .iter()
+        .zip(energies)
+        .map(|(&g, &e)| g - beta * e)
+        .collect();
+    let (low, high) = exponents.split_at(split_bin);
+    -(logsumexp(high) - logsumexp(low)) / beta
+}
+
+/// Computes the microcanonical inverse temperature `β(E) = d ln g / dE`.
+///
+/// The derivative is taken as the finite difference between adjacent bins,
+/// so entry `i` belongs to the midpoint of bins `i` and `i + 1`. In a system
+/// with a first-order transition β(E) is not monotonic: it decreases, rises
+/// again and then resumes decreasing, which is the backbending or S-loop of
+/// the microcanonical caloric curve.
+///
+/// # Parameters
+///
+/// * `ln_g` - The ln(g) value of each bin
+/// * `energies` - The energy of each bin, strictly increasing
+///
+/// # Returns
+///
+/// The `n - 1` slopes between consecutive bins, or an empty vector for fewer
+/// than two bins
+///
+/// # Panics
+///
+/// Panics if `ln_g` and `energies` differ in length.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::thermo::microcanonical_beta;
+///
+/// let beta = microcanonical_beta(&[0.0, 2.0, 3.0], &[0.0, 1.0, 2.0]);
+/// assert_eq!(beta, [2.0, 1.0]);
+/// ```
+pub fn microcanonical_beta(ln_g: &[f64], energies: &[f64]) -> Vec<f64> {
+    assert_eq!(
+        ln_g.len(),
+        energies.len(),
+        "ln_g and energies differ in length"
+    );
+    ln_g.windows(2)
+        .zip(energies.windows(2))
+        .map(|(g, e)| (g[1] - g[0]) / (e[1] - e[0]))
+        .collect()
+}
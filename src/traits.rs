@@ -5,6 +5,7 @@
 //! - [`State`]: Represents a microscopic configuration of the system
 //! - [`Move`]: Defines Monte Carlo move proposals that modify states
 //! - [`Macrospace`]: Maps microscopic states to macroscopic energy/parameter bins
+//! - [`BinIndex`]: Flattens (possibly multidimensional) bin identifiers for storage
 //! - [`Schedule`]: Controls how the modification factor (ln_f) decays over time
 //! - [`Flatness`]: Determines when a histogram is considered "flat enough"
 //!
@@ -67,6 +68,27 @@ pub trait Move<S: State, R: RngCore> {
     /// * `state` - The current system state, which will be modified in-place
     /// * `rng` - A random number generator for stochastic move proposals
     fn propose(&mut self, state: &mut S, rng: &mut R);
+
+    /// Proposes a move and returns the log proposal ratio.
+    ///
+    /// Moves whose proposal distribution is not symmetric must report
+    /// `ln[q(new → old) / q(old → new)]` so the driver can correct the
+    /// acceptance and keep detailed balance; the ratio is added to the
+    /// Wang-Landau acceptance exponent. The default implementation calls
+    /// [`propose`](Self::propose) and returns `0.0`, i.e. a symmetric proposal.
+    ///
+    /// # Parameters
+    ///
+    /// * `state` - The current system state, which will be modified in-place
+    /// * `rng` - A random number generator for stochastic move proposals
+    ///
+    /// # Returns
+    ///
+    /// The natural log of the reverse-to-forward proposal probability ratio
+    fn propose_biased(&mut self, state: &mut S, rng: &mut R) -> f64 {
+        self.propose(state, rng);
+        0.0
+    }
 }
 
 /// Maps microscopic states to macroscopic bins (typically energy levels).
@@ -117,8 +139,8 @@ pub trait Move<S: State, R: RngCore> {
 /// ```
 pub trait Macrospace<S: State> {
     /// The type that identifies a specific macroscopic bin.
-    /// Must be convertible to `usize` for array indexing.
-    type Bin: Copy + Into<usize>;
+    /// Must be flattenable to a `usize` for array indexing (see [`BinIndex`]).
+    type Bin: BinIndex;
 
     /// Maps a given state to its corresponding macroscopic bin.
     ///
@@ -139,6 +161,143 @@ pub trait Macrospace<S: State> {
     ///
     /// A slice containing all bin identifiers that could be returned by `locate`
     fn bins(&self) -> &[Self::Bin];
+
+    /// Returns the extent of each bin dimension.
+    ///
+    /// The driver stores ln(g) and the histogram as flat arrays of
+    /// `shape().iter().product()` entries and flattens bin identifiers with
+    /// [`BinIndex::to_flat`]. The default is a single dimension holding every
+    /// bin returned by [`bins`](Self::bins); mappers with tuple or array bins
+    /// should override it.
+    ///
+    /// # Returns
+    ///
+    /// The number of bins along each dimension
+    fn shape(&self) -> Vec<usize> {
+        vec![self.bins().len()]
+    }
+
+    /// Returns where a state lies within its bin along a continuous coordinate.
+    ///
+    /// Used by [`WLDriver::with_kernel_deposition`](crate::driver::WLDriver::with_kernel_deposition)
+    /// to split each ln_f update between the located bin and its nearest
+    /// neighbor. `0.0` is the lower edge of the bin returned by
+    /// [`locate`](Self::locate), `0.5` its center and values approach `1.0`
+    /// towards the upper edge. The default, `None`, means the position is
+    /// unknown and the whole update goes to the located bin.
+    ///
+    /// # Parameters
+    ///
+    /// * `state` - The system state to locate
+    ///
+    /// # Returns
+    ///
+    /// The fractional position in `[0, 1)` within the state's bin, or `None`
+    fn position_in_bin(&self, _state: &S) -> Option<f64> {
+        None
+    }
+}
+
+/// Converts bin identifiers to and from flat array indices.
+///
+/// Implementing this trait lets [`Macrospace::locate`] return structured bins,
+/// such as a pair of indices for a two-dimensional collective variable, while
+/// the driver keeps ln(g) and the histogram in flat arrays. Multidimensional
+/// bins are flattened in row-major order with respect to
+/// [`Macrospace::shape`].
+///
+/// Implementations are provided for the unsigned integer types (which ignore
+/// the shape), `(usize, usize)` and `[usize; N]`.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::prelude::*;
+///
+/// let shape = [3, 4];
+/// let bin = (2, 1);
+///
+/// assert_eq!(bin.to_flat(&shape), 9);
+/// assert_eq!(<(usize, usize)>::from_flat(9, &shape), bin);
+/// ```
+pub trait BinIndex: Copy {
+    /// Flattens this bin identifier into an array index.
+    ///
+    /// # Parameters
+    ///
+    /// * `shape` - The extent of each bin dimension
+    ///
+    /// # Returns
+    ///
+    /// The row-major flat index of the bin
+    fn to_flat(&self, shape: &[usize]) -> usize;
+
+    /// Reconstructs a bin identifier from its flat array index.
+    ///
+    /// # Parameters
+    ///
+    /// * `flat` - The row-major flat index
+    /// * `shape` - The extent of each bin dimension
+    ///
+    /// # Returns
+    ///
+    /// The bin identifier whose flat index is `flat`
+    ///
+    /// # Panics
+    ///
+    /// The scalar implementations panic if `flat` does not fit in the
+    /// integer type.
+    fn from_flat(flat: usize, shape: &[usize]) -> Self;
+}
+
+macro_rules! scalar_bin_index {
+    ($($t:ty),*) => {
+        $(
+            impl BinIndex for $t {
+                #[inline]
+                fn to_flat(&self, _shape: &[usize]) -> usize {
+                    *self as usize
+                }
+
+                #[inline]
+                fn from_flat(flat: usize, _shape: &[usize]) -> Self {
+                    <$t>::try_from(flat).unwrap_or_else(|_| {
+                        panic!("flat index {} does not fit in {}", flat, stringify!($t))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+scalar_bin_index!(u8, u16, u32, usize);
+
+impl BinIndex for (usize, usize) {
+    #[inline]
+    fn to_flat(&self, shape: &[usize]) -> usize {
+        self.0 * shape[1] + self.1
+    }
+
+    #[inline]
+    fn from_flat(flat: usize, shape: &[usize]) -> Self {
+        (flat / shape[1], flat % shape[1])
+    }
+}
+
+impl<const N: usize> BinIndex for [usize; N] {
+    #[inline]
+    fn to_flat(&self, shape: &[usize]) -> usize {
+        self.iter().zip(shape).fold(0, |acc, (&i, &n)| acc * n + i)
+    }
+
+    fn from_flat(mut flat: usize, shape: &[usize]) -> Self {
+        let mut bin = [0; N];
+        for (i, &n) in bin.iter_mut().zip(shape).rev() {
+            *i = flat % n;
+            flat /= n;
+        }
+        bin
+    }
 }
 
 /// Controls how the modification factor (ln_f) changes during simulation.
@@ -179,6 +338,60 @@ pub trait Schedule {
     ///
     /// `true` if the algorithm should be considered converged, `false` otherwise
     fn update(&mut self, ln_f: &mut f64) -> bool; // return true if converged
+
+    /// Notifies the schedule that a flat event occurred at `step`.
+    ///
+    /// The driver calls this just before [`update`](Self::update), giving
+    /// schedules visibility into how many steps each stage took. The default
+    /// implementation ignores it.
+    ///
+    /// # Parameters
+    ///
+    /// * `step` - The driver's step count at the flat event
+    fn on_flat(&mut self, _step: u64) {}
+
+    /// Returns the ln_f below which the schedule reports convergence.
+    ///
+    /// The default implementation returns `None`, for schedules without a
+    /// single fixed tolerance.
+    ///
+    /// # Returns
+    ///
+    /// The convergence tolerance, if the schedule has one
+    fn tolerance(&self) -> Option<f64> {
+        None
+    }
+
+    /// Estimates how many more updates are needed to reach convergence.
+    ///
+    /// The default implementation returns `None`, for schedules that cannot
+    /// predict their own progress.
+    ///
+    /// # Parameters
+    ///
+    /// * `ln_f` - The current modification factor (ln f)
+    ///
+    /// # Returns
+    ///
+    /// The number of further calls to [`update`](Self::update) before it
+    /// returns `true`, if known
+    fn remaining_updates(&self, _ln_f: f64) -> Option<u32> {
+        None
+    }
+
+    /// Restarts the schedule's progress from the current modification factor.
+    ///
+    /// After a change to the run, e.g. to the parameters or the initial
+    /// ln_f, schedules with internal counters can pick up from `ln_f`
+    /// instead of from where their counters left off, giving the run more
+    /// stages without changing `ln_f` itself. The default implementation
+    /// does nothing, which suits schedules whose next value depends only on
+    /// `ln_f`.
+    ///
+    /// # Parameters
+    ///
+    /// * `ln_f` - The current modification factor (ln f)
+    fn reset_progress(&mut self, _ln_f: f64) {}
 }
 
 /// Defines a criterion for histogram flatness.
@@ -213,10 +426,113 @@ pub trait Flatness {
     ///
     /// * `hist` - The current histogram of visited states
     /// * `flatness` - A parameter controlling how strict the flatness criterion is,
-    ///                typically between 0.0 and 1.0
+    ///   typically between 0.0 and 1.0
     ///
     /// # Returns
     ///
     /// `true` if the histogram is considered flat enough, `false` otherwise
     fn is_flat(&self, hist: &[u64], flatness: f64) -> bool;
+
+    /// Determines if a histogram is flat under a separate threshold per bin.
+    ///
+    /// Used by the driver when per-bin thresholds were installed with
+    /// [`WLDriver::set_bin_flatness`](crate::driver::WLDriver::set_bin_flatness).
+    /// The default requires every bin to reach its own fraction of the mean,
+    /// `hist[i] >= flatness[i] * mean(hist)`, regardless of the scalar
+    /// criterion `is_flat` implements.
+    ///
+    /// # Parameters
+    ///
+    /// * `hist` - The current histogram of visited states
+    /// * `flatness` - The threshold of each bin, one entry per bin
+    ///
+    /// # Returns
+    ///
+    /// `true` if every bin meets its threshold, `false` otherwise or for an
+    /// empty histogram
+    fn is_flat_per_bin(&self, hist: &[u64], flatness: &[f64]) -> bool {
+        if hist.is_empty() {
+            return false;
+        }
+        let mean = hist.iter().sum::<u64>() as f64 / hist.len() as f64;
+        hist.iter()
+            .zip(flatness)
+            .all(|(&h, &flat)| h as f64 >= flat * mean)
+    }
+
+    /// Determines if the histogram of a subset of the bins is flat.
+    ///
+    /// Used by the driver when an active mask or local freezing leaves some
+    /// bins out of the check. `hist[i]` counts the visits to bin `bins[i]`,
+    /// so criteria that report bins, such as
+    /// [`Explained`](crate::flatness::Explained), can name the real ones.
+    /// The default ignores `bins` and defers to [`is_flat`](Self::is_flat).
+    ///
+    /// # Parameters
+    ///
+    /// * `hist` - The histogram of the included bins
+    /// * `bins` - The bin number of each entry of `hist`
+    /// * `flatness` - The strictness of the criterion, as for `is_flat`
+    ///
+    /// # Returns
+    ///
+    /// `true` if the histogram is considered flat enough, `false` otherwise
+    fn is_flat_subset(&self, hist: &[u64], _bins: &[usize], flatness: f64) -> bool {
+        self.is_flat(hist, flatness)
+    }
+
+    /// Determines if the histogram of a subset of the bins is flat under a
+    /// separate threshold per bin.
+    ///
+    /// The per-bin counterpart of [`is_flat_subset`](Self::is_flat_subset);
+    /// the default defers to [`is_flat_per_bin`](Self::is_flat_per_bin).
+    ///
+    /// # Parameters
+    ///
+    /// * `hist` - The histogram of the included bins
+    /// * `bins` - The bin number of each entry of `hist`
+    /// * `flatness` - The threshold of each entry of `hist`
+    ///
+    /// # Returns
+    ///
+    /// `true` if every bin meets its threshold, `false` otherwise or for an
+    /// empty histogram
+    fn is_flat_per_bin_subset(&self, hist: &[u64], _bins: &[usize], flatness: &[f64]) -> bool {
+        self.is_flat_per_bin(hist, flatness)
+    }
+}
+
+/// Defines the acceptance probability of a proposed move between bins.
+///
+/// The driver computes `delta = ln g(old) - ln g(new) + ln_ratio`, including
+/// any external bias and the move's log proposal ratio, and accepts the move
+/// with the probability returned here. Any rule for which
+/// `probability(delta) / probability(-delta) = exp(delta)` satisfies detailed
+/// balance with respect to the instantaneous `1/g` weights.
+///
+/// # Example
+///
+/// ```
+/// use wanglandau::prelude::*;
+///
+/// /// Metropolis acceptance scaled down by a constant factor.
+/// struct Lazy(f64);
+///
+/// impl Acceptance for Lazy {
+///     fn probability(&self, delta: f64) -> f64 {
+///         self.0 * delta.exp().min(1.0)
+///     }
+/// }
+/// ```
+pub trait Acceptance {
+    /// Returns the probability of accepting a move with log weight ratio `delta`.
+    ///
+    /// # Parameters
+    ///
+    /// * `delta` - The log ratio of the target weights, new over old
+    ///
+    /// # Returns
+    ///
+    /// The acceptance probability, compared against a uniform draw in `[0, 1)`
+    fn probability(&self, delta: f64) -> f64;
 }
@@ -0,0 +1,67 @@
+//! Test the pluggable acceptance rules.
+
+mod common;
+
+use common::spread;
+use wanglandau::prelude::*;
+
+/// The heat-bath rule follows the logistic form for known deltas.
+#[test]
+fn glauber_matches_logistic_form() {
+    for (delta, expected) in [
+        (0.0, 0.5),
+        (2.0f64.ln(), 2.0 / 3.0),
+        (-(3.0f64.ln()), 0.25),
+        (50.0, 1.0),
+    ] {
+        let p = GlauberWL.probability(delta);
+        assert!((p - expected).abs() < 1e-12, "p({}) = {}", delta, p);
+        // Detailed balance: p(δ) / p(-δ) = exp(δ)
+        let ratio = p / GlauberWL.probability(-delta);
+        assert!((ratio / delta.exp() - 1.0).abs() < 1e-9);
+    }
+    assert_eq!(Metropolis.probability(-(2.0f64.ln())), 0.5);
+    assert_eq!(Metropolis.probability(1.0), 1.0);
+}
+
+/// A dice driver with heat-bath acceptance still converges to a flat ln(g).
+#[test]
+fn glauber_driver_converges_on_dice() {
+    let mut drv = common::dice_driver(1e-6, 23).with_acceptance(GlauberWL);
+    drv.run(10_000_000);
+
+    assert!(drv.ln_f() < 1e-6, "ln_f = {}", drv.ln_f());
+    assert!(spread(drv.ln_g()) < 0.3, "{:?}", drv.ln_g());
+}
+
+/// Linear cooling from 0.1 to 1 over the first 10 000 steps.
+fn cooling(step: u64) -> f64 {
+    (0.1 + 0.9 * step as f64 / 10_000.0).min(1.0)
+}
+
+/// The tempering prefactor follows the schedule step by step, and the run
+/// still converges to the flat dice ln g.
+#[test]
+fn beta_schedule_sets_prefactor() {
+    let mut drv = common::dice_driver(1e-6, 8).with_beta_schedule(cooling);
+    assert_eq!(drv.tempering_beta(), 1.0);
+    let mut converged = false;
+    for _ in 0..10_000_000 {
+        let step = drv.step_count();
+        converged = drv.step();
+        assert_eq!(drv.tempering_beta(), cooling(step));
+        if converged {
+            break;
+        }
+    }
+    assert!(converged);
+    assert_eq!(drv.tempering_beta(), 1.0);
+    assert!(spread(drv.ln_g()) < 0.2, "{:?}", drv.ln_g());
+
+    // A constant prefactor of one is plain Wang-Landau
+    let mut plain = common::dice_driver(1e-6, 8);
+    plain.run(100_000);
+    let mut constant = common::dice_driver(1e-6, 8).with_beta_schedule(|_| 1.0);
+    constant.run(100_000);
+    assert_eq!(plain.ln_g(), constant.ln_g());
+}
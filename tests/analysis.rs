@@ -0,0 +1,330 @@
+//! Test the post-processing utilities in `wanglandau::analysis`.
+
+mod common;
+
+use wanglandau::analysis;
+
+/// The Bhattacharyya coefficient of synthetic histograms with known overlap.
+#[test]
+fn overlap_of_synthetic_histograms() {
+    let a = [0, 10, 10, 0];
+    let b = [0, 0, 10, 10];
+
+    // p = [0, 1/2, 1/2, 0], q = [0, 0, 1/2, 1/2] → BC = 1/2
+    assert!((analysis::overlap(&a, &b, (0, 4)) - 0.5).abs() < 1e-12);
+
+    // Restricted to bins 1..3: p = [1/2, 1/2], q = [0, 1] → BC = √(1/2)
+    let restricted = analysis::overlap(&a, &b, (1, 3));
+    assert!((restricted - 0.5f64.sqrt()).abs() < 1e-12);
+
+    // Identical shapes overlap perfectly regardless of scale
+    let c = [0, 30, 30, 0];
+    assert!((analysis::overlap(&a, &c, (0, 4)) - 1.0).abs() < 1e-12);
+
+    // Disjoint windows and empty ranges have no overlap
+    assert_eq!(analysis::overlap(&[5, 5, 0, 0], &[0, 0, 5, 5], (0, 4)), 0.0);
+    assert_eq!(analysis::overlap(&a, &b, (0, 1)), 0.0);
+}
+
+/// A spiky ln g is rough; a linear one, also as seen by a driver, is not.
+#[test]
+fn roughness_flags_spikes() {
+    let linear: Vec<f64> = (0..10).map(|i| 0.7 * i as f64 - 3.0).collect();
+    assert!(analysis::roughness(&linear) < 1e-20);
+
+    let mut spiky = linear.clone();
+    spiky[4] += 5.0;
+    assert!(analysis::roughness(&spiky) > 10.0);
+
+    let mut drv = common::dice_driver(1e-6, 3);
+    assert_eq!(drv.ln_g_roughness(), 0.0);
+    drv.pin_bin(2, 5.0);
+    assert_eq!(drv.ln_g_roughness(), analysis::roughness(drv.ln_g()));
+    assert!(drv.ln_g_roughness() > 10.0);
+}
+
+/// An AR(1) series with coefficient φ has τ = (1 + φ) / (1 - φ) and ρ(t) = φᵗ.
+#[test]
+fn autocorrelation_of_ar1_series() {
+    use rand::Rng;
+
+    let phi: f64 = 0.8;
+    let mut rng = wanglandau::rng::seeded(12);
+    let mut x = 0.0;
+    let series: Vec<f64> = (0..200_000)
+        .map(|_| {
+            x = phi * x + rng.random_range(-1.0..1.0);
+            x
+        })
+        .collect();
+
+    let rho = analysis::autocorrelation(&series, 5);
+    assert_eq!(rho[0], 1.0);
+    for (t, r) in rho.iter().enumerate() {
+        assert!((r - phi.powi(t as i32)).abs() < 0.02, "ρ({}) = {}", t, r);
+    }
+
+    let tau = analysis::integrated_autocorr_time(&series);
+    let exact = (1.0 + phi) / (1.0 - phi);
+    assert!(
+        (tau - exact).abs() < 0.1 * exact,
+        "τ = {}, expected {}",
+        tau,
+        exact
+    );
+}
+
+/// Stages at a smaller ln_f dominate the merge, whatever their offsets.
+#[test]
+fn weighted_merge_favors_low_ln_f_stages() {
+    let truth = [0.0, 1.0, 3.0, 6.0];
+    let noisy = vec![10.0, 12.5, 12.0, 17.0]; // wrong shape, shifted by 10
+    let accurate: Vec<f64> = truth.iter().map(|g| g - 4.0).collect();
+
+    // Weights 1 and 1000
+    let merged = analysis::weighted_merge(&[(noisy.clone(), 1.0), (accurate, 1e-3)]);
+    assert_eq!(merged[0], 0.0);
+    for (m, t) in merged.iter().zip(truth) {
+        assert!((m - t).abs() < 0.01, "merged {:?}", merged);
+    }
+
+    // With equal ln_f the two shapes count equally
+    let even = analysis::weighted_merge(&[(noisy, 1.0), (truth.to_vec(), 1.0)]);
+    assert!((even[1] - even[0] - 1.75).abs() < 1e-12);
+
+    // The driver records one curve per flat event, in the merge's format
+    let mut drv = common::dice_driver(1e-4, 5).with_stage_ln_g();
+    drv.run(1_000_000);
+    let stages = drv.stage_ln_g();
+    assert!(!stages.is_empty());
+    assert!(stages.windows(2).all(|w| w[1].1 < w[0].1));
+    assert!(common::spread(&analysis::weighted_merge(stages)) < 0.2);
+}
+
+/// Curves with known per-bin scatter give the analytic standard error.
+#[test]
+fn jackknife_error_matches_scatter() {
+    use rand::Rng;
+
+    let truth = [0.0, 1.0, 3.0, 6.0, 10.0, 15.0];
+    let half_width = [0.1, 0.2, 0.3, 0.1, 0.2, 0.3];
+    let n = 2000;
+    let mut rng = wanglandau::rng::seeded(11);
+    let samples: Vec<Vec<f64>> = (0..n)
+        .map(|k| {
+            // Each curve has its own arbitrary offset
+            let offset = 100.0 * k as f64;
+            truth
+                .iter()
+                .zip(half_width)
+                .map(|(g, a)| g + offset + rng.random_range(-a..a))
+                .collect()
+        })
+        .collect();
+
+    let (mean, err) = analysis::jackknife(&samples);
+
+    // Uniform noise on [-a, a] has variance a²/3; aligning each curve to zero
+    // mean mixes in the noise of the other bins
+    let bins = truth.len() as f64;
+    let var: Vec<f64> = half_width.iter().map(|a| a * a / 3.0).collect();
+    let total: f64 = var.iter().sum();
+    let truth_mean = truth.iter().sum::<f64>() / bins;
+    for bin in 0..truth.len() {
+        let aligned_var = var[bin] * (1.0 - 2.0 / bins) + total / (bins * bins);
+        let expected = (aligned_var / n as f64).sqrt();
+        assert!(
+            (err[bin] - expected).abs() < 0.1 * expected,
+            "bin {}: error {} vs {}",
+            bin,
+            err[bin],
+            expected
+        );
+        assert!((mean[bin] - (truth[bin] - truth_mean)).abs() < 4.0 * expected);
+    }
+
+    // Deterministic: the same ensemble gives the same answer
+    assert_eq!(analysis::jackknife(&samples), (mean, err));
+}
+
+/// All faces of a die are equally likely, so every ratio is near zero and
+/// consistent with its error bar.
+#[test]
+fn dice_ln_g_ratios_vanish() {
+    let runs: Vec<Vec<f64>> = (0..8)
+        .map(|seed| {
+            let mut drv = common::dice_driver(1e-6, 100 + seed);
+            drv.run(10_000_000);
+            // The ratio does not care about the offset of the estimate
+            let shifted = drv.ln_g()[3] + 7.0 - (drv.ln_g()[1] + 7.0);
+            assert!((drv.ln_g_ratio(3, 1) - shifted).abs() < 1e-12);
+            drv.ln_g().to_vec()
+        })
+        .collect();
+
+    for a in 0..6 {
+        for b in 0..6 {
+            let (ratio, err) = analysis::ln_g_ratio_with_error(&runs, a, b);
+            assert!(ratio.abs() < 0.1, "ln g({}) - ln g({}) = {}", a, b, ratio);
+            assert!(ratio.abs() <= 4.0 * err + 1e-12, "{} ± {}", ratio, err);
+        }
+    }
+}
+
+/// Uniformly rolled dice reweighted to a peaked target give its biased mean.
+#[test]
+fn reweighted_dice_sums_follow_target() {
+    use rand::Rng;
+
+    // Sums 2..=12 in bins 0..=10 with g = 1, 2, ..., 6, ..., 2, 1
+    let ln_g: Vec<f64> = (0..11i32)
+        .map(|b| (6 - (b - 5).abs()) as f64)
+        .map(f64::ln)
+        .collect();
+    let target: Vec<f64> = (0..11).map(|b| -0.5 * (b as f64 - 8.0).powi(2)).collect();
+
+    let mut rng = wanglandau::rng::seeded(17);
+    let samples: Vec<(usize, f64)> = (0..200_000)
+        .map(|_| {
+            let sum = rng.random_range(1..=6) + rng.random_range(1..=6);
+            (sum - 2, sum as f64)
+        })
+        .collect();
+
+    let z: f64 = target.iter().map(|t| t.exp()).sum();
+    let expected: f64 = (0..11).map(|b| (b + 2) as f64 * target[b].exp() / z).sum();
+    let got = analysis::reweight(&samples, &ln_g, &target);
+    assert!((got - expected).abs() < 0.02, "{} vs {}", got, expected);
+
+    // The identity target reproduces the plain sample mean of 7
+    let plain = analysis::reweight(&samples, &ln_g, &ln_g);
+    assert!((plain - 7.0).abs() < 0.05, "{}", plain);
+}
+
+/// A curve matches itself and any shifted copy of itself exactly.
+#[test]
+fn compare_ignores_offsets() {
+    let ln_g: Vec<f64> = (0..20)
+        .map(|i| (i as f64 * 0.3).sin() * 4.0 + i as f64)
+        .collect();
+
+    let same = analysis::compare(&ln_g, &ln_g);
+    assert_eq!(same.max_abs_diff, 0.0);
+    assert_eq!(same.rms_diff, 0.0);
+    assert!((same.correlation - 1.0).abs() < 1e-12);
+
+    let shifted: Vec<f64> = ln_g.iter().map(|g| g + 123.5).collect();
+    let cmp = analysis::compare(&ln_g, &shifted);
+    assert!(cmp.max_abs_diff < 1e-12, "{:?}", cmp);
+    assert!(cmp.rms_diff < 1e-12, "{:?}", cmp);
+    assert!((cmp.correlation - 1.0).abs() < 1e-12);
+
+    // A single perturbed bin shows up in both distances
+    let mut bumped = shifted.clone();
+    bumped[7] += 0.4;
+    let cmp = analysis::compare(&ln_g, &bumped);
+    assert!((cmp.max_abs_diff - 0.4).abs() < 1e-9, "{:?}", cmp);
+    assert!(
+        (cmp.rms_diff - 0.4 / 20f64.sqrt()).abs() < 1e-9,
+        "{:?}",
+        cmp
+    );
+
+    // Forbidden bins are skipped
+    let mut forbidden = shifted;
+    forbidden[0] = f64::NEG_INFINITY;
+    assert!(analysis::compare(&ln_g, &forbidden).max_abs_diff < 1e-12);
+}
+
+/// Passes of a six-bin curve with independent uniform noise in each bin.
+fn noisy_passes(seed: u64, n: usize, scatter: [f64; 6]) -> Vec<Vec<f64>> {
+    use rand::Rng;
+
+    let truth = [0.0, 1.0, 3.0, 4.0, 3.0, 1.0];
+    let mut rng = wanglandau::rng::seeded(seed);
+    (0..n)
+        .map(|k| {
+            // Uniform noise on ±√3 σ has standard deviation σ
+            (0..6)
+                .map(|bin| {
+                    let half = 3f64.sqrt() * scatter[bin];
+                    truth[bin] + 2.7 * k as f64 + rng.random_range(-half..half)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A noisy bin neither drags the alignment nor hides behind a small error.
+#[test]
+fn inverse_variance_combine_downweights_noisy_bins() {
+    use wanglandau::analysis::{inverse_variance_combine, jackknife};
+
+    let truth = [0.0, 1.0, 3.0, 4.0, 3.0, 1.0];
+    let passes = noisy_passes(3, 8, [3.0, 0.01, 0.01, 0.01, 0.01, 0.01]);
+    let (ln_g, err) = inverse_variance_combine(&passes);
+    for bin in 1..6 {
+        let diff = (ln_g[bin] - ln_g[1]) - (truth[bin] - truth[1]);
+        assert!(diff.abs() < 0.05, "combined {:?}", ln_g);
+    }
+
+    // Only the noisy bin carries a large error, whereas a zero-mean alignment
+    // spreads its scatter over every bin
+    let (_, naive_err) = jackknife(&passes);
+    assert!(err[0] > 0.5, "{:?}", err);
+    assert!(err[1..].iter().all(|&e| e < 0.05), "{:?}", err);
+    assert!(naive_err[1..].iter().all(|&e| e > 0.1), "{:?}", naive_err);
+    assert_eq!(ln_g.iter().copied().fold(f64::INFINITY, f64::min), 0.0);
+}
+
+/// With equal scatter in every bin no bin reports a falsely small error.
+#[test]
+fn inverse_variance_combine_keeps_equal_errors() {
+    use wanglandau::analysis::inverse_variance_combine;
+
+    let scatter = 0.1;
+    let n = 8;
+    let expected = scatter / (n as f64).sqrt();
+    for seed in 0..50 {
+        let (_, err) = inverse_variance_combine(&noisy_passes(seed, n, [scatter; 6]));
+        assert!(
+            err.iter().all(|&e| e > 0.2 * expected),
+            "seed {seed}: {:?}",
+            err
+        );
+    }
+
+    // Even two or three passes do not pull the alignment onto one bin
+    for n in [2, 3] {
+        for seed in 0..50 {
+            let (_, err) = inverse_variance_combine(&noisy_passes(seed, n, [scatter; 6]));
+            assert!(err.iter().all(|&e| e > 1e-6), "seed {seed}: {:?}", err);
+        }
+    }
+}
+
+/// The round-trip estimate grows linearly with the bin count and with the
+/// inverse square of the target error.
+#[test]
+fn round_trip_estimate_scales_with_bins_and_error() {
+    let base = analysis::round_trips_for_error(50, 0.2);
+    assert_eq!(analysis::round_trips_for_error(100, 0.2), 2 * base);
+    assert_eq!(analysis::round_trips_for_error(50, 0.1), 4 * base);
+
+    // Tighter targets and wider ranges never need fewer round trips
+    let mut prev = 0;
+    for error in [1.0, 0.5, 0.2, 0.1, 0.05, 0.01] {
+        let n = analysis::round_trips_for_error(20, error);
+        assert!(n > prev, "{} round trips for error {}", n, error);
+        prev = n;
+    }
+    for bins in 1..200 {
+        assert!(
+            analysis::round_trips_for_error(bins + 1, 0.3)
+                >= analysis::round_trips_for_error(bins, 0.3)
+        );
+    }
+
+    // A loose target still asks for at least one round trip
+    assert_eq!(analysis::round_trips_for_error(4, 100.0), 1);
+}
@@ -0,0 +1,52 @@
+//! Test the fixed-size array-backed driver.
+
+mod common;
+
+use common::{dice_driver, Dice, Face, Roll};
+use wanglandau::array::WLDriverArray;
+use wanglandau::prelude::*;
+
+/// From the same seed the array backend reproduces the Vec backend exactly.
+#[test]
+fn array_driver_matches_vec_driver() {
+    let mut vec_drv = dice_driver(1e-6, 13);
+    let mut arr_drv: WLDriverArray<_, _, _, 6> = WLDriverArray::new(
+        Dice(1),
+        Roll,
+        Face,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-6,
+        },
+        Fraction,
+        wanglandau::rng::seeded(13),
+    );
+
+    vec_drv.run(1_000_000);
+    arr_drv.run(1_000_000);
+
+    assert_eq!(vec_drv.ln_g(), arr_drv.ln_g().as_slice());
+    assert_eq!(vec_drv.histogram(), arr_drv.histogram().as_slice());
+    assert_eq!(vec_drv.ln_f(), arr_drv.ln_f());
+    assert_eq!(vec_drv.step_count(), arr_drv.step_count());
+    assert_eq!(vec_drv.state(), arr_drv.state());
+}
+
+/// A bin count that disagrees with the mapper is rejected at construction.
+#[test]
+#[should_panic(expected = "mapper shape")]
+fn array_length_must_match_mapper() {
+    let _: WLDriverArray<_, _, _, 4> = WLDriverArray::new(
+        Dice(1),
+        Roll,
+        Face,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-6,
+        },
+        Fraction,
+        wanglandau::rng::seeded(13),
+    );
+}
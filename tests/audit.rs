@@ -0,0 +1,49 @@
+//! Test the acceptance audit log.
+
+mod common;
+
+use common::dice_driver;
+use wanglandau::driver::AuditEntry;
+use wanglandau::prelude::*;
+
+/// Replays logged draws through an acceptance rule and checks each decision.
+fn replay(log: &[AuditEntry], rule: impl Acceptance) {
+    for (i, e) in log.iter().enumerate() {
+        let expected = match (e.delta, e.draw) {
+            (Some(delta), Some(draw)) => draw < rule.probability(delta),
+            (None, None) => e.bin_old == e.bin_new,
+            _ => panic!("entry {} has only one of delta and draw: {:?}", i, e),
+        };
+        assert_eq!(e.accepted, expected, "entry {} does not replay: {:?}", i, e);
+    }
+}
+
+/// Every logged decision is reproduced from its draw, and the bins chain.
+#[test]
+fn logged_draws_replay_decisions() {
+    let mut drv = dice_driver(0.0, 7).with_audit_log();
+    drv.run(5000);
+    let log = drv.audit_log();
+    assert_eq!(log.len(), 5000);
+    assert!(log.iter().any(|e| !e.accepted), "no rejection was logged");
+    replay(log, Metropolis);
+
+    // Each proposal starts in the bin the previous decision left the walker in
+    for (i, pair) in log.windows(2).enumerate() {
+        let end = if pair[0].accepted {
+            pair[0].bin_new
+        } else {
+            pair[0].bin_old
+        };
+        assert_eq!(pair[1].bin_old, end, "entries {} and {}", i, i + 1);
+        assert_eq!(pair[1].step, pair[0].step + 1);
+    }
+
+    let mut glauber = dice_driver(0.0, 7)
+        .with_acceptance(GlauberWL)
+        .with_audit_log();
+    glauber.run(5000);
+    replay(glauber.audit_log(), GlauberWL);
+
+    assert!(dice_driver(0.0, 7).audit_log().is_empty());
+}
@@ -0,0 +1,73 @@
+//! Test the lockstep multi-walker driver.
+
+mod common;
+
+use common::{dice_driver, spread, Dice, Face, Roll};
+use wanglandau::batch::BatchDriver;
+use wanglandau::prelude::*;
+
+const K: usize = 4;
+
+fn sched() -> Geometric {
+    Geometric {
+        alpha: 0.5,
+        tol: 1e-6,
+    }
+}
+
+/// Shifts a curve so that its first bin is zero.
+fn relative(ln_g: &[f64]) -> Vec<f64> {
+    ln_g.iter().map(|g| g - ln_g[0]).collect()
+}
+
+/// K walkers on one shared ln g agree with K separate single-walker runs.
+#[test]
+fn shared_batch_matches_separate_runs() {
+    let mut batch = BatchDriver::shared(
+        vec![Dice(1), Dice(2), Dice(5), Dice(6)],
+        Roll,
+        Face,
+        Params::default(),
+        sched(),
+        Fraction,
+        wanglandau::rng::seeded(31),
+    );
+    batch.run(10_000_000);
+    assert!(batch.is_shared());
+    assert!(batch.ln_f(0) < 1e-6);
+    assert_eq!(batch.ln_g(0), batch.ln_g(K - 1));
+    let shared = relative(batch.ln_g(0));
+    assert!(spread(&shared) < 0.5, "shared ln g {:?}", shared);
+
+    for seed in 0..K as u64 {
+        let mut single = dice_driver(1e-6, 100 + seed);
+        single.run(10_000_000);
+        let separate = relative(single.ln_g());
+        for (a, b) in shared.iter().zip(&separate) {
+            assert!((a - b).abs() < 0.5, "{:?} vs {:?}", shared, separate);
+        }
+    }
+}
+
+/// Per-walker estimates evolve independently and each converge.
+#[test]
+fn independent_batch_keeps_separate_estimates() {
+    let mut batch = BatchDriver::independent(
+        vec![Dice(1); K],
+        Roll,
+        Face,
+        Params::default(),
+        sched(),
+        Fraction,
+        wanglandau::rng::seeded(31),
+    );
+    batch.run(10_000_000);
+
+    assert!(!batch.is_shared());
+    assert_eq!(batch.n_walkers(), K);
+    assert_ne!(batch.ln_g(0), batch.ln_g(1));
+    for walker in 0..K {
+        assert!(batch.ln_f(walker) < 1e-6);
+        assert!(spread(batch.ln_g(walker)) < 0.5);
+    }
+}
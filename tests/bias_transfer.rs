@@ -0,0 +1,77 @@
+//! Test exporting ln(g) from one run and importing it into another.
+
+mod common;
+
+use common::{dice_driver, Dice, Face};
+use wanglandau::error::WLError;
+use wanglandau::prelude::*;
+
+/// Steps the die to a neighbouring face, wrapping around.
+struct Tilt;
+impl<R: rand::RngCore> Move<Dice, R> for Tilt {
+    fn propose(&mut self, s: &mut Dice, rng: &mut R) {
+        use rand::Rng;
+        s.0 = if rng.random() {
+            s.0 % 6 + 1
+        } else {
+            (s.0 + 4) % 6 + 1
+        };
+    }
+}
+
+fn tilt_driver(seed: u64) -> WLDriver<Dice, Tilt, Face> {
+    WLDriver::new(
+        Dice(1),
+        Tilt,
+        Face,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-6,
+        },
+        Fraction,
+        wanglandau::rng::seeded(seed),
+    )
+}
+
+/// A bias exported from a rolling die steers a tilting die.
+#[test]
+fn bias_transfers_across_move_sets() {
+    let mut pilot = dice_driver(1e-6, 3);
+    pilot.run(1_000_000);
+    let exported = pilot.export_bias();
+    assert_eq!(exported.as_slice(), pilot.ln_g());
+
+    let mut drv = tilt_driver(8);
+    drv.run(2_000);
+    drv.import_bias(&exported).unwrap();
+    assert_eq!(drv.ln_g(), exported.as_slice());
+    assert_eq!(drv.ln_f(), Params::default().ln_f0);
+    assert!(drv.histogram().iter().all(|&h| h == 0));
+
+    // The refinement keeps converging with the new moves
+    drv.run(1_000_000);
+    assert!(drv.ln_f() < 1e-6);
+    assert!(common::spread(drv.ln_g()) < 0.5, "{:?}", drv.ln_g());
+
+    // A tilted bias is what the frozen walk actually samples against
+    let tilted: Vec<f64> = (0..6).map(|i| i as f64).collect();
+    drv.import_bias(&tilted).unwrap();
+    let counts = drv.produce_observable_histogram(100_000, |s| (s.0 - 1) as usize, 6);
+    let z: f64 = tilted.iter().map(|&g| (-g).exp()).sum();
+    let p0 = counts[0] as f64 / 100_000.0;
+    assert!((p0 - 1.0 / z).abs() < 0.01, "face 1 has frequency {}", p0);
+}
+
+/// Importing a bias of the wrong length is rejected.
+#[test]
+fn import_bias_checks_length() {
+    let mut drv = tilt_driver(1);
+    assert_eq!(
+        drv.import_bias(&[0.0; 4]),
+        Err(WLError::LengthMismatch {
+            expected: 6,
+            found: 4
+        })
+    );
+}
@@ -0,0 +1,165 @@
+//! Test structured (multidimensional) bin identifiers.
+//!
+//! Mappers may return tuples or arrays as bins; the driver flattens them into
+//! row-major arrays using the shape reported by the mapper.
+
+use wanglandau::{flatness, prelude::*, rng, schedule};
+
+/// Tuple bins survive a flatten/unflatten round trip and flatten row-major.
+#[test]
+fn tuple_round_trip() {
+    let shape = [3, 5];
+    let mut seen = [false; 15];
+    for i in 0..3 {
+        for j in 0..5 {
+            let flat = (i, j).to_flat(&shape);
+            assert_eq!(flat, i * 5 + j);
+            assert_eq!(<(usize, usize)>::from_flat(flat, &shape), (i, j));
+            seen[flat] = true;
+        }
+    }
+    assert!(seen.iter().all(|&s| s), "flattening is not a bijection");
+}
+
+/// Array bins survive a flatten/unflatten round trip for every index.
+#[test]
+fn array_round_trip() {
+    let shape = [2, 3, 4];
+    for flat in 0..24 {
+        let bin = <[usize; 3]>::from_flat(flat, &shape);
+        assert!(bin.iter().zip(&shape).all(|(&i, &n)| i < n));
+        assert_eq!(bin.to_flat(&shape), flat);
+    }
+    assert_eq!([1, 2, 3].to_flat(&shape), 12 + 8 + 3);
+}
+
+/// Scalar bins convert back exactly when the index fits.
+#[test]
+fn scalar_round_trip() {
+    assert_eq!(u8::from_flat(255, &[256]), 255);
+    assert_eq!(u16::from_flat(300, &[301]).to_flat(&[301]), 300);
+}
+
+/// A flat index too large for a scalar bin type panics instead of wrapping.
+#[test]
+#[should_panic(expected = "flat index 256 does not fit in u8")]
+fn scalar_from_flat_rejects_overflow() {
+    u8::from_flat(256, &[300]);
+}
+
+/// Two independent coins, binned by the pair of faces
+#[derive(Clone)]
+struct Coins(bool, bool);
+impl State for Coins {}
+
+/// Re-tosses one of the two coins
+struct Toss;
+impl<R: rand::RngCore> Move<Coins, R> for Toss {
+    fn propose(&mut self, s: &mut Coins, rng: &mut R) {
+        use rand::Rng;
+        if rng.random() {
+            s.0 = rng.random();
+        } else {
+            s.1 = rng.random();
+        }
+    }
+}
+
+/// Maps the coins to a 2×2 grid of bins without pre-flattening
+struct Faces;
+impl Macrospace<Coins> for Faces {
+    type Bin = (usize, usize);
+    fn locate(&self, s: &Coins) -> (usize, usize) {
+        (s.0 as usize, s.1 as usize)
+    }
+    fn bins(&self) -> &[(usize, usize)] {
+        &[(0, 0), (0, 1), (1, 0), (1, 1)]
+    }
+    fn shape(&self) -> Vec<usize> {
+        vec![2, 2]
+    }
+}
+
+/// A driver over tuple bins samples all four cells evenly.
+#[test]
+fn driver_with_tuple_bins() {
+    let mut drv = WLDriver::new(
+        Coins(false, false),
+        Toss,
+        Faces,
+        Params::default(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-8,
+        },
+        flatness::Fraction,
+        rng::seeded(3),
+    );
+    drv.run(1_000_000);
+
+    assert_eq!(drv.shape(), &[2, 2]);
+    let ln_g = drv.ln_g();
+    assert_eq!(ln_g.len(), 4);
+    let (min, max) = ln_g
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &x| {
+            (a.min(x), b.max(x))
+        });
+    assert!(max - min < 1.0, "cells are unevenly weighted: {:?}", ln_g);
+}
+
+/// Mistakenly lists every other index as a bin
+struct Sparse;
+impl Macrospace<Coins> for Sparse {
+    type Bin = usize;
+    fn locate(&self, s: &Coins) -> usize {
+        2 * (s.0 as usize + s.1 as usize)
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 2, 4]
+    }
+}
+
+/// The fallible constructor rejects gaps in the bin indices.
+#[test]
+fn try_new_rejects_non_contiguous_bins() {
+    use wanglandau::error::WLError;
+
+    let err = WLDriver::try_new(
+        Coins(false, false),
+        Toss,
+        Sparse,
+        Params::default(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-8,
+        },
+        flatness::Fraction,
+        rng::seeded(3),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+        err,
+        WLError::NonContiguousBins {
+            position: 1,
+            found: 2
+        }
+    );
+    assert!(err.to_string().contains("position 1"));
+
+    // Row-major tuple bins are contiguous once flattened
+    let drv = WLDriver::try_new(
+        Coins(false, false),
+        Toss,
+        Faces,
+        Params::default(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-8,
+        },
+        flatness::Fraction,
+        rng::seeded(3),
+    );
+    assert!(drv.is_ok());
+}
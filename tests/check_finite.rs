@@ -0,0 +1,91 @@
+//! Test the opt-in check for non-finite ln(g) values.
+
+use wanglandau::error::WLError;
+use wanglandau::prelude::*;
+
+/// A die that can only ever land on four
+#[derive(Clone)]
+struct Dice(u8);
+impl State for Dice {}
+
+/// A roll that always shows four
+struct RollFour;
+impl<R: rand::RngCore> Move<Dice, R> for RollFour {
+    fn propose(&mut self, s: &mut Dice, _rng: &mut R) {
+        s.0 = 4;
+    }
+}
+
+/// Maps die values to bins (0-5 for values 1-6)
+struct Face;
+impl Macrospace<Dice> for Face {
+    type Bin = usize;
+    fn locate(&self, s: &Dice) -> usize {
+        (s.0 - 1) as usize
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4, 5]
+    }
+}
+
+/// A buggy schedule whose first reduction turns ln_f into NaN
+struct Poisoned;
+impl Schedule for Poisoned {
+    fn update(&mut self, ln_f: &mut f64) -> bool {
+        *ln_f = f64::NAN;
+        false
+    }
+}
+
+/// Declares every histogram flat, so the schedule updates after each step
+struct AlwaysFlat;
+impl Flatness for AlwaysFlat {
+    fn is_flat(&self, _hist: &[u64], _flatness: f64) -> bool {
+        true
+    }
+}
+
+/// Builds a driver whose ln(g) turns NaN on its second step.
+fn poisoned_driver(
+    check_finite: bool,
+) -> WLDriver<Dice, RollFour, Face, Rng64, Poisoned, AlwaysFlat> {
+    let params = Params {
+        check_finite,
+        ..Params::default()
+    };
+    WLDriver::new(
+        Dice(1),
+        RollFour,
+        Face,
+        params,
+        Poisoned,
+        AlwaysFlat,
+        wanglandau::rng::seeded(1),
+    )
+}
+
+/// The second step adds the NaN ln_f to bin 3, which the check reports.
+#[test]
+fn nan_ln_g_is_reported_with_its_bin() {
+    let mut drv = poisoned_driver(false);
+    drv.step();
+    assert_eq!(drv.check_ln_g_finite(), Ok(()));
+    assert!(drv.ln_f().is_nan());
+
+    drv.step();
+    assert_eq!(drv.check_ln_g_finite(), Err(WLError::NonFinite { bin: 3 }));
+
+    // Forbidden bins are exempt
+    let mut pinned = poisoned_driver(false);
+    pinned.pin_bin(0, f64::NEG_INFINITY);
+    assert_eq!(pinned.check_ln_g_finite(), Ok(()));
+}
+
+/// With `check_finite` the step that poisons ln(g) panics.
+#[test]
+#[should_panic(expected = "ln(g) of bin 3 is not finite")]
+fn check_finite_panics_on_nan() {
+    let mut drv = poisoned_driver(true);
+    drv.step();
+    drv.step();
+}
@@ -0,0 +1,127 @@
+//! Test full-driver checkpoints and restoring them with fresh components.
+
+mod common;
+
+use common::{Dice, Face, Roll};
+use wanglandau::driver::Checkpoint;
+use wanglandau::error::WLError;
+use wanglandau::prelude::*;
+
+/// A `OneOverT` schedule with its clock at the start.
+fn fresh_schedule() -> OneOverT {
+    let mut sched = OneOverT::default();
+    sched.tol = 1e-4;
+    sched
+}
+
+fn one_over_t_driver(seed: u64) -> WLDriver<Dice, Roll, Face, Rng64, OneOverT> {
+    WLDriver::new(
+        Dice(1),
+        Roll,
+        Face,
+        Params::default(),
+        fresh_schedule(),
+        Fraction,
+        wanglandau::rng::seeded(seed),
+    )
+}
+
+/// A restored run continues step for step, including the `OneOverT` clock.
+#[test]
+fn one_over_t_run_resumes_from_checkpoint() {
+    let mut original = one_over_t_driver(17);
+    original.run(20_000);
+    assert!(
+        original.ln_f() < 0.1,
+        "too few stages: ln_f = {}",
+        original.ln_f()
+    );
+
+    let checkpoint = original.checkpoint();
+    assert_eq!(checkpoint.ln_g.as_slice(), original.ln_g());
+    assert_eq!(checkpoint.step, original.step_count());
+    let mut resumed = WLDriver::from_checkpoint(checkpoint.clone(), Roll, Face).unwrap();
+
+    for _ in 0..20_000 {
+        assert_eq!(original.step(), resumed.step());
+    }
+    assert_eq!(resumed.ln_g(), original.ln_g());
+    assert_eq!(resumed.histogram(), original.histogram());
+    assert_eq!(resumed.total_histogram(), original.total_histogram());
+    assert_eq!(resumed.ln_f(), original.ln_f());
+    assert_eq!(resumed.step_count(), original.step_count());
+
+    // Restarting the schedule's clock instead sends ln_f back up to 1/2
+    let mut reset = Checkpoint {
+        sched: fresh_schedule(),
+        ..checkpoint
+    };
+    reset.hist = vec![100; 6];
+    let mut restarted = WLDriver::from_checkpoint(reset, Roll, Face).unwrap();
+    restarted.step();
+    assert_eq!(restarted.ln_f(), 0.5);
+}
+
+/// A checkpoint for a different bin count is rejected.
+#[test]
+fn checkpoint_for_other_bins_is_rejected() {
+    let mut checkpoint = one_over_t_driver(2).checkpoint();
+    checkpoint.hist.pop();
+    assert_eq!(
+        WLDriver::from_checkpoint(checkpoint, Roll, Face).err(),
+        Some(WLError::LengthMismatch {
+            expected: 6,
+            found: 5
+        })
+    );
+}
+
+/// Checkpoints of the built-in components are serializable with `serde`.
+#[cfg(feature = "serde")]
+#[test]
+fn checkpoint_is_serializable() {
+    use wanglandau::flatness::Explained;
+    use wanglandau::schedule::Then;
+
+    fn assert_serde<T: serde::Serialize + serde::de::DeserializeOwned>() {}
+    assert_serde::<Checkpoint<u8>>();
+    assert_serde::<Checkpoint<u8, Rng64, Then<OneOverT, Geometric>, Explained<Fraction>>>();
+}
+
+/// A checkpoint whose previous-stage ln(g) does not fit `kl_stop` is rejected.
+#[test]
+fn checkpoint_with_short_kl_prev_is_rejected() {
+    let params = Params {
+        kl_stop: Some(1e-6),
+        ..Params::default()
+    };
+    let mut checkpoint = common::dice_driver_with(params, 1e-4, 3).checkpoint();
+    assert_eq!(checkpoint.kl_prev.len(), 6);
+    checkpoint.kl_prev.pop();
+    assert_eq!(
+        WLDriver::from_checkpoint(checkpoint, Roll, Face).err(),
+        Some(WLError::LengthMismatch {
+            expected: 6,
+            found: 5
+        })
+    );
+}
+
+/// Frozen bins stay frozen, and out of the update, on restore.
+#[test]
+fn frozen_bins_survive_checkpoint() {
+    let mut original = common::dice_driver(1e-6, 5).with_local_freezing(0.05, 2);
+    original.run(200_000);
+    let frozen = original.frozen_bins();
+    assert!(frozen.len() >= 2, "too few frozen bins: {frozen:?}");
+
+    let checkpoint = original.checkpoint();
+    assert!(checkpoint.freeze.is_some());
+    let mut resumed = WLDriver::from_checkpoint(checkpoint, Roll, Face).unwrap();
+    assert_eq!(resumed.frozen_bins(), frozen);
+    for _ in 0..20_000 {
+        assert_eq!(original.step(), resumed.step());
+    }
+    assert_eq!(resumed.ln_g(), original.ln_g());
+    assert_eq!(resumed.frozen_bins(), original.frozen_bins());
+}
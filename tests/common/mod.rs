@@ -0,0 +1,62 @@
+//! Toy models shared by the integration tests.
+//!
+//! Each test crate only uses a subset of these, so dead-code warnings are
+//! silenced for the whole module.
+
+#![allow(dead_code)]
+
+use wanglandau::prelude::*;
+
+/// A system representing a six-sided die with values 1-6
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dice(pub u8);
+impl State for Dice {}
+
+/// A move that randomly rolls the die to a new value
+pub struct Roll;
+impl<R: rand::RngCore> Move<Dice, R> for Roll {
+    fn propose(&mut self, s: &mut Dice, rng: &mut R) {
+        use rand::Rng;
+        s.0 = rng.random_range(1..=6);
+    }
+}
+
+/// Maps die values to bins (0-5 for values 1-6)
+pub struct Face;
+impl Macrospace<Dice> for Face {
+    type Bin = usize;
+    fn locate(&self, s: &Dice) -> usize {
+        (s.0 - 1) as usize
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4, 5]
+    }
+}
+
+/// Builds a dice driver with single-proposal sweeps and a geometric schedule.
+pub fn dice_driver(tol: f64, seed: u64) -> WLDriver<Dice, Roll, Face> {
+    dice_driver_with(Params::default(), tol, seed)
+}
+
+/// Builds a dice driver with custom parameters and a geometric schedule.
+pub fn dice_driver_with(params: Params, tol: f64, seed: u64) -> WLDriver<Dice, Roll, Face> {
+    WLDriver::new(
+        Dice(1),
+        Roll,
+        Face,
+        params,
+        Geometric { alpha: 0.5, tol },
+        Fraction,
+        wanglandau::rng::seeded(seed),
+    )
+}
+
+/// Spread (max - min) of a slice of values.
+pub fn spread(values: &[f64]) -> f64 {
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &x| {
+            (a.min(x), b.max(x))
+        });
+    max - min
+}
@@ -0,0 +1,43 @@
+//! Test the static external bias added to ln(g) during acceptance.
+
+mod common;
+
+use common::{dice_driver, spread};
+use wanglandau::error::WLError;
+
+/// Favouring one face raises its visitation, yet ln_g + bias stays flat.
+#[test]
+fn external_bias_steers_but_does_not_bias_estimate() {
+    let bias = vec![0.0, 0.0, -2.0, 0.0, 0.0, 0.0];
+
+    // With ln g frozen at zero the bias alone sets the sampling weights
+    let mut frozen = dice_driver(1e-6, 8);
+    frozen.set_external_bias(bias.clone()).unwrap();
+    let mut visits = [0u64; 6];
+    frozen.produce(200_000, |s| visits[(s.0 - 1) as usize] += 1);
+    // Expected ratio to every other face is e² ≈ 7.4
+    for (face, &v) in visits.iter().enumerate().filter(|&(f, _)| f != 2) {
+        assert!(visits[2] > 5 * v, "face {} visits {:?}", face, visits);
+    }
+
+    // After adaptation ln g absorbs the bias
+    let mut drv = dice_driver(1e-7, 8);
+    drv.set_external_bias(bias).unwrap();
+    drv.run(10_000_000);
+    let corrected: Vec<f64> = drv
+        .ln_g()
+        .iter()
+        .zip(drv.external_bias())
+        .map(|(g, b)| g + b)
+        .collect();
+    assert!(spread(&corrected) < 0.5, "corrected ln g {:?}", corrected);
+    assert!((drv.ln_g()[2] - drv.ln_g()[0] - 2.0).abs() < 0.5);
+
+    assert_eq!(
+        drv.set_external_bias(vec![0.0; 2]),
+        Err(WLError::LengthMismatch {
+            expected: 6,
+            found: 2
+        })
+    );
+}
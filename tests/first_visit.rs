@@ -0,0 +1,153 @@
+//! Test first-visit tracking and its callback.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::dice_driver;
+
+/// The callback fires once per bin, at the step the tracker reports.
+#[test]
+fn first_visit_callback_fires_once_per_bin() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    let mut drv = dice_driver(1e-6, 23).on_first_visit(move |bin, step| {
+        log.lock().unwrap().push((bin, step));
+    });
+    drv.run(1_000_000);
+
+    let mut seen = seen.lock().unwrap().clone();
+    assert_eq!(seen.len(), 6, "{:?}", seen);
+    assert!(seen.windows(2).all(|w| w[0].1 <= w[1].1), "out of order");
+
+    seen.sort();
+    let bins: Vec<usize> = seen.iter().map(|&(b, _)| b).collect();
+    assert_eq!(bins, [0, 1, 2, 3, 4, 5]);
+    for &(bin, step) in &seen {
+        assert_eq!(drv.first_visit_steps()[bin], Some(step));
+        assert!(drv.total_histogram()[bin] > 0);
+    }
+    assert!(drv.total_histogram().iter().sum::<u64>() > drv.histogram().iter().sum::<u64>());
+}
+
+/// The run stops on the step the target bin's cumulative count is reached.
+#[test]
+fn run_until_bin_count_stops_at_threshold() {
+    let mut drv = dice_driver(0.0, 24);
+    assert!(drv.run_until_bin_count(4, 5000, 10_000_000));
+
+    // Single-proposal sweeps add one visit per step, so it lands exactly
+    assert_eq!(drv.total_histogram()[4], 5000);
+    // Well past the first flat events, which reset the stage histogram
+    assert!(drv.ln_f() < 1.0);
+    assert!(drv.histogram()[4] < 5000);
+
+    // Already reached: no further steps
+    let steps = drv.step_count();
+    assert!(drv.run_until_bin_count(4, 500, 10));
+    assert_eq!(drv.step_count(), steps);
+
+    // Out of budget
+    assert!(!drv.run_until_bin_count(4, 1_000_000, 10));
+}
+
+/// Recent rates of bins the walker can no longer reach decay towards zero.
+#[test]
+fn recent_visit_rate_decays_for_unreachable_bins() {
+    let mut drv = dice_driver(0.0, 25).with_visit_rate_window(100);
+    drv.run(2_000);
+    let rates = drv.recent_visit_rate();
+    assert!(rates.iter().all(|&r| r > 0.05), "{:?}", rates);
+    assert!((rates.iter().sum::<f64>() - 1.0).abs() < 0.01);
+
+    // A prohibitive bias confines the walker to faces 1-3
+    drv.set_external_bias(vec![0.0, 0.0, 0.0, 1e9, 1e9, 1e9])
+        .unwrap();
+    drv.run(2_000);
+    let rates = drv.recent_visit_rate();
+    for (bin, &r) in rates.iter().enumerate() {
+        if bin < 3 {
+            assert!(r > 0.2, "{:?}", rates);
+        } else {
+            assert!(r < 1e-6, "{:?}", rates);
+        }
+    }
+    assert!(drv.total_histogram()[3..].iter().all(|&h| h > 0));
+}
+
+/// Bins narrower than the spacing of the die faces are reported as too fine.
+#[test]
+fn over_fine_binning_is_reported() {
+    use wanglandau::driver::BinningSuggestion;
+    use wanglandau::macrospace::EnergyBins;
+    use wanglandau::prelude::*;
+
+    let face = |s: &common::Dice| s.0 as f64;
+    let mut fine = WLDriver::new(
+        common::Dice(1),
+        common::Roll,
+        EnergyBins::new(face, 0.1, 0.95, 60),
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-6,
+        },
+        Fraction,
+        wanglandau::rng::seeded(24),
+    );
+    fine.run(20_000);
+    let report = fine.binning_quality();
+    assert!((report.empty_fraction - 0.9).abs() < 1e-12, "{:?}", report);
+    assert_eq!(report.suggestion, BinningSuggestion::TooFine);
+
+    let mut drv = dice_driver(1e-6, 24);
+    drv.run(1_000_000);
+    let report = drv.binning_quality();
+    assert_eq!(report.empty_fraction, 0.0);
+    assert!(report.occupancy_ratio.unwrap() < 1.5, "{:?}", report);
+    assert_eq!(report.suggestion, BinningSuggestion::Ok);
+}
+
+/// Only ever proposes the first two faces.
+struct LowRoll;
+impl<R: rand::RngCore> wanglandau::prelude::Move<common::Dice, R> for LowRoll {
+    fn propose(&mut self, s: &mut common::Dice, rng: &mut R) {
+        use rand::Rng;
+        s.0 = rng.random_range(1..=2);
+    }
+}
+
+/// The extremes of a skewed cumulative histogram point at the starved bins.
+#[test]
+fn extremes_of_skewed_histogram() {
+    use wanglandau::prelude::*;
+
+    let mut drv = WLDriver::new(
+        common::Dice(1),
+        LowRoll,
+        common::Face,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-8,
+        },
+        Fraction,
+        wanglandau::rng::seeded(5),
+    );
+    drv.run(1_000);
+
+    let total = drv.total_histogram().to_vec();
+    let (least, most) = drv.extremes();
+    assert_eq!(least, (2, 0), "first unreached face wins the tie");
+    assert!(most.0 < 2);
+    assert_eq!(most.1, total[0].max(total[1]));
+    assert_eq!(most.1, total[most.0]);
+
+    // Masking out the unreachable faces leaves the two sampled ones
+    drv.set_active_mask(vec![true, true, false, false, false, false])
+        .unwrap();
+    let (least, most) = drv.extremes();
+    assert_eq!(least.1, total[0].min(total[1]));
+    assert!(least.0 < 2 && most.0 < 2);
+    assert_eq!(most.1, total[0].max(total[1]));
+}
@@ -0,0 +1,177 @@
+//! Test the flatness criteria and decorators.
+
+mod common;
+
+use wanglandau::flatness::Explained;
+use wanglandau::prelude::*;
+
+/// A failed check names the starving bin; a passing check clears the reason.
+#[test]
+fn explained_identifies_starving_bin() {
+    let flat = Explained::new(Fraction);
+    assert!(flat.explanation().is_none());
+
+    let hist = [40, 38, 41, 3, 39, 40];
+    assert_eq!(flat.is_flat(&hist, 0.8), Fraction.is_flat(&hist, 0.8));
+    let reason = flat.explanation().expect("no explanation recorded");
+    assert!(
+        reason.starts_with("least-visited bin 3: 3 visits"),
+        "{}",
+        reason
+    );
+    assert!(reason.ends_with("threshold 0.8"), "{}", reason);
+
+    assert!(flat.is_flat(&[40, 38, 41, 39, 39, 40], 0.8));
+    assert!(flat.explanation().is_none());
+}
+
+/// The explanation holds for criteria other than `Fraction`.
+#[test]
+fn explained_is_true_for_rms() {
+    // min/mean = 100/107.5 = 0.930 clears 0.9, but σ/μ = 0.121 exceeds 0.1
+    let flat = Explained::new(RMS);
+    assert!(!flat.is_flat(&[100, 100, 100, 130], 0.9));
+    assert_eq!(
+        flat.explanation().unwrap(),
+        "least-visited bin 0: 100 visits, min/mean 0.930, threshold 0.9"
+    );
+}
+
+/// Per-bin thresholds are explained by the bin furthest below its own bound.
+#[test]
+fn explained_per_bin_names_worst_bin() {
+    let flat = Explained::new(Fraction);
+    let thresholds = [0.5, 0.5, 0.95, 0.95, 0.5, 0.5];
+
+    // Mean 40: bin 0 passes its loose bound, bin 2 misses its strict one
+    let hist = [25, 45, 36, 44, 45, 45];
+    assert!(!flat.is_flat_per_bin(&hist, &thresholds));
+    let reason = flat.explanation().expect("no explanation recorded");
+    assert!(reason.starts_with("worst bin 2: 36 visits"), "{}", reason);
+    assert!(reason.ends_with("threshold 0.95"), "{}", reason);
+
+    assert!(flat.is_flat_per_bin(&[25, 45, 39, 44, 45, 42], &thresholds));
+    assert!(flat.explanation().is_none());
+}
+
+/// A move that never rolls a six.
+struct LowRoll;
+impl<R: rand::RngCore> Move<common::Dice, R> for LowRoll {
+    fn propose(&mut self, s: &mut common::Dice, rng: &mut R) {
+        use rand::Rng;
+        s.0 = rng.random_range(1..=5);
+    }
+}
+
+/// With bins masked out the explanation names real bins, not positions.
+#[test]
+fn explained_names_real_bins_under_a_mask() {
+    let mut drv = WLDriver::new(
+        common::Dice(2),
+        LowRoll,
+        common::Face,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-4,
+        },
+        Explained::new(Fraction),
+        wanglandau::rng::seeded(8),
+    );
+    let mut mask = vec![true; 6];
+    mask[0] = false;
+    drv.set_active_mask(mask).unwrap();
+    drv.run(1_000);
+
+    // Bin 5 starves; position 4 of the masked histogram is bin 5
+    let reason = drv
+        .flatness()
+        .explanation()
+        .expect("no explanation recorded");
+    assert!(
+        reason.starts_with("least-visited bin 5: 0 visits"),
+        "{}",
+        reason
+    );
+}
+
+/// Every stage of a run with a strict critical window meets the tighter bound.
+#[test]
+fn strict_bins_hold_back_flat_events() {
+    let mut thresholds = vec![0.3; 6];
+    thresholds[2] = 0.97;
+    thresholds[3] = 0.97;
+    let mut drv = common::dice_driver(1e-4, 31).with_stage_histograms();
+    drv.set_bin_flatness(thresholds.clone()).unwrap();
+    assert_eq!(
+        drv.set_bin_flatness(vec![0.8; 3]),
+        Err(wanglandau::error::WLError::LengthMismatch {
+            expected: 6,
+            found: 3
+        })
+    );
+    drv.run(10_000_000);
+
+    let stages = drv.stage_histograms();
+    assert!(!stages.is_empty());
+    let mut loosest: f64 = 1.0;
+    for hist in stages {
+        let mean = hist.iter().sum::<u64>() as f64 / hist.len() as f64;
+        for (bin, &h) in hist.iter().enumerate() {
+            assert!(h as f64 >= thresholds[bin] * mean, "{:?}", hist);
+        }
+        loosest = loosest.min(hist.iter().map(|&h| h as f64 / mean).fold(1.0, f64::min));
+    }
+    // The loose bins were allowed to flatten below the strict bound
+    assert!(loosest < 0.97, "{}", loosest);
+}
+
+/// Records every threshold it is asked to check against.
+struct Recording(std::sync::Arc<std::sync::Mutex<Vec<f64>>>);
+impl Flatness for Recording {
+    fn is_flat(&self, hist: &[u64], flatness: f64) -> bool {
+        self.0.lock().unwrap().push(flatness);
+        Fraction.is_flat(hist, flatness)
+    }
+}
+
+/// A flatness schedule loosens the threshold passed to the criterion stage by stage.
+#[test]
+fn flatness_schedule_loosens_across_stages() {
+    let schedule = |stage: u32| 0.95 - 0.25 * (stage.min(10) as f64 / 10.0);
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut drv = WLDriver::new(
+        common::Dice(1),
+        common::Roll,
+        common::Face,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-4,
+        },
+        Recording(seen.clone()),
+        wanglandau::rng::seeded(6),
+    )
+    .with_flatness_schedule(schedule);
+    assert_eq!(drv.effective_flatness(), 0.95);
+
+    let mut stage = 0;
+    loop {
+        let outcome = drv.step_detailed();
+        // Each check of the stage used the schedule's value for that stage
+        assert_eq!(*seen.lock().unwrap().last().unwrap(), schedule(stage));
+        if outcome.flat_event {
+            stage += 1;
+            assert_eq!(drv.effective_flatness(), schedule(stage));
+        }
+        if outcome.converged {
+            break;
+        }
+    }
+    assert_eq!(stage, 14);
+
+    let mut used: Vec<f64> = seen.lock().unwrap().clone();
+    used.dedup();
+    let expected: Vec<f64> = (0..=10).map(schedule).collect();
+    assert_eq!(used, expected);
+}
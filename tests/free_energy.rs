@@ -0,0 +1,107 @@
+//! Test the free-energy profile and entropy derived from ln(g).
+
+use rand::Rng;
+use wanglandau::prelude::*;
+
+/// Microstates per bin: a symmetric double well in -ln g
+const MULTIPLICITY: [usize; 7] = [8, 4, 2, 1, 2, 4, 8];
+
+/// A microstate, identified by its global index
+#[derive(Clone)]
+struct Micro(usize);
+impl State for Micro {}
+
+/// Jumps to a uniformly random microstate
+struct Jump;
+impl<R: rand::RngCore> Move<Micro, R> for Jump {
+    fn propose(&mut self, s: &mut Micro, rng: &mut R) {
+        s.0 = rng.random_range(0..MULTIPLICITY.iter().sum::<usize>());
+    }
+}
+
+/// Maps a microstate to its position along the coordinate
+struct Coordinate;
+impl Macrospace<Micro> for Coordinate {
+    type Bin = usize;
+    fn locate(&self, s: &Micro) -> usize {
+        let mut rest = s.0;
+        for (bin, &m) in MULTIPLICITY.iter().enumerate() {
+            if rest < m {
+                return bin;
+            }
+            rest -= m;
+        }
+        unreachable!()
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4, 5, 6]
+    }
+}
+
+/// Builds a driver over the double-well coordinate.
+fn double_well() -> WLDriver<Micro, Jump, Coordinate> {
+    WLDriver::new(
+        Micro(0),
+        Jump,
+        Coordinate,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-7,
+        },
+        Fraction,
+        wanglandau::rng::seeded(4),
+    )
+}
+
+/// The two wells have equal free energy and the barrier between them is positive.
+#[test]
+fn double_well_profile() {
+    let mut drv = double_well();
+    drv.run(10_000_000);
+
+    let beta = 2.0;
+    let f = drv.free_energy_profile(beta);
+    assert_eq!(f.iter().copied().fold(f64::INFINITY, f64::min), 0.0);
+    assert!((f[0] - f[6]).abs() < 0.15, "wells differ: {:?}", f);
+
+    // The barrier top holds 1 of the 8 microstates of a well
+    let barrier = f[3] - f[0].max(f[6]);
+    assert!(barrier > 0.0);
+    assert!((barrier - 8f64.ln() / beta).abs() < 0.15, "{:?}", f);
+}
+
+/// The entropy is ln(g) scaled by k_B, with the shape of ln(g) preserved and
+/// the offset set by an optional reference bin.
+#[test]
+fn entropy_scales_ln_g() {
+    let mut drv = double_well();
+    drv.run(10_000_000);
+
+    let k_b = 8.617e-5; // eV/K
+    let entropy = drv.entropy(k_b, None);
+    assert_eq!(drv.entropy(1.0, None), drv.ln_g());
+    for (s, g) in entropy.iter().zip(drv.ln_g()) {
+        assert!((s - k_b * g).abs() < 1e-15);
+    }
+
+    // A well holds 8 microstates and the barrier top 1
+    let drop = (entropy[0] - entropy[3]) / k_b;
+    assert!((drop - 8f64.ln()).abs() < 0.15, "{:?}", entropy);
+
+    // With the barrier top as reference, S = k_B ln 1 = 0 there
+    let anchored = drv.entropy(k_b, Some((3, 0.0)));
+    assert_eq!(anchored[3], 0.0);
+    assert!(
+        (anchored[0] / k_b - 8f64.ln()).abs() < 0.15,
+        "{:?}",
+        anchored
+    );
+    for (a, s) in anchored.iter().zip(&entropy) {
+        assert!((a - s - (anchored[0] - entropy[0])).abs() < 1e-15);
+    }
+
+    let pairs = drv.entropy_per_bin_with_energies(k_b, Some((3, 0.0)), |bin| bin as f64 * 0.5);
+    assert_eq!(pairs.len(), 7);
+    assert_eq!(pairs[4], (2.0, anchored[4]));
+}
@@ -0,0 +1,101 @@
+//! Test adaptive freezing of locally converged bins.
+
+use wanglandau::prelude::*;
+use wanglandau::rng;
+
+/// Two dice that are rerolled one at a time
+#[derive(Clone)]
+struct Pair(u8, u8);
+impl State for Pair {}
+
+/// Rerolls one of the two dice
+struct RerollOne;
+impl<R: rand::RngCore> Move<Pair, R> for RerollOne {
+    fn propose(&mut self, s: &mut Pair, rng: &mut R) {
+        use rand::Rng;
+        let face = rng.random_range(1..=6);
+        if rng.random() {
+            s.0 = face;
+        } else {
+            s.1 = face;
+        }
+    }
+}
+
+/// Bins the sum of the dice, 2..=12
+struct Sum;
+impl Macrospace<Pair> for Sum {
+    type Bin = usize;
+    fn locate(&self, s: &Pair) -> usize {
+        (s.0 + s.1 - 2) as usize
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+    }
+}
+
+/// Builds a two-dice driver with a geometric schedule.
+fn pair_driver(seed: u64) -> WLDriver<Pair, RerollOne, Sum> {
+    WLDriver::new(
+        Pair(1, 1),
+        RerollOne,
+        Sum,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-7,
+        },
+        Fraction,
+        rng::seeded(seed),
+    )
+}
+
+/// Largest deviation between two ln g curves after aligning their means.
+fn deviation(a: &[f64], b: &[f64]) -> f64 {
+    let offset = (a.iter().sum::<f64>() - b.iter().sum::<f64>()) / a.len() as f64;
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - offset - y).abs())
+        .fold(0.0, f64::max)
+}
+
+/// Bins freeze one by one and keep their ln g from then on, the run finishes
+/// sooner, and ln g is unchanged.
+#[test]
+fn frozen_bins_speed_up_convergence() {
+    let mut plain = pair_driver(0);
+    plain.run(10_000_000);
+    assert!(plain.ln_f() < 1e-7);
+
+    let mut frozen = pair_driver(0).with_local_freezing(1e-3, 3);
+    let mut partial = false;
+    let mut at_freeze = [None; 11];
+    for _ in 0..10_000_000 {
+        let n_frozen = frozen.frozen_bins().len();
+        partial |= n_frozen > 0 && n_frozen < 11;
+        for bin in frozen.frozen_bins() {
+            let value = *at_freeze[bin].get_or_insert(frozen.ln_g()[bin]);
+            assert_eq!(frozen.ln_g()[bin], value, "frozen bin {} moved", bin);
+        }
+        if frozen.step() {
+            break;
+        }
+    }
+    assert!(frozen.ln_f() < 1e-7);
+    assert!(partial, "bins were never frozen while others still moved");
+    assert_eq!(frozen.frozen_bins().len(), 11);
+    // Freezing is separate from pinning
+    assert!(!frozen.is_pinned(4));
+
+    assert!(
+        frozen.step_count() < plain.step_count(),
+        "frozen {} vs plain {} steps",
+        frozen.step_count(),
+        plain.step_count()
+    );
+    let exact: Vec<f64> = (2..=12)
+        .map(|s: i32| ((6 - (s - 7).abs()) as f64).ln())
+        .collect();
+    assert!(deviation(frozen.ln_g(), plain.ln_g()) < 0.05);
+    assert!(deviation(frozen.ln_g(), &exact) < 0.4);
+}
@@ -0,0 +1,102 @@
+//! Test pre-loading the visit histogram.
+
+mod common;
+
+use common::{dice_driver, spread};
+use wanglandau::error::WLError;
+
+/// Returns the step count at the first flat event.
+fn first_flat(
+    drv: &mut wanglandau::prelude::WLDriver<common::Dice, common::Roll, common::Face>,
+) -> u64 {
+    let ln_f0 = drv.ln_f();
+    while drv.ln_f() == ln_f0 {
+        drv.step();
+    }
+    drv.step_count()
+}
+
+/// Seeding the histogram moves the first flat event but not the converged ln g.
+#[test]
+fn seeded_histogram_shifts_first_flat_event() {
+    let mut plain = dice_driver(1e-6, 21);
+    let mut seeded = dice_driver(1e-6, 21);
+    seeded.set_histogram(&[200, 0, 0, 0, 0, 0]).unwrap();
+
+    let plain_first = first_flat(&mut plain);
+    let seeded_first = first_flat(&mut seeded);
+    assert!(
+        seeded_first > plain_first,
+        "seeded {} vs plain {}",
+        seeded_first,
+        plain_first
+    );
+
+    plain.run(10_000_000);
+    seeded.run(10_000_000);
+    assert!(spread(plain.ln_g()) < 0.5);
+    assert!(spread(seeded.ln_g()) < 0.5);
+}
+
+/// A histogram of the wrong length is rejected and leaves the driver untouched.
+#[test]
+fn set_histogram_validates_length() {
+    let mut drv = dice_driver(1e-6, 21);
+    assert_eq!(
+        drv.set_histogram(&[1, 2, 3]),
+        Err(WLError::LengthMismatch {
+            expected: 6,
+            found: 3
+        })
+    );
+    assert!(drv.histogram().iter().all(|&h| h == 0));
+}
+
+/// Bins below the threshold fraction of the mean count are flagged.
+#[test]
+fn undersampled_bins_of_skewed_histogram() {
+    let mut drv = dice_driver(1e-6, 22);
+    assert!(drv.undersampled_bins(0.8).is_empty());
+
+    // Mean 100
+    drv.set_histogram(&[150, 10, 120, 79, 80, 161]).unwrap();
+    assert_eq!(drv.undersampled_bins(0.8), vec![1, 3]);
+    assert_eq!(drv.undersampled_bins(0.05), Vec::<usize>::new());
+    assert_eq!(drv.undersampled_bins(1.3), vec![1, 2, 3, 4]);
+}
+
+/// Masked-out bins are neither flagged nor counted towards the mean.
+#[test]
+fn undersampled_bins_skip_inactive_bins() {
+    let mut drv = dice_driver(1e-6, 22);
+    drv.set_active_mask(vec![true, true, false, true, true, false])
+        .unwrap();
+
+    // Mean 100 over the four active bins; the zeros would halve it
+    drv.set_histogram(&[120, 100, 0, 79, 101, 0]).unwrap();
+    assert_eq!(drv.undersampled_bins(0.8), vec![3]);
+    assert_eq!(drv.undersampled_bins(0.5), Vec::<usize>::new());
+}
+
+/// Visits concentrated in one bin with others unvisited flag a stall.
+#[test]
+fn saturation_of_concentrated_histogram() {
+    let mut drv = dice_driver(1e-6, 22);
+    assert!(!drv.is_saturated(0.5));
+
+    drv.set_histogram(&[900, 50, 50, 0, 0, 0]).unwrap();
+    assert!(drv.is_saturated(0.8));
+    assert!(!drv.is_saturated(0.95));
+
+    // Full coverage is never saturated, however uneven
+    drv.set_histogram(&[900, 20, 20, 20, 20, 20]).unwrap();
+    assert!(!drv.is_saturated(0.8));
+    drv.set_histogram(&[100; 6]).unwrap();
+    assert!(!drv.is_saturated(0.1));
+
+    // Masked-out bins do not make coverage incomplete
+    drv.set_histogram(&[900, 50, 50, 0, 0, 0]).unwrap();
+    drv.set_active_mask(vec![true, true, true, false, false, false])
+        .unwrap();
+    assert!(!drv.is_saturated(0.8));
+}
@@ -0,0 +1,40 @@
+//! Test starting the walker from a configuration ensemble.
+
+mod common;
+
+use std::collections::HashSet;
+
+use common::{Dice, Face, Roll};
+use wanglandau::prelude::*;
+
+fn ensemble_driver(seed: u64) -> WLDriver<Dice, Roll, Face> {
+    WLDriver::with_initial_ensemble(
+        vec![Dice(2), Dice(4), Dice(6)],
+        Roll,
+        Face,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-6,
+        },
+        Fraction,
+        wanglandau::rng::seeded(seed),
+    )
+}
+
+/// The starting state is always an ensemble member, and every member gets picked.
+#[test]
+fn start_is_drawn_from_ensemble() {
+    let members = [Dice(2), Dice(4), Dice(6)];
+    let mut seen = HashSet::new();
+    for seed in 0..50 {
+        let mut drv = ensemble_driver(seed);
+        assert!(members.contains(drv.state()), "{:?}", drv.state());
+        seen.insert(drv.state().0);
+
+        drv.run(100);
+        drv.reset_to_random_initial();
+        assert!(members.contains(drv.state()), "{:?}", drv.state());
+    }
+    assert_eq!(seen.len(), 3, "not every member was picked: {:?}", seen);
+}
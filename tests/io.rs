@@ -0,0 +1,237 @@
+//! Test reading and writing results with `wanglandau::io`.
+
+mod common;
+
+use std::io::ErrorKind;
+
+use wanglandau::io::{read_binary, read_dos, write_binary, write_dos};
+
+/// A large synthetic ln g survives a round trip bit for bit.
+#[test]
+fn binary_round_trip_is_exact() {
+    let n = 1_000_000;
+    let ln_g: Vec<f64> = (0..n).map(|i| (i as f64).sqrt() * 1.000_000_1).collect();
+    let hist: Vec<u64> = (0..n as u64).map(|i| i * 7 % 1013).collect();
+
+    let mut buf = Vec::new();
+    write_binary(&mut buf, &ln_g, &hist).unwrap();
+    assert_eq!(buf.len(), 20 + 16 * n);
+
+    let (ln_g_back, hist_back) = read_binary(buf.as_slice()).unwrap();
+    assert_eq!(ln_g_back, ln_g);
+    assert_eq!(hist_back, hist);
+}
+
+/// Corrupted headers and truncated data are rejected.
+#[test]
+fn binary_reader_validates_header() {
+    let mut buf = Vec::new();
+    write_binary(&mut buf, &[1.0, 2.0], &[3, 4]).unwrap();
+
+    let mut bad_magic = buf.clone();
+    bad_magic[0] ^= 0xff;
+    assert_eq!(
+        read_binary(bad_magic.as_slice()).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let mut bad_version = buf.clone();
+    bad_version[8] = 99;
+    assert_eq!(
+        read_binary(bad_version.as_slice()).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let truncated = &buf[..buf.len() - 1];
+    assert_eq!(
+        read_binary(truncated).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let err = write_binary(Vec::new(), &[1.0], &[]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+/// Binary files of two independent dice runs merge into a flat ln(g) and the
+/// sum of their histograms.
+#[test]
+fn merged_binary_files_average_runs() {
+    use wanglandau::analysis::merge_binary;
+
+    let checkpoints: Vec<Vec<u8>> = [31, 32]
+        .into_iter()
+        .map(|seed| {
+            let mut drv = common::dice_driver(1e-4, seed);
+            drv.run(1_000_000);
+            let mut buf = Vec::new();
+            write_binary(&mut buf, drv.ln_g(), drv.total_histogram()).unwrap();
+            buf
+        })
+        .collect();
+
+    let (merged, hist) = merge_binary(checkpoints.iter().map(|c| c.as_slice()).collect()).unwrap();
+    assert_eq!(merged.len(), 6);
+    assert!(common::spread(&merged) < 0.3, "{:?}", merged);
+
+    // Identical to merging the curves directly at equal ln_f
+    let runs: Vec<(Vec<f64>, Vec<u64>)> = checkpoints
+        .iter()
+        .map(|c| read_binary(c.as_slice()).unwrap())
+        .collect();
+    let direct =
+        wanglandau::analysis::weighted_merge(&[(runs[0].0.clone(), 1.0), (runs[1].0.clone(), 1.0)]);
+    assert_eq!(merged, direct);
+    for (bin, &h) in hist.iter().enumerate() {
+        assert!(h > 0);
+        assert_eq!(h, runs[0].1[bin] + runs[1].1[bin]);
+    }
+
+    // Checkpoints of different sizes cannot be merged
+    let mut small = Vec::new();
+    write_binary(&mut small, &[0.0], &[1]).unwrap();
+    let err = merge_binary(vec![checkpoints[0].as_slice(), small.as_slice()]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+/// Forbidden bins shared by every file stay at -inf; mismatched ones are
+/// rejected.
+#[test]
+fn merged_binary_files_keep_forbidden_bins() {
+    use wanglandau::analysis::merge_binary;
+
+    let inf = f64::NEG_INFINITY;
+    let write = |ln_g: &[f64], hist: &[u64]| {
+        let mut buf = Vec::new();
+        write_binary(&mut buf, ln_g, hist).unwrap();
+        buf
+    };
+    let a = write(&[0.0, inf, 1.0, 2.0], &[3, 0, 3, 3]);
+    let b = write(&[5.0, inf, 6.4, 7.0], &[2, 0, 4, 3]);
+
+    let (ln_g, hist) = merge_binary(vec![a.as_slice(), b.as_slice()]).unwrap();
+    assert_eq!(ln_g[1], inf);
+    assert_eq!(ln_g[0], 0.0);
+    assert!((ln_g[2] - 1.2).abs() < 1e-12, "{:?}", ln_g);
+    assert!((ln_g[3] - 2.0).abs() < 1e-12, "{:?}", ln_g);
+    assert_eq!(hist, [5, 0, 7, 6]);
+
+    let c = write(&[0.0, 1.0, inf, 2.0], &[3, 3, 0, 3]);
+    let err = merge_binary(vec![a.as_slice(), c.as_slice()]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let nan = write(&[0.0, f64::NAN, 1.0, 2.0], &[3, 3, 3, 3]);
+    assert!(merge_binary(vec![nan.as_slice()]).is_err());
+}
+
+/// Checkpoints of two dice runs merge into a flat ln(g), weighted by ln_f.
+#[test]
+fn merged_checkpoints_weight_by_ln_f() {
+    use wanglandau::analysis::{merge_checkpoints, weighted_merge};
+    use wanglandau::driver::Checkpoint;
+
+    let stored: Vec<Checkpoint<common::Dice>> = [(31, 1e-4), (32, 1e-2)]
+        .into_iter()
+        .map(|(seed, tol)| {
+            let mut drv = common::dice_driver(tol, seed);
+            drv.run(1_000_000);
+            drv.checkpoint()
+        })
+        .collect();
+    assert!(stored[0].ln_f < stored[1].ln_f);
+
+    // Stands in for a serde format: each reader names a stored checkpoint
+    let load = |mut r: &[u8]| {
+        let mut index = [0];
+        std::io::Read::read_exact(&mut r, &mut index)?;
+        Ok(stored[index[0] as usize].clone())
+    };
+    let merged = merge_checkpoints(vec![&[0][..], &[1][..]], load).unwrap();
+    assert!(common::spread(&merged) < 0.3, "{:?}", merged);
+    let direct = weighted_merge(&[
+        (stored[0].ln_g.clone(), stored[0].ln_f),
+        (stored[1].ln_g.clone(), stored[1].ln_f),
+    ]);
+    assert_eq!(merged, direct);
+
+    // A checkpoint without a positive ln_f cannot be weighted
+    let paused = |_: &[u8]| {
+        Ok(Checkpoint {
+            ln_f: 0.0,
+            ..stored[0].clone()
+        })
+    };
+    let err = merge_checkpoints(vec![&[0][..]], paused).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+/// Three appended snapshots parse back with their step labels.
+#[test]
+fn snapshot_trace_round_trip() {
+    use wanglandau::io::read_snapshots;
+
+    let mut drv = common::dice_driver(1e-6, 33);
+    let mut trace = Vec::new();
+    let mut expected = Vec::new();
+    for _ in 0..3 {
+        drv.run(500);
+        let label = drv.step_count();
+        drv.append_ln_g_snapshot(&mut trace, label).unwrap();
+        expected.push((label, drv.ln_g().to_vec()));
+    }
+
+    let text = String::from_utf8(trace.clone()).unwrap();
+    assert_eq!(text.lines().count(), 3);
+
+    let snapshots = read_snapshots(trace.as_slice()).unwrap();
+    assert_eq!(snapshots, expected);
+    assert!(snapshots.windows(2).all(|w| w[0].0 < w[1].0));
+
+    let err = read_snapshots(&b"10 1.0 x\n"[..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+/// Energies and ln g, including a forbidden bin, survive a `.dos` round trip.
+#[test]
+fn dos_round_trip_is_exact() {
+    let energies: Vec<f64> = (0..50).map(|i| -2.0 + 0.1 * i as f64).collect();
+    let mut ln_g: Vec<f64> = energies.iter().map(|e| (e * e).sqrt() * 3.7).collect();
+    ln_g[7] = f64::NEG_INFINITY;
+
+    let mut buf = Vec::new();
+    write_dos(&mut buf, &energies, &ln_g).unwrap();
+    let (energies_back, ln_g_back) = read_dos(buf.as_slice()).unwrap();
+    assert_eq!(energies_back, energies);
+    assert_eq!(ln_g_back, ln_g);
+
+    let err = write_dos(&mut Vec::new(), &[0.0], &[1.0, 2.0]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+/// The header is a comment and every other line is a pair of numbers.
+#[test]
+fn dos_layout_is_two_columns() {
+    let mut buf = Vec::new();
+    write_dos(&mut buf, &[1.0, 2.5, 4.0], &[0.0, 0.5, 3.25]).unwrap();
+    let text = String::from_utf8(buf).unwrap();
+
+    let mut lines = text.lines();
+    assert!(lines.next().unwrap().starts_with('#'));
+    let rows: Vec<(f64, f64)> = lines
+        .map(|line| {
+            let cols: Vec<f64> = line
+                .split_whitespace()
+                .map(|v| v.parse().unwrap())
+                .collect();
+            assert_eq!(cols.len(), 2, "{:?}", line);
+            (cols[0], cols[1])
+        })
+        .collect();
+    assert_eq!(rows, [(1.0, 0.0), (2.5, 0.5), (4.0, 3.25)]);
+
+    // Foreign comments and blank lines are skipped; malformed rows are not
+    let foreign = b"# E lnG\n\n# generated elsewhere\n-1.0\t2.0\n";
+    assert_eq!(read_dos(&foreign[..]).unwrap(), (vec![-1.0], vec![2.0]));
+    for bad in [&b"1.0\n"[..], b"1.0 2.0 3.0\n", b"1.0 x\n"] {
+        assert_eq!(read_dos(bad).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
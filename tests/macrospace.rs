@@ -0,0 +1,331 @@
+//! Test the reusable state-to-bin mappings in `wanglandau::macrospace`.
+
+use rand::Rng;
+use wanglandau::macrospace::EnergyBins;
+use wanglandau::prelude::*;
+
+#[derive(Clone)]
+struct Harmonic(f64);
+impl State for Harmonic {}
+
+fn energy(s: &Harmonic) -> f64 {
+    0.5 * s.0 * s.0
+}
+
+/// Reproduces the harmonic test's hand-rolled binning and counts clamps.
+#[test]
+fn energy_bins_match_harmonic_binning() {
+    let bins = EnergyBins::new(energy, 0.1, 0.0, 100);
+    assert_eq!(bins.bins().len(), 100);
+
+    let mut rng = wanglandau::rng::seeded(3);
+    let mut expected_high = 0;
+    for _ in 0..10_000 {
+        let s = Harmonic(rng.random_range(-6.0..6.0));
+        let hand_rolled = ((energy(&s) / 0.1).floor() as usize).min(99);
+        if energy(&s) >= 10.0 {
+            expected_high += 1;
+        }
+        assert_eq!(bins.locate(&s), hand_rolled);
+    }
+    assert!(expected_high > 0);
+    assert_eq!(bins.n_clamped_high(), expected_high);
+    assert_eq!(bins.n_clamped_low(), 0);
+
+    // A window starting at E = 1 clamps lower energies into its first bin
+    let window = EnergyBins::new(energy, 0.1, 1.0, 10);
+    assert_eq!(window.locate(&Harmonic(0.0)), 0);
+    assert_eq!(window.locate(&Harmonic(1.5)), 1); // E = 1.125
+    assert_eq!(window.n_clamped_low(), 1);
+
+    window.reset_clamp_counters();
+    assert_eq!(window.n_clamped_low(), 0);
+}
+
+/// Known energies land in the right non-uniform bins; outliers are clamped.
+#[test]
+fn edge_bins_locate_by_binary_search() {
+    use wanglandau::macrospace::EdgeBins;
+
+    // Fine bins around E = 2, coarse elsewhere
+    let edges = vec![0.0, 1.0, 1.9, 1.95, 2.0, 2.05, 2.1, 5.0, 10.0];
+    let bins = EdgeBins::new(energy, edges);
+    assert_eq!(bins.bins().len(), 8);
+
+    let at = |e: f64| Harmonic((2.0 * e).sqrt());
+    assert_eq!(bins.locate(&at(0.5)), 0);
+    assert_eq!(bins.locate(&at(1.0)), 1); // lower edges are inclusive
+    assert_eq!(bins.locate(&at(1.93)), 2);
+    assert_eq!(bins.locate(&at(2.02)), 4);
+    assert_eq!(bins.locate(&at(2.07)), 5);
+    assert_eq!(bins.locate(&at(7.5)), 7);
+    assert_eq!(bins.n_clamped_high(), 0);
+
+    assert_eq!(bins.locate(&at(10.0)), 7);
+    assert_eq!(bins.locate(&at(50.0)), 7);
+    assert_eq!(bins.n_clamped_high(), 2);
+    assert_eq!(bins.n_clamped_low(), 0);
+
+    // Positions are relative to each bin's own width, unknown when clamped
+    let position = bins.position_in_bin(&at(1.93)).unwrap();
+    assert!((position - 0.6).abs() < 1e-9, "{}", position);
+    let position = bins.position_in_bin(&at(7.5)).unwrap();
+    assert!((position - 0.5).abs() < 1e-9, "{}", position);
+    assert_eq!(bins.position_in_bin(&at(50.0)), None);
+}
+
+/// Indices past either end of a periodic coordinate wrap around.
+#[test]
+fn periodic_indices_wrap() {
+    use wanglandau::macrospace::Periodic;
+
+    #[derive(Clone)]
+    struct Index(i64);
+    impl State for Index {}
+
+    let bins = Periodic::new(|s: &Index| s.0, 8);
+    assert_eq!(bins.bins().len(), 8);
+    assert_eq!(bins.locate(&Index(3)), 3);
+    assert_eq!(bins.locate(&Index(8)), 0);
+    assert_eq!(bins.locate(&Index(-1)), 7);
+    assert_eq!(bins.locate(&Index(-9)), 7);
+    assert_eq!(bins.locate(&Index(17)), 1);
+}
+
+/// The three phases of a toy substance
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Solid,
+    Liquid,
+    Gas,
+}
+
+impl From<Phase> for usize {
+    fn from(p: Phase) -> usize {
+        p as usize
+    }
+}
+
+impl TryFrom<usize> for Phase {
+    type Error = usize;
+    fn try_from(i: usize) -> Result<Self, usize> {
+        match i {
+            0 => Ok(Phase::Solid),
+            1 => Ok(Phase::Liquid),
+            2 => Ok(Phase::Gas),
+            _ => Err(i),
+        }
+    }
+}
+
+/// Each variant of an enum macrostate gets its own bin.
+#[test]
+fn enum_variants_map_to_bins() {
+    use wanglandau::macrospace::EnumBins;
+
+    #[derive(Clone)]
+    struct Temperature(f64);
+    impl State for Temperature {}
+
+    let phase = |s: &Temperature| match s.0 {
+        t if t < 0.0 => Phase::Solid,
+        t if t < 100.0 => Phase::Liquid,
+        _ => Phase::Gas,
+    };
+    let bins = EnumBins::new(phase, 3);
+    assert_eq!(bins.bins(), [0, 1, 2]);
+    assert_eq!(bins.locate(&Temperature(-5.0)), 0);
+    assert_eq!(bins.locate(&Temperature(25.0)), 1);
+    assert_eq!(bins.locate(&Temperature(150.0)), 2);
+
+    let variants: Vec<_> = (0..4).map(|b| bins.variant(b)).collect();
+    assert_eq!(
+        variants,
+        [
+            Some(Phase::Solid),
+            Some(Phase::Liquid),
+            Some(Phase::Gas),
+            None
+        ]
+    );
+}
+
+/// A variant count that disagrees with the enum is rejected.
+#[test]
+#[should_panic(expected = "more than 2 variants")]
+fn enum_bins_reject_short_count() {
+    use wanglandau::macrospace::EnumBins;
+
+    #[derive(Clone)]
+    struct Unit;
+    impl State for Unit {}
+
+    EnumBins::new(|_: &Unit| Phase::Solid, 2);
+}
+
+/// A weighted sum of two order parameters lands in the expected bins.
+#[test]
+fn linear_combination_bins_weighted_sum() {
+    use wanglandau::macrospace::LinearCombination;
+
+    #[derive(Clone)]
+    struct Magnet {
+        energy: f64,
+        magnetization: f64,
+    }
+    impl State for Magnet {}
+
+    // 0.7 E + 0.3 M on bins of width 0.5 starting at -2
+    let bins = LinearCombination::new(
+        vec![
+            (0.7, Box::new(|s: &Magnet| s.energy)),
+            (0.3, Box::new(|s: &Magnet| s.magnetization)),
+        ],
+        0.5,
+        -2.0,
+        12,
+    );
+    let at = |energy, magnetization| Magnet {
+        energy,
+        magnetization,
+    };
+    assert_eq!(bins.bins().len(), 12);
+
+    // 0.7·2 + 0.3·(-1) = 1.1 → cell 2 of the grid, bin 2 - (-4) = 6
+    assert!((bins.coordinate(&at(2.0, -1.0)) - 1.1).abs() < 1e-12);
+    assert_eq!(bins.locate(&at(2.0, -1.0)), 6);
+    // 0.7·(-1) + 0.3·(-4) = -1.9 → first bin
+    assert_eq!(bins.locate(&at(-1.0, -4.0)), 0);
+    // 0.7·5 + 0.3·0.5 = 3.65 → last bin
+    assert_eq!(bins.locate(&at(5.0, 0.5)), 11);
+    assert_eq!(bins.n_clamped_high(), 0);
+
+    assert_eq!(bins.locate(&at(10.0, 0.0)), 11);
+    assert_eq!(bins.locate(&at(-10.0, 0.0)), 0);
+    assert_eq!(bins.n_clamped_high(), 1);
+    assert_eq!(bins.n_clamped_low(), 1);
+
+    // 1.1 lies a fifth of the way into [1.0, 1.5), for kernel deposition
+    let position = bins.position_in_bin(&at(2.0, -1.0)).unwrap();
+    assert!((position - 0.2).abs() < 1e-9, "{}", position);
+    assert_eq!(bins.position_in_bin(&at(10.0, 0.0)), None);
+}
+
+/// A displacement confined to |x| ≤ 4, i.e. energies 0 ≤ E ≤ 8
+struct BoxedDisplace;
+impl<R: rand::RngCore> Move<Harmonic, R> for BoxedDisplace {
+    fn propose(&mut self, s: &mut Harmonic, rng: &mut R) {
+        let x = s.0 + rng.random_range(-0.5..=0.5);
+        s.0 = if x.abs() > 4.0 { s.0 } else { x };
+    }
+}
+
+/// A scan of the confined oscillator sizes a grid covering its energies.
+#[test]
+fn scanned_energy_bins_cover_sampled_energies() {
+    let mut rng = wanglandau::rng::seeded(9);
+    let bins = EnergyBins::from_scan(
+        energy,
+        0.1,
+        Harmonic(0.0),
+        &mut BoxedDisplace,
+        &mut rng,
+        200_000,
+    );
+
+    // About 80 bins for 0 ≤ E ≤ 8, plus padding
+    let n = bins.bins().len();
+    assert!((78..=83).contains(&n), "{} bins", n);
+    assert_eq!(bins.locate(&Harmonic(0.0)), 1);
+
+    for _ in 0..100_000 {
+        bins.locate(&Harmonic(rng.random_range(-4.0..=4.0)));
+    }
+    assert_eq!(bins.n_clamped_low(), 0);
+    assert!(bins.n_clamped_high() < 100, "{}", bins.n_clamped_high());
+}
+
+/// Quantile edges over a skewed sample give bins of nearly equal occupancy.
+#[test]
+fn quantile_edges_equalize_skewed_sample() {
+    use wanglandau::macrospace::{quantile_edges, EdgeBins};
+
+    // Exponentially distributed energies, dense near zero with a long tail
+    let mut rng = wanglandau::rng::seeded(10);
+    let pilot: Vec<f64> = (0..50_000)
+        .map(|_| -(1.0 - rng.random::<f64>()).ln())
+        .collect();
+    let edges = quantile_edges(&pilot, 10);
+    assert_eq!(edges.len(), 11);
+    assert!(edges[1] - edges[0] < edges[10] - edges[9]);
+
+    // A fresh sample from the same distribution fills the bins evenly
+    let bins = EdgeBins::from_quantiles(|s: &Harmonic| s.0, &pilot, 10);
+    let mut counts = [0u32; 10];
+    for _ in 0..50_000 {
+        let e = -(1.0 - rng.random::<f64>()).ln();
+        counts[bins.locate(&Harmonic(e))] += 1;
+    }
+    for &c in &counts {
+        assert!((4500..=5500).contains(&c), "{:?}", counts);
+    }
+
+    // Discrete energies merge coinciding quantiles
+    let dice: Vec<f64> = (0..600).map(|i| (i % 3) as f64).collect();
+    assert_eq!(quantile_edges(&dice, 6).len(), 4);
+}
+
+/// A particle on the segment [0, 10), energy equal to its position.
+#[derive(Clone)]
+struct Segment(f64);
+impl State for Segment {}
+
+/// Drifts the particle by less than a bin, staying put at the walls.
+struct Drift;
+impl<R: rand::RngCore> Move<Segment, R> for Drift {
+    fn propose(&mut self, s: &mut Segment, rng: &mut R) {
+        let x = s.0 + rng.random_range(-0.2..0.2);
+        if (0.0..10.0).contains(&x) {
+            s.0 = x;
+        }
+    }
+}
+
+/// Splitting updates across bin edges smooths the ln(g) of the early stages.
+#[test]
+fn kernel_deposition_smooths_ln_g() {
+    let mapper = EnergyBins::new(|s: &Segment| s.0, 0.5, 0.0, 20);
+    assert!((mapper.position_in_bin(&Segment(1.2)).unwrap() - 0.4).abs() < 1e-12);
+    assert_eq!(mapper.position_in_bin(&Segment(11.0)), None);
+
+    // Roughness of ln(g) once the first two stages are complete
+    let roughness = |kernel: bool, seed: u64| {
+        let mapper = EnergyBins::new(|s: &Segment| s.0, 0.5, 0.0, 20);
+        let mut drv = WLDriver::new(
+            Segment(5.0),
+            Drift,
+            mapper,
+            Params::default(),
+            Geometric {
+                alpha: 0.5,
+                tol: 1e-8,
+            },
+            Fraction,
+            wanglandau::rng::seeded(seed),
+        );
+        if kernel {
+            drv = drv.with_kernel_deposition();
+        }
+        while drv.ln_f() > 0.25 {
+            drv.step();
+        }
+        drv.ln_g_roughness()
+    };
+
+    let (mut hard, mut smooth) = (0.0, 0.0);
+    for seed in 0..16 {
+        hard += roughness(false, seed);
+        smooth += roughness(true, seed);
+    }
+    assert!(smooth < 0.5 * hard, "kernel {} vs hard {}", smooth, hard);
+}
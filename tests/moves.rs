@@ -0,0 +1,612 @@
+//! Test the reusable move proposals in `wanglandau::moves`.
+
+use wanglandau::array::WLDriverArray;
+use wanglandau::batch::BatchDriver;
+use wanglandau::macrospace::EnergyBins;
+use wanglandau::moves::{BinScaledDisplace, Coordinate, GraphNode, NeighborMove};
+use wanglandau::parallel::SharedWalker;
+use wanglandau::{flatness, prelude::*, rng, schedule};
+
+/// A walker sitting on a node of a graph
+#[derive(Clone)]
+struct Site(usize);
+impl State for Site {}
+impl GraphNode for Site {
+    fn node(&self) -> usize {
+        self.0
+    }
+    fn set_node(&mut self, node: usize) {
+        self.0 = node;
+    }
+}
+
+/// Each node of the graph is its own bin
+struct NodeBins(Vec<usize>);
+impl Macrospace<Site> for NodeBins {
+    type Bin = usize;
+    fn locate(&self, s: &Site) -> usize {
+        s.0
+    }
+    fn bins(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+/// The same neighbor hop, but discarding the proposal ratio
+struct Uncorrected(NeighborMove);
+impl<R: rand::RngCore> Move<Site, R> for Uncorrected {
+    fn propose(&mut self, s: &mut Site, rng: &mut R) {
+        self.0.propose(s, rng);
+    }
+}
+
+/// A star graph: node 0 is connected to four leaves
+fn star() -> NeighborMove {
+    NeighborMove::new(vec![vec![1, 2, 3, 4], vec![0], vec![0], vec![0], vec![0]])
+}
+
+/// Runs WL on the star graph and returns ln g(center) - mean ln g(leaf).
+fn center_excess<Mv: Move<Site, rng::Rng64>>(moves: Mv) -> f64 {
+    let mut drv = WLDriver::new(
+        Site(0),
+        moves,
+        NodeBins((0..5).collect()),
+        Params::default(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-7,
+        },
+        flatness::Fraction,
+        rng::seeded(17),
+    );
+    drv.run(5_000_000);
+    let ln_g = drv.ln_g();
+    ln_g[0] - ln_g[1..].iter().sum::<f64>() / 4.0
+}
+
+/// Every node holds one microstate, so the corrected ln g must be flat while
+/// the naive walk is biased by ln(deg(center) / deg(leaf)) = ln 4.
+#[test]
+fn degree_correction_on_irregular_graph() {
+    let hop = star();
+    assert_eq!(hop.degree(0), 4);
+    assert_eq!(hop.degree(3), 1);
+
+    let corrected = center_excess(star());
+    assert!(
+        corrected.abs() < 0.3,
+        "corrected walk is biased: {}",
+        corrected
+    );
+
+    let naive = center_excess(Uncorrected(star()));
+    assert!(
+        (naive - 4f64.ln()).abs() < 0.3,
+        "naive walk should be biased by ln 4, got {}",
+        naive
+    );
+}
+
+/// Two bins over the star graph: {center, leaf 1} and {leaves 2-4}
+struct HalfStar;
+impl Macrospace<Site> for HalfStar {
+    type Bin = usize;
+    fn locate(&self, s: &Site) -> usize {
+        usize::from(s.0 >= 2)
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1]
+    }
+}
+
+/// Long sweeps and a strict criterion, so each stage averages over many hops
+fn half_star_params() -> Params {
+    Params {
+        flatness: 0.95,
+        sweep_len: 100,
+        ..Params::default()
+    }
+}
+
+/// Runs an engine on the half star from a seed, returning ln g(B) - ln g(A)
+type HalfStarRun = fn(u64) -> f64;
+
+/// A bin holding nodes of different degree still needs the proposal ratio
+/// for hops inside it, or the walk overweights the center's bin. Every
+/// engine is checked, averaged over a few seeds.
+#[test]
+fn degree_correction_within_a_bin() {
+    let engines: [(&str, HalfStarRun); 4] = [
+        ("WLDriver", |seed| {
+            let mut drv = WLDriver::new(
+                Site(0),
+                star(),
+                HalfStar,
+                half_star_params(),
+                schedule::Geometric {
+                    alpha: 0.5,
+                    tol: 1e-7,
+                },
+                flatness::Fraction,
+                rng::seeded(seed),
+            );
+            drv.run(5_000_000);
+            drv.ln_g()[1] - drv.ln_g()[0]
+        }),
+        ("WLDriverArray", |seed| {
+            let mut drv: WLDriverArray<_, _, _, 2> = WLDriverArray::new(
+                Site(0),
+                star(),
+                HalfStar,
+                half_star_params(),
+                schedule::Geometric {
+                    alpha: 0.5,
+                    tol: 1e-7,
+                },
+                flatness::Fraction,
+                rng::seeded(seed),
+            );
+            drv.run(5_000_000);
+            drv.ln_g()[1] - drv.ln_g()[0]
+        }),
+        ("BatchDriver", |seed| {
+            let mut drv = BatchDriver::shared(
+                vec![Site(0); 2],
+                star(),
+                HalfStar,
+                half_star_params(),
+                schedule::Geometric {
+                    alpha: 0.5,
+                    tol: 1e-7,
+                },
+                flatness::Fraction,
+                rng::seeded(seed),
+            );
+            drv.run(5_000_000);
+            drv.ln_g(0)[1] - drv.ln_g(0)[0]
+        }),
+        ("SharedWalker", |seed| {
+            let mut drv = SharedWalker::new(
+                vec![Site(0); 2],
+                star(),
+                HalfStar,
+                half_star_params(),
+                schedule::Geometric {
+                    alpha: 0.5,
+                    tol: 1e-7,
+                },
+                flatness::Fraction,
+                vec![rng::seeded(seed), rng::seeded(seed + 100)],
+            );
+            drv.run_serial(5_000_000);
+            drv.ln_g()[1] - drv.ln_g()[0]
+        }),
+    ];
+
+    let seeds = 4;
+    for (name, excess) in engines {
+        let mean = (0..seeds).map(excess).sum::<f64>() / seeds as f64;
+        // Three nodes against two, one microstate each
+        assert!(
+            (mean - 1.5f64.ln()).abs() < 0.1,
+            "{}: ln g(B) - ln g(A) = {}, expected ln 1.5",
+            name,
+            mean
+        );
+    }
+}
+
+/// Steps around the leaves 2 → 3 → 4 → 2, claiming a proposal ratio of 1/4
+#[derive(Clone)]
+struct LeafCycle;
+impl<R: rand::RngCore> Move<Site, R> for LeafCycle {
+    fn propose(&mut self, s: &mut Site, _rng: &mut R) {
+        s.0 = 2 + (s.0 - 1) % 3;
+    }
+    fn propose_biased(&mut self, s: &mut Site, rng: &mut R) -> f64 {
+        self.propose(s, rng);
+        0.25f64.ln()
+    }
+}
+
+/// The scripted draws for [`LeafCycle`]: the first exceeds the ratio of 1/4
+/// and rejects, the second accepts.
+fn leaf_cycle_rng() -> rng::DeterministicSequence {
+    rng::DeterministicSequence::from_unit_f64(&[0.5, 0.1]).strict()
+}
+
+/// A single-proposal sweep and a criterion that never ends a stage
+fn leaf_cycle_params() -> Params {
+    Params {
+        flatness: 2.0,
+        ..Params::default()
+    }
+}
+
+/// The array driver decides a hop within a bin by its proposal ratio alone.
+#[test]
+fn array_driver_weighs_moves_within_a_bin() {
+    let mut drv: WLDriverArray<_, _, _, 2, _> = WLDriverArray::new(
+        Site(2),
+        LeafCycle,
+        HalfStar,
+        leaf_cycle_params(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-7,
+        },
+        flatness::Fraction,
+        leaf_cycle_rng(),
+    );
+    drv.step();
+    assert_eq!(drv.state().0, 2);
+    drv.step();
+    assert_eq!(drv.state().0, 3);
+}
+
+/// The batch driver decides a hop within a bin by its proposal ratio alone.
+#[test]
+fn batch_driver_weighs_moves_within_a_bin() {
+    let mut drv = BatchDriver::shared(
+        vec![Site(2)],
+        LeafCycle,
+        HalfStar,
+        leaf_cycle_params(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-7,
+        },
+        flatness::Fraction,
+        leaf_cycle_rng(),
+    );
+    drv.step();
+    assert_eq!(drv.states()[0].0, 2);
+    drv.step();
+    assert_eq!(drv.states()[0].0, 3);
+}
+
+/// The shared-estimate walkers decide a hop within a bin by its proposal
+/// ratio alone.
+#[test]
+fn shared_walkers_weigh_moves_within_a_bin() {
+    let mut drv = SharedWalker::new(
+        vec![Site(2)],
+        LeafCycle,
+        HalfStar,
+        leaf_cycle_params(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-7,
+        },
+        flatness::Fraction,
+        vec![leaf_cycle_rng()],
+    );
+    drv.run_serial(1);
+    assert_eq!(drv.state(0).0, 2);
+    drv.run_serial(1);
+    assert_eq!(drv.state(0).0, 3);
+}
+
+/// A one-dimensional harmonic oscillator
+#[derive(Clone)]
+struct Oscillator(f64);
+impl State for Oscillator {}
+impl Coordinate for Oscillator {
+    fn coordinate(&self) -> f64 {
+        self.0
+    }
+    fn set_coordinate(&mut self, x: f64) {
+        self.0 = x;
+    }
+}
+
+/// A fixed-size displacement, as in the harmonic integration test
+struct FixedDisplace(f64);
+impl<R: rand::RngCore> Move<Oscillator, R> for FixedDisplace {
+    fn propose(&mut self, s: &mut Oscillator, rng: &mut R) {
+        use rand::Rng;
+        s.0 += rng.random_range(-self.0..=self.0);
+    }
+}
+
+/// Energy bin of width 0.1; bin 100 collects everything at E ≥ 10
+fn energy_bin(s: &Oscillator) -> usize {
+    ((0.5 * s.0 * s.0 / 0.1).floor() as usize).min(100)
+}
+
+/// Positional width of energy bin `i` on either side of the minimum.
+fn bin_width(i: usize) -> f64 {
+    (0.2 * (i + 1) as f64).sqrt() - (0.2 * i as f64).sqrt()
+}
+
+/// Samples with the exact ln g as a frozen bias and returns the coefficient
+/// of variation of the visits across the 100 energy bins.
+fn coverage_spread<Mv: Move<Oscillator, rng::Rng64>>(moves: Mv, steps: u64) -> f64 {
+    let energy = |s: &Oscillator| 0.5 * s.0 * s.0;
+    let mut drv = WLDriver::new(
+        Oscillator(0.0),
+        moves,
+        EnergyBins::new(energy, 0.1, 0.0, 101),
+        Params::default(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-3,
+        },
+        flatness::Fraction,
+        rng::seeded(29),
+    );
+    drv.pin_bin(100, f64::NEG_INFINITY);
+    let mut bias: Vec<f64> = (0..100).map(|i| bin_width(i).ln()).collect();
+    bias.push(0.0);
+    drv.set_external_bias(bias).unwrap();
+
+    let mut hist = [0u64; 100];
+    drv.produce(steps, |s| hist[energy_bin(s)] += 1);
+    let mean = steps as f64 / 100.0;
+    let var = hist.iter().map(|&h| (h as f64 - mean).powi(2)).sum::<f64>() / 100.0;
+    var.sqrt() / mean
+}
+
+/// Steps matched to the positional width of each energy bin cover the
+/// energy range more evenly than a fixed step of the same size at the top.
+#[test]
+fn bin_scaled_steps_cover_evenly() {
+    // Both moves cross about six bins per step at E ≈ 10
+    let scales: Vec<f64> = (0..=100).map(|i| 6.0 * bin_width(i)).collect();
+    let scaled = coverage_spread(BinScaledDisplace::new(energy_bin, scales), 300_000);
+    let fixed = coverage_spread(FixedDisplace(0.135), 300_000);
+    assert!(scaled < fixed, "scaled {} vs fixed {}", scaled, fixed);
+}
+
+/// Two three-sided dice
+#[derive(Clone, Debug, PartialEq)]
+struct Trits(u8, u8);
+impl State for Trits {}
+
+/// Rerolls one of the dice and counts how often it was applied
+struct RerollDie {
+    first: bool,
+    applied: std::rc::Rc<std::cell::Cell<u64>>,
+}
+impl<R: rand::RngCore> Move<Trits, R> for RerollDie {
+    fn propose(&mut self, s: &mut Trits, rng: &mut R) {
+        use rand::Rng;
+        let face = rng.random_range(0..3);
+        if self.first {
+            s.0 = face;
+        } else {
+            s.1 = face;
+        }
+        self.applied.set(self.applied.get() + 1);
+    }
+}
+
+/// Bins the sum of the dice, 0..=4
+struct TritSum;
+impl Macrospace<Trits> for TritSum {
+    type Bin = usize;
+    fn locate(&self, s: &Trits) -> usize {
+        (s.0 + s.1) as usize
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4]
+    }
+}
+
+/// Both sub-moves run on every proposal, and a rejection undoes both.
+#[test]
+fn sequence_applies_and_reverts_as_one() {
+    use std::{cell::Cell, rc::Rc};
+    use wanglandau::moves::Sequence;
+
+    let (first, second) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+    let moves = Sequence::new()
+        .then(RerollDie {
+            first: true,
+            applied: first.clone(),
+        })
+        .then(RerollDie {
+            first: false,
+            applied: second.clone(),
+        });
+    assert_eq!(moves.len(), 2);
+    let mut drv = WLDriver::new(
+        Trits(0, 0),
+        moves,
+        TritSum,
+        Params::default(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-6,
+        },
+        flatness::Fraction,
+        rng::seeded(5),
+    )
+    .with_audit_log();
+
+    let mut rejected = 0;
+    for step in 0..2000 {
+        let before = drv.state().clone();
+        drv.step();
+        assert_eq!(first.get(), step + 1);
+        assert_eq!(second.get(), step + 1);
+        let entry = *drv.audit_log().last().unwrap();
+        if entry.accepted {
+            assert_eq!(TritSum.locate(drv.state()), entry.bin_new);
+        } else {
+            rejected += 1;
+            assert_eq!(*drv.state(), before, "rejection was not reverted");
+        }
+    }
+    assert!(rejected > 0);
+
+    // The composite samples the exact density of the sum
+    drv.run(10_000_000);
+    let exact = [1f64, 2.0, 3.0, 2.0, 1.0].map(f64::ln);
+    let ln_g = drv.ln_g();
+    for bin in 1..5 {
+        let (got, want) = (ln_g[bin] - ln_g[0], exact[bin] - exact[0]);
+        assert!(
+            (got - want).abs() < 0.15,
+            "bin {}: {} vs {}",
+            bin,
+            got,
+            want
+        );
+    }
+}
+
+/// Eight spins bonded into four pairs that always point the same way
+#[derive(Clone, Debug, PartialEq)]
+struct Pairs([bool; 8]);
+impl State for Pairs {}
+
+/// Bins by the number of up pairs, 0 to 4
+struct UpPairs;
+impl Macrospace<Pairs> for UpPairs {
+    type Bin = usize;
+    fn locate(&self, s: &Pairs) -> usize {
+        s.0.iter().filter(|&&up| up).count() / 2
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4]
+    }
+}
+
+/// Bins eight independent spins by their number of up spins, 0 to 8
+struct FreeSpins;
+impl Macrospace<Pairs> for FreeSpins {
+    type Bin = usize;
+    fn locate(&self, s: &Pairs) -> usize {
+        s.0.iter().filter(|&&up| up).count()
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8]
+    }
+}
+
+/// Builds a driver over bonded pairs with the given cluster move.
+fn pairs_driver(
+    moves: wanglandau::moves::Cluster<Pairs, rng::Rng64>,
+    seed: u64,
+) -> WLDriver<Pairs, wanglandau::moves::Cluster<Pairs, rng::Rng64>, UpPairs> {
+    WLDriver::new(
+        Pairs([false; 8]),
+        moves,
+        UpPairs,
+        Params::default(),
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-6,
+        },
+        flatness::Fraction,
+        rng::seeded(seed),
+    )
+}
+
+/// Flipping a bonded pair as one cluster samples the binomial density.
+#[test]
+fn cluster_flips_converge_to_exact_density() {
+    use rand::Rng;
+    use wanglandau::moves::Cluster;
+
+    let flip_pair = Cluster::new(|s: &mut Pairs, rng: &mut rng::Rng64| {
+        let site = rng.random_range(0..8);
+        let partner = site ^ 1;
+        s.0[site] = !s.0[site];
+        s.0[partner] = !s.0[partner];
+        0.0
+    });
+    let mut drv = pairs_driver(flip_pair, 6).with_audit_log();
+    drv.run(10_000_000);
+    assert!(drv.ln_f() < 1e-6, "run did not converge");
+    assert!(drv.state().0.chunks(2).all(|pair| pair[0] == pair[1]));
+    assert!(drv.audit_log().iter().any(|entry| !entry.accepted));
+
+    let exact = [1f64, 4.0, 6.0, 4.0, 1.0].map(f64::ln);
+    let ln_g = drv.ln_g();
+    for bin in 1..5 {
+        let (got, want) = (ln_g[bin] - ln_g[0], exact[bin] - exact[0]);
+        assert!(
+            (got - want).abs() < 0.15,
+            "bin {}: {} vs {}",
+            bin,
+            got,
+            want
+        );
+    }
+}
+
+/// A pure cluster move is accepted on every proposal but never into a forbidden bin.
+#[test]
+fn always_accepted_cluster_skips_acceptance() {
+    use wanglandau::moves::Cluster;
+
+    // A global flip maps bin m to bin 4 - m, which has the same density
+    let global_flip = Cluster::always_accepted(|s: &mut Pairs, _rng: &mut rng::Rng64| {
+        for up in &mut s.0 {
+            *up = !*up;
+        }
+    });
+    let mut drv = pairs_driver(global_flip, 7).with_audit_log();
+    for _ in 0..100 {
+        drv.step();
+    }
+    assert!(drv.audit_log().iter().all(|entry| entry.accepted));
+    assert_eq!(drv.ln_g()[0], drv.ln_g()[4]);
+
+    drv.pin_bin(4, f64::NEG_INFINITY);
+    let before = drv.state().clone();
+    drv.step();
+    assert!(!drv.audit_log().last().unwrap().accepted);
+    assert_eq!(*drv.state(), before);
+}
+
+/// Each driver step is one sweep touching every site once, in varying order.
+#[test]
+fn permutation_sweep_visits_each_site_once() {
+    use std::{cell::RefCell, rc::Rc};
+    use wanglandau::moves::RandomPermutationSweep;
+
+    let touched = Rc::new(RefCell::new(Vec::new()));
+    let log = touched.clone();
+    let sweep =
+        RandomPermutationSweep::new(8, move |s: &mut Pairs, site, _rng: &mut rng::Rng64| {
+            log.borrow_mut().push(site);
+            s.0[site] = !s.0[site];
+        });
+    let params = Params {
+        sweep_len: 8,
+        ..Params::default()
+    };
+    let mut drv = WLDriver::new(
+        Pairs([false; 8]),
+        sweep,
+        FreeSpins,
+        params,
+        schedule::Geometric {
+            alpha: 0.5,
+            tol: 1e-6,
+        },
+        flatness::Fraction,
+        rng::seeded(8),
+    );
+    for _ in 0..50 {
+        drv.step();
+    }
+
+    let touched = touched.borrow();
+    assert_eq!(touched.len(), 50 * 8);
+    let sweeps: Vec<&[usize]> = touched.chunks(8).collect();
+    for sweep in &sweeps {
+        let mut sites = sweep.to_vec();
+        sites.sort();
+        assert_eq!(sites, (0..8).collect::<Vec<_>>(), "{:?}", sweep);
+    }
+    assert!(
+        sweeps.windows(2).any(|w| w[0] != w[1]),
+        "order never changed"
+    );
+    let distinct: std::collections::HashSet<&[usize]> = sweeps.iter().copied().collect();
+    assert!(distinct.len() > 40, "{} distinct orders", distinct.len());
+}
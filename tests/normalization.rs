@@ -0,0 +1,65 @@
+//! Test the non-negative ln(g) normalization convention.
+
+mod common;
+
+use common::dice_driver_with;
+use wanglandau::prelude::*;
+
+/// Pinning a bin at zero drives the others negative unless clamping is on;
+/// either way the walk itself is identical.
+#[test]
+fn clamp_keeps_ln_g_nonneg_without_changing_acceptance() {
+    let clamped_params = Params {
+        clamp_ln_g_nonneg: true,
+        ..Default::default()
+    };
+    let mut plain = dice_driver_with(Params::default(), 1e-6, 14);
+    let mut clamped = dice_driver_with(clamped_params, 1e-6, 14);
+    plain.pin_bin(0, 0.0);
+    clamped.pin_bin(0, 0.0);
+
+    plain.run(10_000_000);
+    clamped.run(10_000_000);
+
+    assert!(plain.ln_g().iter().any(|&g| g < 0.0), "{:?}", plain.ln_g());
+    assert!(
+        clamped.ln_g().iter().all(|&g| g >= 0.0),
+        "{:?}",
+        clamped.ln_g()
+    );
+
+    // Same trajectory: the curves differ only by a constant
+    assert_eq!(plain.step_count(), clamped.step_count());
+    assert_eq!(plain.state(), clamped.state());
+    let shift = clamped.ln_g()[0] - plain.ln_g()[0];
+    for (a, b) in plain.ln_g().iter().zip(clamped.ln_g()) {
+        assert!((b - a - shift).abs() < 1e-9);
+    }
+}
+
+/// The reference bin takes its known value and differences are unchanged.
+#[test]
+fn normalize_to_reference_shifts_all_bins() {
+    let mut drv = dice_driver_with(Params::default(), 1e-6, 3);
+    drv.pin_bin(5, f64::NEG_INFINITY);
+    drv.set_active_mask(vec![true, true, true, true, true, false])
+        .unwrap();
+    drv.run(10_000_000);
+    assert!(drv.ln_f() < 1e-6);
+    let before = drv.ln_g().to_vec();
+
+    // Say face 1 is known to have exactly one microstate
+    drv.normalize_to_reference(0, 0.0);
+    let after = drv.ln_g();
+    assert_eq!(after[0], 0.0);
+    let shift = after[1] - before[1];
+    assert!((shift + before[0]).abs() < 1e-12);
+    for bin in 0..5 {
+        assert!((after[bin] - before[bin] - shift).abs() < 1e-12);
+        assert!(after[bin].abs() < 0.2, "bin {} = {}", bin, after[bin]);
+    }
+    assert_eq!(after[5], f64::NEG_INFINITY);
+
+    drv.normalize_to_reference(2, 2f64.ln());
+    assert!((drv.ln_g()[2] - 2f64.ln()).abs() < 1e-15);
+}
@@ -0,0 +1,121 @@
+//! Test the multi-threaded shared-ln(g) walkers in `wanglandau::parallel`.
+
+use wanglandau::parallel::SharedWalker;
+use wanglandau::{flatness, prelude::*, rng, schedule};
+
+/// A pair of six-sided dice
+#[derive(Clone)]
+struct Pair([u8; 2]);
+impl State for Pair {}
+
+/// Rerolls one of the two dice
+#[derive(Clone)]
+struct Reroll;
+impl<R: rand::RngCore> Move<Pair, R> for Reroll {
+    fn propose(&mut self, s: &mut Pair, rng: &mut R) {
+        use rand::Rng;
+        let die = rng.random_range(0..2);
+        s.0[die] = rng.random_range(1..=6);
+    }
+}
+
+/// Bins the sum of the dice, 2-12, as 0-10
+struct Sum;
+impl Macrospace<Pair> for Sum {
+    type Bin = usize;
+    fn locate(&self, s: &Pair) -> usize {
+        (s.0[0] + s.0[1] - 2) as usize
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+    }
+}
+
+fn params() -> Params {
+    Params {
+        sweep_len: 100,
+        ..Default::default()
+    }
+}
+
+fn sched() -> schedule::Geometric {
+    schedule::Geometric {
+        alpha: 0.5,
+        tol: 1e-6,
+    }
+}
+
+fn shared(seed: u64) -> SharedWalker<Pair, Reroll, Sum> {
+    SharedWalker::new(
+        vec![Pair([1, 1]); 4],
+        Reroll,
+        Sum,
+        params(),
+        sched(),
+        flatness::Fraction,
+        (0..4).map(|i| rng::seeded(seed + i)).collect(),
+    )
+}
+
+/// ln g shifted to zero mean, relative to the exact multiplicities
+/// 1, 2, …, 6, …, 2, 1 of the sums 2-12.
+fn error(ln_g: &[f64]) -> Vec<f64> {
+    let exact: Vec<f64> = (0..11)
+        .map(|b| ((6 - (b - 5i32).abs()) as f64).ln())
+        .collect();
+    let shift = (ln_g.iter().sum::<f64>() - exact.iter().sum::<f64>()) / 11.0;
+    ln_g.iter().zip(exact).map(|(g, e)| g - shift - e).collect()
+}
+
+/// Four walkers sharing ln(g) agree with the exact result as well as a
+/// single walker does, both threaded and in the serial fallback.
+#[test]
+fn shared_walkers_match_single_walker() {
+    let mut walkers = shared(100);
+    assert_eq!(walkers.n_walkers(), 4);
+    walkers.run(10_000_000);
+    assert!(walkers.is_converged());
+    assert!(walkers.ln_f() < 1e-6);
+
+    let mut single = WLDriver::new(
+        Pair([1, 1]),
+        Reroll,
+        Sum,
+        params(),
+        sched(),
+        flatness::Fraction,
+        rng::seeded(200),
+    );
+    single.run(10_000_000);
+
+    // The serial fallback is reproducible, so it gets the tight bound
+    let mut fallback = shared(100);
+    fallback.run_serial(10_000_000);
+    assert!(fallback.is_converged());
+    let fallback = error(&fallback.ln_g());
+    for e in &fallback {
+        assert!(e.abs() < 0.1, "serial fallback error {:?}", fallback);
+    }
+
+    // Threads interleave nondeterministically, so allow for WL noise
+    let parallel = error(&walkers.ln_g());
+    let serial = error(single.ln_g());
+    for (p, s) in parallel.iter().zip(&serial) {
+        assert!(p.abs() < 0.3, "parallel error {:?}", parallel);
+        assert!(s.abs() < 0.3, "serial error {:?}", serial);
+    }
+}
+
+/// The single-threaded fallback is reproducible from the seeds.
+#[test]
+fn serial_fallback_is_deterministic() {
+    let mut a = shared(7);
+    let mut b = shared(7);
+    a.run_serial(2_000);
+    b.run_serial(2_000);
+
+    assert_eq!(a.ln_g(), b.ln_g());
+    assert_eq!(a.histogram(), b.histogram());
+    assert_eq!(a.step_count(), b.step_count());
+    assert!(a.ln_f() < 1.0);
+}
@@ -0,0 +1,85 @@
+//! Test pinning the ln(g) of individual bins.
+
+mod common;
+
+use common::{dice_driver, spread};
+
+/// A pinned die face keeps its value while the others converge relative to it.
+#[test]
+fn pinned_bin_anchors_estimate() {
+    let mut drv = dice_driver(1e-8, 2025);
+    drv.pin_bin(0, 5.0);
+    assert!(drv.is_pinned(0));
+    assert!(!drv.is_pinned(1));
+
+    drv.run(2_000_000);
+
+    let ln_g = drv.ln_g();
+    assert_eq!(ln_g[0], 5.0, "pinned bin drifted");
+    // All faces are equally likely, so every bin should settle near the pin
+    for (bin, &v) in ln_g.iter().enumerate() {
+        assert!((v - 5.0).abs() < 1.0, "bin {} = {} is far from 5.0", bin, v);
+    }
+    assert!(spread(ln_g) < 1.0);
+}
+
+/// Several pinned faces share the raise of a visit, so each one still gets
+/// the visits of a free face and the run converges.
+#[test]
+fn several_pinned_bins_converge() {
+    for pins in [&[0, 1][..], &[0, 2, 4], &[0, 1, 2, 3, 4]] {
+        let mut drv = dice_driver(1e-6, 2025);
+        for &bin in pins {
+            drv.pin_bin(bin, 0.0);
+        }
+        drv.run(2_000_000);
+
+        assert!(drv.ln_f() < 1e-6, "{:?}: ln_f = {}", pins, drv.ln_f());
+        let ln_g = drv.ln_g();
+        for &bin in pins {
+            assert_eq!(ln_g[bin], 0.0, "pinned bin {} drifted", bin);
+        }
+        assert!(ln_g.iter().all(|v| v.abs() < 1.0), "{:?}: {:?}", pins, ln_g);
+    }
+}
+
+/// A bin pinned at -inf is never entered and no NaN leaks into ln g.
+#[test]
+fn neg_infinity_pin_forbids_bin() {
+    let mut drv = dice_driver(1e-8, 2025);
+    drv.pin_bin(2, f64::NEG_INFINITY);
+    drv.run(200_000);
+
+    assert_eq!(drv.total_histogram()[2], 0);
+    assert_eq!(drv.first_visit_steps()[2], None);
+    assert_eq!(drv.ln_g()[2], f64::NEG_INFINITY);
+    assert!(drv.ln_g().iter().all(|g| !g.is_nan()), "{:?}", drv.ln_g());
+    assert!(drv.total_histogram().iter().filter(|&&h| h > 0).count() == 5);
+
+    // Starting inside the forbidden bin, the walker leaves at the first
+    // proposal to another face and never comes back
+    let mut inside = dice_driver(1e-8, 1);
+    inside.pin_bin(0, f64::NEG_INFINITY);
+    inside.run(1000);
+    let visits = inside.total_histogram()[0];
+    assert!(visits < 10, "{} visits to the forbidden bin", visits);
+    inside.run(10_000);
+    assert_eq!(inside.total_histogram()[0], visits);
+}
+
+/// A masked-out face is never entered and does not hold back flatness.
+#[test]
+fn active_mask_excludes_bin() {
+    let mut drv = dice_driver(1e-6, 2025);
+    assert!(drv.set_active_mask(vec![true; 5]).is_err());
+    drv.set_active_mask(vec![true, true, false, true, true, true])
+        .unwrap();
+    drv.run(2_000_000);
+
+    // The empty bin would fail every flatness check if it were counted
+    assert!(drv.ln_f() < 1e-6, "ln_f = {}", drv.ln_f());
+    assert_eq!(drv.total_histogram()[2], 0);
+    assert_eq!(drv.first_visit_steps()[2], None);
+    let active: Vec<f64> = [0, 1, 3, 4, 5].iter().map(|&b| drv.ln_g()[b]).collect();
+    assert!(spread(&active) < 0.3, "{:?}", drv.ln_g());
+}
@@ -0,0 +1,22 @@
+//! Test selecting the floating-point precision of ln(g).
+
+mod common;
+
+use common::{dice_driver, spread};
+
+/// An f32 dice driver converges at a looser tolerance and halves ln(g) storage.
+#[test]
+fn f32_driver_converges_with_half_storage() {
+    let f64_drv = dice_driver(1e-4, 19);
+    let mut drv = dice_driver(1e-4, 19).with_precision::<f32>();
+
+    assert_eq!(
+        std::mem::size_of_val(drv.ln_g()) * 2,
+        std::mem::size_of_val(f64_drv.ln_g())
+    );
+
+    drv.run(10_000_000);
+    assert!(drv.ln_f() < 1e-4, "ln_f = {}", drv.ln_f());
+    let ln_g: Vec<f64> = drv.ln_g().iter().map(|&g| g as f64).collect();
+    assert!(spread(&ln_g) < 0.5, "{:?}", ln_g);
+}
@@ -0,0 +1,234 @@
+//! Test the frozen-ln(g) production phase.
+
+mod common;
+
+use common::{dice_driver, dice_driver_with};
+use wanglandau::analysis::Accumulator;
+use wanglandau::prelude::*;
+
+/// Welford's algorithm stays accurate where the naive formula cancels badly.
+#[test]
+fn accumulator_is_numerically_stable() {
+    let offset = 1e9;
+    let n = 1_000_000u64;
+
+    let mut acc = Accumulator::new();
+    let (mut sum, mut sum_sq) = (0.0f64, 0.0f64);
+    for i in 0..n {
+        let x = offset + (i % 2) as f64;
+        acc.push(x);
+        sum += x;
+        sum_sq += x * x;
+    }
+
+    // The series alternates offset, offset + 1: mean offset + 1/2, variance 1/4
+    assert_eq!(acc.count(), n);
+    assert!((acc.mean() - (offset + 0.5)).abs() < 1e-6);
+    assert!((acc.population_variance() - 0.25).abs() < 1e-9);
+
+    // The textbook formula loses the variance entirely at this offset
+    let naive = sum_sq / n as f64 - (sum / n as f64).powi(2);
+    assert!(
+        (naive - 0.25).abs() > 1.0,
+        "naive variance was accurate: {}",
+        naive
+    );
+}
+
+/// A converged die produces uniformly distributed faces during production.
+#[test]
+fn production_recovers_face_moments() {
+    let mut drv = dice_driver(1e-8, 9);
+    drv.run(2_000_000);
+
+    let ln_g = drv.ln_g().to_vec();
+    let hist = drv.histogram().to_vec();
+    let steps = drv.step_count();
+
+    // Offset by a large constant to exercise the accumulator's stability
+    let face: Box<dyn Fn(&common::Dice) -> f64> = Box::new(|s| 1e9 + s.0 as f64);
+    let mut observables = [(face, Accumulator::new())];
+    drv.produce_with_accumulators(200_000, &mut observables);
+
+    // With ln(g) frozen, face i is sampled with probability ∝ exp(-ln_g[i])
+    let weights: Vec<f64> = ln_g.iter().map(|&g| (-g).exp()).collect();
+    let z: f64 = weights.iter().sum();
+    let mean: f64 = (0..6).map(|i| weights[i] * (i + 1) as f64).sum::<f64>() / z;
+    let var: f64 = (0..6)
+        .map(|i| weights[i] * ((i + 1) as f64 - mean).powi(2))
+        .sum::<f64>()
+        / z;
+
+    let acc = &observables[0].1;
+    assert_eq!(acc.count(), 200_000);
+    assert!(
+        (mean - 3.5).abs() < 0.3,
+        "converged die is biased: {}",
+        mean
+    );
+    assert!(
+        (acc.mean() - (1e9 + mean)).abs() < 0.05,
+        "mean {}",
+        acc.mean()
+    );
+    assert!((acc.variance() - var).abs() < 0.1, "var {}", acc.variance());
+
+    // Production leaves the WL estimate untouched
+    assert_eq!(drv.ln_g(), &ln_g[..]);
+    assert_eq!(drv.histogram(), &hist[..]);
+    assert_eq!(drv.step_count(), steps);
+}
+
+/// A production tail extends the run past convergence without touching ln(g).
+#[test]
+fn production_tail_freezes_ln_g() {
+    let tail = 5_000;
+
+    let mut plain = dice_driver(1e-6, 4);
+    plain.run(2_000_000);
+
+    let params = Params {
+        production_tail: tail,
+        ..Default::default()
+    };
+    let mut tailed = dice_driver_with(params, 1e-6, 4);
+    tailed.run(2_000_000);
+
+    assert_eq!(tailed.step_count(), plain.step_count() + tail);
+    assert_eq!(tailed.ln_g(), plain.ln_g());
+    assert_eq!(tailed.histogram(), plain.histogram());
+    assert_eq!(tailed.ln_f(), plain.ln_f());
+}
+
+/// Independent rolls give an uncorrelated production bin series.
+#[test]
+fn production_bin_series_of_independent_rolls() {
+    let mut drv = dice_driver(1e-6, 6).with_bin_series();
+    drv.run(10_000_000);
+    assert!(drv.bin_series().is_empty());
+
+    drv.produce(50_000, |_| {});
+    assert_eq!(drv.bin_series().len(), 50_000);
+    assert_eq!(drv.bin_autocorrelation()[0], 1.0);
+
+    let tau = drv.integrated_autocorr_time();
+    assert!((tau - 1.0).abs() < 0.2, "τ = {}", tau);
+}
+
+/// Equilibration moves the walker but leaves every statistic untouched.
+#[test]
+fn equilibrate_leaves_statistics_untouched() {
+    let mut drv = dice_driver(1e-6, 15).with_bin_series();
+    drv.equilibrate(100);
+
+    assert_ne!(drv.state().0, 1, "walker never left the initial face");
+    assert!(drv.ln_g().iter().all(|&g| g == 0.0));
+    assert!(drv.histogram().iter().all(|&h| h == 0));
+    assert!(drv.total_histogram().iter().all(|&h| h == 0));
+    assert_eq!(drv.step_count(), 0);
+    assert!(drv.bin_series().is_empty());
+}
+
+/// The first production proposal starts from the supplied state.
+#[test]
+fn production_starts_from_supplied_state() {
+    use common::Dice;
+
+    let mut drv = dice_driver(1e-4, 12);
+    drv.run(1_000_000);
+    let ln_g = drv.ln_g().to_vec();
+
+    for face in [1, 4, 6] {
+        let mut drv = dice_driver(1e-4, 12).with_audit_log();
+        drv.run(1_000_000);
+        let start = drv.audit_log().len();
+        let mut collected = 0;
+        drv.produce_from(Dice(face), 100, |s| {
+            assert!((1..=6).contains(&s.0));
+            collected += 1;
+        });
+        assert_eq!(collected, 100);
+        assert_eq!(drv.audit_log()[start].bin_old, (face - 1) as usize);
+        assert_eq!(drv.ln_g(), ln_g.as_slice());
+    }
+
+    // With no steps the walker is simply placed at the start
+    drv.produce_from(Dice(3), 0, |_| {});
+    assert_eq!(drv.state(), &Dice(3));
+}
+
+/// With ln(g) converged, the histogram of the current bin is flat.
+#[test]
+fn observable_histogram_of_bin_is_flat() {
+    // A strict flatness criterion drives ln(g) close to the exact value
+    let params = Params {
+        flatness: 0.99,
+        ..Default::default()
+    };
+    let mut drv = dice_driver_with(params, 1e-6, 21);
+    drv.run(10_000_000);
+    let ln_g = drv.ln_g().to_vec();
+
+    let steps = 120_000;
+    let counts = drv.produce_observable_histogram(steps, |s| (s.0 - 1) as usize, 6);
+    assert_eq!(counts.iter().sum::<u64>(), steps);
+
+    // Each face is sampled with probability ∝ exp(-ln_g), which is close to
+    // the flat target 1/6 for a converged run
+    let weights: Vec<f64> = ln_g.iter().map(|&g| (-g).exp()).collect();
+    let z: f64 = weights.iter().sum();
+    for (face, &c) in counts.iter().enumerate() {
+        let p = c as f64 / steps as f64;
+        assert!(
+            (p - weights[face] / z).abs() < 0.005,
+            "face {} visited {} times",
+            face + 1,
+            c
+        );
+        assert!(
+            (p * 6.0 - 1.0).abs() < 0.1,
+            "face {} visited {} times",
+            face + 1,
+            c
+        );
+    }
+    assert_eq!(drv.ln_g(), ln_g.as_slice());
+}
+
+/// With ln(g) frozen the histogram still fills and flatness is still
+/// evaluated, but no flat event fires until the updates resume.
+#[test]
+fn frozen_ln_g_keeps_histogram_and_flatness() {
+    let mut drv = dice_driver(1e-4, 12);
+    drv.run(10_000_000);
+    assert!(drv.ln_f() < 1e-4);
+    drv.freeze_ln_g(true);
+    assert!(drv.is_ln_g_frozen());
+
+    let ln_g = drv.ln_g().to_vec();
+    let ln_f = drv.ln_f();
+    let mut flat_seen = false;
+    for _ in 0..20_000 {
+        let outcome = drv.step_detailed();
+        assert!(!outcome.converged);
+        flat_seen |= outcome.flat_event;
+    }
+
+    // Sampling under the converged bias visits the faces evenly
+    assert!(flat_seen);
+    assert_eq!(drv.ln_g(), ln_g);
+    assert_eq!(drv.ln_f(), ln_f);
+    assert_eq!(drv.histogram().iter().sum::<u64>(), 20_000);
+    assert!(
+        drv.histogram().iter().all(|&h| h > 2_500),
+        "{:?}",
+        drv.histogram()
+    );
+
+    // Resuming the updates lets the flat histogram end the stage
+    drv.freeze_ln_g(false);
+    let outcome = drv.step_detailed();
+    assert!(outcome.flat_event);
+    assert!(drv.ln_f() < ln_f);
+    assert_ne!(drv.ln_g(), ln_g);
+}
@@ -0,0 +1,230 @@
+//! Test the random number generation utilities.
+
+mod common;
+
+use rand::Rng;
+use wanglandau::rng;
+
+/// Draws the first few values from a generator.
+fn draws(mut r: rng::Rng64) -> Vec<u64> {
+    (0..8).map(|_| r.random()).collect()
+}
+
+/// Labels map to fixed, distinct streams.
+#[test]
+fn seeding_from_labels_is_stable() {
+    let label = "run-ising-L32-T2.5";
+    assert_eq!(
+        draws(rng::seeded_from_str(label)),
+        draws(rng::seeded_from_str(label))
+    );
+    assert_ne!(
+        draws(rng::seeded_from_str(label)),
+        draws(rng::seeded_from_str("run-ising-L32-T2.6"))
+    );
+
+    // The hash is FNV-1a: pin known values so the mapping never changes
+    assert_eq!(
+        draws(rng::seeded_from_str("")),
+        draws(rng::seeded(0xcbf29ce484222325))
+    );
+    assert_eq!(
+        draws(rng::seeded_from_str("a")),
+        draws(rng::seeded(0xaf63dc4c8601ec8c))
+    );
+}
+
+/// A coin whose move flips it when the scripted draw is below one half
+#[derive(Clone, Debug, PartialEq)]
+struct Coin(bool);
+impl wanglandau::prelude::State for Coin {}
+
+struct MaybeFlip;
+impl<R: rand::RngCore> wanglandau::prelude::Move<Coin, R> for MaybeFlip {
+    fn propose(&mut self, s: &mut Coin, rng: &mut R) {
+        if rng.random::<f64>() < 0.5 {
+            s.0 = !s.0;
+        }
+    }
+}
+
+struct Side;
+impl wanglandau::prelude::Macrospace<Coin> for Side {
+    type Bin = usize;
+    fn locate(&self, s: &Coin) -> usize {
+        s.0 as usize
+    }
+    fn bins(&self) -> &[usize] {
+        &[0, 1]
+    }
+}
+
+/// A scripted sequence forces a known accept/reject pattern and exact ln g.
+#[test]
+fn scripted_sequence_drives_coin_exactly() {
+    use wanglandau::prelude::*;
+
+    let script = rng::DeterministicSequence::from_unit_f64(&[
+        0.9, // 1: stay on tails                  ln_g = [1, 0]
+        0.9, // 2: stay on tails                  ln_g = [2, 0]
+        0.1, 0.5, // 3: flip, accept (p = e²)     ln_g = [2, 1]
+        0.1, 0.9, // 4: flip, reject (p = e⁻¹)    ln_g = [2, 2], flat → ln_f = 0.5
+        0.1, 0.99, // 5: flip, accept (p = 1)     ln_g = [2.5, 2]
+    ])
+    .strict();
+
+    let mut drv = WLDriver::new(
+        Coin(false),
+        MaybeFlip,
+        Side,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-8,
+        },
+        Fraction,
+        script,
+    );
+    for _ in 0..5 {
+        assert!(!drv.step());
+    }
+
+    assert_eq!(drv.ln_g(), &[2.5, 2.0]);
+    assert_eq!(drv.ln_f(), 0.5);
+    assert_eq!(drv.histogram(), &[1, 0]);
+    assert_eq!(drv.state(), &Coin(false));
+}
+
+/// A driver built from a seed reports it and matches one given the seeded RNG.
+#[test]
+fn seeded_driver_reports_its_seed() {
+    use common::{Dice, Face, Roll};
+    use wanglandau::prelude::*;
+
+    let sched = Geometric {
+        alpha: 0.5,
+        tol: 1e-4,
+    };
+    let mut seeded =
+        WLDriver::new_seeded(Dice(1), Roll, Face, Params::default(), sched, Fraction, 42);
+    assert_eq!(seeded.seed(), Some(42));
+
+    let mut external = common::dice_driver(1e-4, 42);
+    assert_eq!(external.seed(), None);
+
+    seeded.run(10_000);
+    external.run(10_000);
+    assert_eq!(seeded.ln_g(), external.ln_g());
+}
+
+/// The coin script of `scripted_sequence_drives_coin_exactly`, counted.
+fn counted_coin(
+    cap: Option<(u64, rng::OnExhausted)>,
+) -> wanglandau::driver::WLDriver<Coin, MaybeFlip, Side, rng::Budgeted<rng::DeterministicSequence>>
+{
+    use wanglandau::prelude::*;
+
+    let script = rng::DeterministicSequence::from_unit_f64(&[0.9, 0.9, 0.1, 0.5, 0.1, 0.9]);
+    let mut budgeted = rng::Budgeted::new(script);
+    if let Some((cap, on_exhausted)) = cap {
+        budgeted = budgeted.with_cap(cap, on_exhausted);
+    }
+    WLDriver::new(
+        Coin(false),
+        MaybeFlip,
+        Side,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-8,
+        },
+        Fraction,
+        budgeted,
+    )
+}
+
+/// Draws are counted one per call: one per stay, two per attempted flip.
+#[test]
+fn budgeted_rng_counts_draws() {
+    let mut drv = counted_coin(None);
+    for _ in 0..4 {
+        drv.step();
+    }
+    assert_eq!(drv.rng().draws(), 6);
+    assert_eq!(drv.rng().remaining(), None);
+
+    // Saturating near one turns every step past the budget into a stay on heads
+    let mut capped = counted_coin(Some((6, rng::OnExhausted::Saturate(u64::MAX))));
+    for _ in 0..10 {
+        capped.step();
+    }
+    assert!(capped.rng().is_exhausted());
+    assert_eq!(capped.rng().draws(), 12);
+    assert_eq!(capped.histogram(), &[0, 6]);
+}
+
+/// A panicking cap stops a driver that draws past its budget.
+#[test]
+#[should_panic(expected = "random draw budget of 5 exhausted")]
+fn budgeted_rng_panics_past_cap() {
+    let mut drv = counted_coin(Some((5, rng::OnExhausted::Panic)));
+    for _ in 0..4 {
+        drv.step();
+    }
+}
+
+/// A counting generator accounts for one draw per proposal and one per
+/// random acceptance decision.
+#[test]
+fn rng_draws_match_proposals_and_decisions() {
+    use wanglandau::prelude::*;
+
+    let drv = WLDriver::new(
+        Coin(false),
+        MaybeFlip,
+        Side,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 0.0,
+        },
+        Fraction,
+        rng::Budgeted::new(rng::seeded(8)),
+    )
+    .with_audit_log();
+    assert_eq!(drv.rng_draws(), None);
+    let mut drv = drv.with_draw_count();
+    assert_eq!(drv.rng_draws(), Some(0));
+    drv.run(10_000);
+
+    let log = drv.audit_log();
+    let decisions = log.iter().filter(|e| e.draw.is_some()).count() as u64;
+    assert!(decisions > 0 && decisions < 10_000);
+    assert_eq!(drv.rng_draws(), Some(log.len() as u64 + decisions));
+}
+
+/// A large batch of registry seeds is collision-free and reproducible.
+#[test]
+fn seed_registry_batch_is_distinct_and_reproducible() {
+    use std::collections::HashSet;
+
+    let seeds = rng::SeedRegistry::new(7).batch(10_000);
+    let distinct: HashSet<u64> = seeds.iter().copied().collect();
+    assert_eq!(distinct.len(), 10_000);
+    assert_eq!(rng::SeedRegistry::new(7).batch(10_000), seeds);
+    assert_ne!(rng::SeedRegistry::new(8).batch(10_000), seeds);
+
+    // Threads sharing a registry never receive the same seed
+    let registry = std::sync::Arc::new(rng::SeedRegistry::new(7));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let registry = registry.clone();
+            std::thread::spawn(move || (0..2_500).map(|_| registry.next_seed()).collect::<Vec<_>>())
+        })
+        .collect();
+    let shared: HashSet<u64> = handles
+        .into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect();
+    assert_eq!(shared, distinct);
+}
@@ -0,0 +1,77 @@
+//! Test the time-limited `WLDriver::run_for`.
+
+mod common;
+
+use std::time::Duration;
+use wanglandau::prelude::*;
+
+/// A budget far too short to converge returns promptly and says so.
+#[test]
+fn short_budget_returns_unconverged() {
+    let mut drv = common::dice_driver(0.0, 1);
+    let report = drv.run_for(Duration::from_millis(50));
+
+    assert!(!report.converged);
+    assert!(report.elapsed >= Duration::from_millis(50));
+    assert!(report.elapsed < Duration::from_secs(5), "{:?}", report);
+    assert!(report.steps > 0);
+    assert_eq!(report.steps, drv.step_count());
+    assert_eq!(report.ln_f, drv.ln_f());
+
+    // A zero budget performs no steps at all
+    let report = drv.run_for(Duration::ZERO);
+    assert_eq!(report.steps, 0);
+}
+
+/// A generous budget ends at convergence rather than at the deadline.
+#[test]
+fn long_budget_stops_at_convergence() {
+    let mut drv = common::dice_driver(1e-3, 2);
+    let report = drv.run_for(Duration::from_secs(60));
+
+    assert!(report.converged);
+    assert!(report.ln_f < 1e-3);
+    assert!(report.elapsed < Duration::from_secs(60));
+}
+
+/// Rolls only the low half of the die, so faces 4-6 are never reached.
+struct LowRoll;
+impl<R: rand::RngCore> Move<common::Dice, R> for LowRoll {
+    fn propose(&mut self, s: &mut common::Dice, rng: &mut R) {
+        use rand::Rng;
+        s.0 = rng.random_range(1..=3);
+    }
+}
+
+/// A flatness threshold of 0 converges by ln_f, and the report exposes the
+/// bins that were never visited.
+#[test]
+fn report_exposes_unvisited_bins() {
+    let params = Params {
+        flatness: 0.0,
+        ..Params::default()
+    };
+    let mut drv = WLDriver::new(
+        common::Dice(1),
+        LowRoll,
+        common::Face,
+        params,
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-3,
+        },
+        Fraction,
+        wanglandau::rng::seeded(3),
+    );
+    let report = drv.run_for(Duration::from_secs(60));
+
+    assert!(report.converged);
+    assert!((report.coverage - 0.5).abs() < 1e-12, "{:?}", report);
+    assert_eq!(report.sampling_efficiency, 0.0);
+
+    // A well-set run covers every face about evenly
+    let report = common::dice_driver(1e-3, 4).run_for(Duration::from_secs(60));
+    assert!(report.converged);
+    assert_eq!(report.coverage, 1.0);
+    assert!(report.sampling_efficiency > 0.5, "{:?}", report);
+}
@@ -0,0 +1,274 @@
+//! Test the modification factor schedules.
+
+mod common;
+
+use wanglandau::prelude::*;
+use wanglandau::schedule::{BudgetGeometric, Tabulated, Then};
+
+/// Feeds a sequence of flat-event steps to a schedule, updating after each.
+fn feed<S: Schedule>(sched: &mut S, flat_steps: &[u64]) -> f64 {
+    let mut ln_f = 1.0;
+    for &step in flat_steps {
+        sched.on_flat(step);
+        sched.update(&mut ln_f);
+    }
+    ln_f
+}
+
+/// Lengthening stages should push alpha towards 1, shortening towards 0.
+#[test]
+fn adaptive_geometric_tracks_stage_length() {
+    // Stage durations 100, 200, 400, 800: always getting longer
+    let mut slowing = AdaptiveGeometric::new(0.5, 1e-8);
+    feed(&mut slowing, &[100, 300, 700, 1500]);
+    assert!(slowing.alpha > 0.5, "alpha did not grow: {}", slowing.alpha);
+    assert!(slowing.alpha < slowing.alpha_max);
+
+    // Stage durations 800, 400, 200, 100: always getting shorter
+    let mut speeding = AdaptiveGeometric::new(0.5, 1e-8);
+    feed(&mut speeding, &[800, 1200, 1400, 1500]);
+    assert!(
+        speeding.alpha < 0.5,
+        "alpha did not shrink: {}",
+        speeding.alpha
+    );
+    assert!(speeding.alpha > speeding.alpha_min);
+}
+
+/// Many long stages must never push alpha out of its bounds.
+#[test]
+fn adaptive_geometric_stays_bounded() {
+    let mut sched = AdaptiveGeometric::new(0.5, 1e-8);
+    let steps: Vec<u64> = (1..200u64).map(|k| k * k).collect();
+    feed(&mut sched, &steps);
+    assert!(sched.alpha <= sched.alpha_max && sched.alpha < 1.0);
+}
+
+/// An alpha set out of bounds by hand is clamped before it is used.
+#[test]
+fn adaptive_geometric_clamps_alpha_set_by_hand() {
+    let mut sched = AdaptiveGeometric::new(0.5, 1e-8);
+    sched.alpha = 1.5;
+    let ln_f = feed(&mut sched, &[100]);
+    assert_eq!(sched.alpha, sched.alpha_max);
+    assert_eq!(ln_f, sched.alpha_max);
+}
+
+/// Bounds outside (0, 1) are rejected rather than silently followed.
+#[test]
+#[should_panic(expected = "must satisfy 0 < alpha_min <= alpha_max < 1")]
+fn adaptive_geometric_rejects_bounds_outside_unit_interval() {
+    let mut sched = AdaptiveGeometric::new(0.5, 1e-8);
+    sched.alpha_max = 1.2;
+    feed(&mut sched, &[100, 300]);
+}
+
+/// A rate above one, which would overshoot the bounds, is rejected.
+#[test]
+#[should_panic(expected = "adaptation rate 2 must lie in [0, 1]")]
+fn adaptive_geometric_rejects_overshooting_rate() {
+    let mut sched = AdaptiveGeometric::new(0.5, 1e-8);
+    sched.rate = 2.0;
+    feed(&mut sched, &[100, 300]);
+}
+
+/// Chaining two geometric schedules changes the reduction factor exactly once.
+#[test]
+fn then_switches_alpha_at_threshold() {
+    let mut sched = Then::new(
+        Geometric {
+            alpha: 0.5,
+            tol: 0.0,
+        },
+        Geometric {
+            alpha: 0.9,
+            tol: 1e-3,
+        },
+        0.1,
+    );
+
+    let mut ln_f = 1.0;
+    let mut ratios = Vec::new();
+    let mut converged = false;
+    while !converged {
+        let before = ln_f;
+        converged = sched.update(&mut ln_f);
+        ratios.push(ln_f / before);
+    }
+
+    // 1 → 0.5 → 0.25 → 0.125 → 0.0625 (switch), then ×0.9 down to 1e-3
+    assert!(ratios[..4].iter().all(|&r| (r - 0.5).abs() < 1e-12));
+    assert!(ratios[4..].iter().all(|&r| (r - 0.9).abs() < 1e-12));
+    assert!(sched.switched());
+    assert!(ln_f < 1e-3);
+}
+
+/// The geometric estimate counts exactly the updates `run` would perform.
+#[test]
+fn remaining_stages_of_geometric_schedule() {
+    let sched = Geometric {
+        alpha: 0.5,
+        tol: 1e-3,
+    };
+    assert_eq!(sched.tolerance(), Some(1e-3));
+    // 1 · 0.5¹⁰ ≈ 9.8e-4 is the first value below 1e-3
+    assert_eq!(sched.remaining_updates(1.0), Some(10));
+    // Landing exactly on the tolerance is not yet converged
+    assert_eq!(sched.remaining_updates(4e-3), Some(3));
+    assert_eq!(sched.remaining_updates(1e-4), Some(0));
+
+    let mut ln_f = 1.0;
+    let mut sched = sched;
+    let mut updates = 0;
+    while !sched.update(&mut ln_f) {
+        updates += 1;
+    }
+    assert_eq!(updates + 1, 10);
+
+    let fresh = common::dice_driver(1e-3, 1);
+    assert_eq!(fresh.estimate_remaining_stages(), Some(10));
+    // 1/t first drops below 1e-8 at t = 10⁸ + 1
+    assert_eq!(
+        OneOverT::default().remaining_updates(1.0),
+        Some(100_000_000)
+    );
+}
+
+/// ln_f follows the table exactly and the run converges with its last entry.
+#[test]
+fn tabulated_schedule_follows_table() {
+    let mut drv = WLDriver::new(
+        common::Dice(1),
+        common::Roll,
+        common::Face,
+        Params::default(),
+        Tabulated::new(vec![0.5, 0.1, 0.01]),
+        Fraction,
+        wanglandau::rng::seeded(30),
+    );
+    assert_eq!(drv.estimate_remaining_stages(), Some(3));
+
+    let mut trajectory = Vec::new();
+    for _ in 0..1_000_000 {
+        let outcome = drv.step_detailed();
+        if outcome.flat_event {
+            trajectory.push(outcome.ln_f);
+        }
+        if outcome.converged {
+            break;
+        }
+    }
+    assert_eq!(trajectory, [0.5, 0.1, 0.01]);
+    assert_eq!(drv.estimate_remaining_stages(), Some(0));
+
+    // An exhausted table stays converged without touching ln_f
+    let mut sched = Tabulated::new(vec![0.2]);
+    let mut ln_f = 1.0;
+    assert!(sched.update(&mut ln_f));
+    assert!(sched.update(&mut ln_f));
+    assert_eq!(ln_f, 0.2);
+}
+
+/// A restarted 1/t clock continues from the current ln_f instead of its own
+/// count of updates.
+#[test]
+fn one_over_t_progress_restarts_at_ln_f() {
+    let mut sched = OneOverT::default();
+    let mut ln_f = 0.05;
+    sched.reset_progress(ln_f);
+    assert!(!sched.update(&mut ln_f));
+    assert_eq!(ln_f, 1.0 / 21.0);
+    assert_eq!(sched.remaining_updates(ln_f), Some(100_000_000 - 20));
+
+    // A driver starting at ln_f = 0.1 would jump back up to 1/2 at its
+    // first flat event, unless the clock is restarted first
+    let params = Params {
+        ln_f0: 0.1,
+        ..Default::default()
+    };
+    let first_ln_f = |reset: bool| {
+        let mut drv = WLDriver::new(
+            common::Dice(1),
+            common::Roll,
+            common::Face,
+            params,
+            OneOverT::default(),
+            Fraction,
+            wanglandau::rng::seeded(4),
+        );
+        if reset {
+            drv.reset_schedule_progress();
+        }
+        assert_eq!(drv.ln_f(), 0.1);
+        while !drv.step_detailed().flat_event {}
+        drv.ln_f()
+    };
+    assert_eq!(first_ln_f(false), 0.5);
+    assert_eq!(first_ln_f(true), 1.0 / 11.0);
+}
+
+/// Every pass runs its schedule down and the average agrees with one pass.
+#[test]
+fn refinement_passes_rerun_the_schedule() {
+    // From ln_f = 1 halving needs 14 stages to pass 1e-4, from 0.01 it needs 7
+    let mut drv = common::dice_driver(1e-4, 41).with_stage_histograms();
+    let averaged = drv.run_refinement_passes(3, 10_000_000, 0.01);
+    assert_eq!(drv.stage_histograms().len(), 14 + 7 + 7);
+    assert!(drv.ln_f() < 1e-4);
+
+    let mut single = common::dice_driver(1e-4, 41);
+    single.run(10_000_000);
+    assert_eq!(averaged.len(), 6);
+    assert!(averaged.iter().all(|&g| g >= 0.0), "{:?}", averaged);
+    assert!(common::spread(&averaged) < 0.3, "{:?}", averaged);
+    assert!(common::spread(single.ln_g()) < 0.3, "{:?}", single.ln_g());
+}
+
+/// A new alpha set through `schedule_mut` governs later reductions.
+#[test]
+fn schedule_alpha_can_be_changed_mid_run() {
+    let mut drv = common::dice_driver(1e-6, 43);
+    assert_eq!(drv.schedule().alpha, 0.5);
+    assert!(drv.flatness().is_flat(&[10, 10], 0.8));
+
+    let next_flat = |drv: &mut WLDriver<common::Dice, common::Roll, common::Face>| {
+        let before = drv.ln_f();
+        while !drv.step_detailed().flat_event {}
+        drv.ln_f() / before
+    };
+    assert_eq!(next_flat(&mut drv), 0.5);
+
+    drv.schedule_mut().alpha = 0.25;
+    assert_eq!(drv.schedule().alpha, 0.25);
+    assert_eq!(next_flat(&mut drv), 0.25);
+    assert_eq!(next_flat(&mut drv), 0.25);
+}
+
+/// A budgeted run converges just after its step budget, however it is loaded.
+#[test]
+fn budget_geometric_converges_at_budget() {
+    for loading in [0.5, 1.0, 2.0] {
+        let mut sched = BudgetGeometric::new(200_000, 12);
+        sched.loading = loading;
+        let mut drv = WLDriver::new(
+            common::Dice(1),
+            common::Roll,
+            common::Face,
+            Params::default(),
+            sched,
+            Fraction,
+            wanglandau::rng::seeded(44),
+        );
+        drv.run(1_000_000);
+
+        let steps = drv.step_count();
+        assert!(
+            (200_000..201_000).contains(&steps),
+            "loading {}: converged at step {}",
+            loading,
+            steps
+        );
+        assert_eq!(drv.ln_f(), 0.5f64.powi(12));
+        assert!(common::spread(drv.ln_g()) < 0.3, "{:?}", drv.ln_g());
+    }
+}
@@ -0,0 +1,198 @@
+//! Test opt-in recording of the histogram and ln(g) at each flat event.
+
+mod common;
+
+use common::dice_driver;
+
+/// Every flat event should leave exactly one recorded histogram of full length.
+#[test]
+fn one_histogram_per_flat_event() {
+    let mut drv = dice_driver(1e-6, 11).with_stage_histograms();
+
+    let mut flat_events = 0;
+    for _ in 0..1_000_000 {
+        let before = drv.ln_f();
+        let converged = drv.step();
+        if drv.ln_f() != before {
+            flat_events += 1;
+        }
+        if converged {
+            break;
+        }
+    }
+
+    assert!(flat_events > 0, "no flat event occurred");
+    let stages = drv.stage_histograms();
+    assert_eq!(stages.len(), flat_events);
+    for hist in stages {
+        assert_eq!(hist.len(), 6);
+        assert!(hist.iter().all(|&h| h > 0), "flat stage with empty bin");
+    }
+}
+
+/// Without opting in nothing is recorded.
+#[test]
+fn recording_is_opt_in() {
+    let mut drv = dice_driver(1e-6, 11);
+    drv.run(100_000);
+    assert!(drv.stage_histograms().is_empty());
+}
+
+/// The metric series has one entry per recorded stage, at increasing steps.
+#[test]
+fn metric_series_has_one_entry_per_flat_event() {
+    let mut drv = dice_driver(1e-4, 12).with_stage_histograms();
+    let series = drv.run_with_metric(1_000_000, |d| d.ln_g_roughness());
+
+    assert!(drv.ln_f() < 1e-4, "run did not converge");
+    assert_eq!(series.len(), drv.stage_histograms().len());
+    assert!(series.windows(2).all(|w| w[0].0 < w[1].0), "{:?}", series);
+    assert!(series.iter().all(|&(_, r)| r.is_finite() && r >= 0.0));
+    assert_eq!(series.last().unwrap().0, drv.step_count());
+}
+
+/// One acceptance rate is stored per stage and the rates settle as ln g converges.
+#[test]
+fn stage_acceptance_settles_on_dice() {
+    let mut drv = dice_driver(1e-6, 13)
+        .with_stage_histograms()
+        .with_stage_acceptance();
+    drv.run(10_000_000);
+    assert!(drv.ln_f() < 1e-6, "run did not converge");
+
+    let rates: Vec<f64> = drv.stage_acceptance().iter().flatten().copied().collect();
+    assert_eq!(rates.len(), drv.stage_histograms().len());
+    assert!(rates.iter().all(|r| (0.0..=1.0).contains(r)), "{:?}", rates);
+
+    // As ln g flattens almost every inter-bin proposal is accepted
+    let late = &rates[rates.len() - 5..];
+    assert!(late.iter().all(|&r| r > 0.85), "{:?}", rates);
+    assert!(common::spread(late) < 0.1, "{:?}", late);
+}
+
+/// A stage without any proposal into another bin has no rate rather than NaN.
+#[test]
+fn stage_acceptance_is_none_without_inter_bin_proposals() {
+    use wanglandau::prelude::*;
+
+    /// Maps every face of the die to a single bin
+    struct OneBin;
+    impl Macrospace<common::Dice> for OneBin {
+        type Bin = usize;
+        fn locate(&self, _: &common::Dice) -> usize {
+            0
+        }
+        fn bins(&self) -> &[usize] {
+            &[0]
+        }
+    }
+
+    let mut drv = WLDriver::new(
+        common::Dice(1),
+        common::Roll,
+        OneBin,
+        Params::default(),
+        Geometric {
+            alpha: 0.5,
+            tol: 1e-3,
+        },
+        Fraction,
+        wanglandau::rng::seeded(3),
+    )
+    .with_stage_acceptance();
+    drv.run(10);
+    assert!(!drv.stage_acceptance().is_empty());
+    assert!(drv.stage_acceptance().iter().all(Option::is_none));
+}
+
+/// Equilibration and production proposals stay out of the stage rates.
+#[test]
+fn stage_acceptance_skips_equilibration_and_production() {
+    // Identical random streams, recording enabled before or after the
+    // untracked phases
+    let run = |early: bool| {
+        let mut drv = dice_driver(1e-6, 14);
+        if early {
+            drv = drv.with_stage_acceptance();
+        }
+        drv.equilibrate(100_000);
+        drv.produce(100_000, |_| {});
+        if !early {
+            drv = drv.with_stage_acceptance();
+        }
+        drv.run(10_000_000);
+        drv.stage_acceptance().to_vec()
+    };
+
+    let rates = run(true);
+    assert!(!rates.is_empty());
+    assert_eq!(rates, run(false));
+}
+
+/// The debug snapshot agrees with the individual accessors.
+#[test]
+fn debug_state_matches_accessors() {
+    use wanglandau::prelude::*;
+
+    let mut drv = dice_driver(1e-6, 14).with_stage_acceptance();
+    while drv.step_count() < 1000 || drv.histogram().iter().sum::<u64>() < 20 {
+        drv.step();
+    }
+    let snapshot = drv.debug_state();
+
+    assert_eq!(snapshot.ln_g, drv.ln_g());
+    assert_eq!(snapshot.hist, drv.histogram());
+    assert_eq!(snapshot.ln_f, drv.ln_f());
+    assert_eq!(snapshot.step, drv.step_count());
+    assert_eq!(snapshot.bin, common::Face.locate(drv.state()));
+    let visited = drv.total_histogram().iter().filter(|&&h| h > 0).count();
+    assert_eq!(snapshot.visited_bins, visited);
+    let rate = snapshot.acceptance_rate.unwrap();
+    assert!((0.0..=1.0).contains(&rate), "{}", rate);
+
+    let summary = format!("{:#?}", snapshot);
+    assert!(
+        summary.contains(&format!("step: {}", drv.step_count())),
+        "{}",
+        summary
+    );
+    assert!(summary.contains("coverage: 6/6 bins"), "{}", summary);
+
+    // Without acceptance recording there is no rate
+    assert_eq!(dice_driver(1e-6, 14).debug_state().acceptance_rate, None);
+}
+
+/// The ln(g) history has one full row per stage, and the offset-free change
+/// from one row to the next shrinks as ln_f does.
+#[test]
+fn ln_g_history_settles_over_stages() {
+    let mut drv = dice_driver(1e-6, 13).with_ln_g_history();
+    assert!(drv.ln_g_history().is_empty());
+
+    let mut flat_events = 0;
+    for _ in 0..1_000_000 {
+        let outcome = drv.step_detailed();
+        flat_events += usize::from(outcome.flat_event);
+        if outcome.converged {
+            break;
+        }
+    }
+
+    let history = drv.ln_g_history();
+    assert!(flat_events > 10);
+    assert_eq!(history.len(), flat_events);
+    assert!(history.iter().all(|row| row.len() == 6));
+    assert_eq!(history.last().unwrap().as_slice(), drv.ln_g());
+
+    // Largest change of a bin between consecutive stages, beyond the
+    // uniform shift all bins share
+    let change = |pair: &[Vec<f64>]| {
+        let delta: Vec<f64> = pair[1].iter().zip(&pair[0]).map(|(b, a)| b - a).collect();
+        let mean = delta.iter().sum::<f64>() / delta.len() as f64;
+        delta.iter().map(|d| (d - mean).abs()).fold(0.0, f64::max)
+    };
+    let changes: Vec<f64> = history.windows(2).map(change).collect();
+    let early: f64 = changes[..3].iter().sum();
+    let late: f64 = changes[changes.len() - 3..].iter().sum();
+    assert!(late < 0.01 * early, "early {} late {}", early, late);
+}
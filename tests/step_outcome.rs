@@ -0,0 +1,62 @@
+//! Test the per-step report of `WLDriver::step_detailed`.
+
+mod common;
+
+/// `flat_event` is set exactly on the steps where ln_f changes.
+#[test]
+fn flat_event_marks_ln_f_changes() {
+    let mut drv = common::dice_driver(1e-4, 8);
+    let mut prev_ln_f = drv.ln_f();
+    let mut n_flat = 0;
+
+    for _ in 0..1_000_000 {
+        let outcome = drv.step_detailed();
+        assert_eq!(outcome.ln_f, drv.ln_f());
+        assert_eq!(outcome.flat_event, outcome.ln_f != prev_ln_f);
+        if outcome.flat_event {
+            n_flat += 1;
+        }
+        prev_ln_f = outcome.ln_f;
+        if outcome.converged {
+            assert!(outcome.flat_event);
+            break;
+        }
+    }
+
+    // ln_f halves from 1 to below 1e-4
+    assert_eq!(n_flat, 14);
+}
+
+/// The flat-event callback fires once per stage, after the ln_f update.
+#[test]
+fn on_flat_fires_once_per_stage() {
+    use std::sync::{Arc, Mutex};
+    use wanglandau::driver::FlatEvent;
+
+    let events = Arc::new(Mutex::new(Vec::<FlatEvent>::new()));
+    let sink = events.clone();
+    let mut drv =
+        common::dice_driver(1e-4, 8).on_flat(move |event| sink.lock().unwrap().push(event));
+
+    let mut flat_steps = Vec::new();
+    loop {
+        let step = drv.step_count();
+        let outcome = drv.step_detailed();
+        if outcome.flat_event {
+            flat_steps.push((step, outcome.ln_f));
+        }
+        if outcome.converged {
+            break;
+        }
+    }
+
+    // Geometric halving from ln_f = 1 to below 1e-4 takes 14 stages
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 14);
+    for (i, (event, &(step, ln_f))) in events.iter().zip(&flat_steps).enumerate() {
+        assert_eq!(event.stage, i as u32 + 1);
+        assert_eq!(event.step, step);
+        assert_eq!(event.ln_f, ln_f);
+        assert_eq!(event.ln_f, 0.5f64.powi(i as i32 + 1));
+    }
+}
@@ -0,0 +1,20 @@
+//! Test the helpers of the `testing` feature.
+
+mod common;
+
+use wanglandau::testing::assert_converges;
+
+/// The dice model converges well within its budget.
+#[test]
+fn dice_converges() {
+    let mut drv = common::dice_driver(1e-6, 1);
+    assert_converges(&mut drv, 1_000_000, 1e-6);
+}
+
+/// A budget of a few steps fails with the final ln_f and coverage.
+#[test]
+#[should_panic(expected = "ln_f = 1e0 is not below 1e-6 after 3 steps (budget 3)")]
+fn short_run_panics_informatively() {
+    let mut drv = common::dice_driver(1e-6, 1);
+    assert_converges(&mut drv, 3, 1e-6);
+}
@@ -0,0 +1,120 @@
+//! Test canonical thermodynamics computed from ln(g).
+
+use wanglandau::thermo::{
+    canonical_distribution, heat_capacity, heat_capacity_peak, phase_free_energy_difference,
+};
+
+/// Solves the Schottky peak condition x tanh(x / 2) = 2 by bisection.
+fn schottky_peak() -> f64 {
+    let (mut lo, mut hi) = (1.0f64, 4.0f64);
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if mid * (mid / 2.0).tanh() < 2.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// A two-level system with gap 2 peaks at β = x / 2, where C = x² e^x / (1 + e^x)².
+#[test]
+fn two_level_peak_matches_schottky() {
+    let gap = 2.0;
+    let (ln_g, energies) = ([0.0, 0.0], [0.0, gap]);
+    let x = schottky_peak();
+    let exact = x * x * x.exp() / (1.0 + x.exp()).powi(2);
+
+    let (beta, c) = heat_capacity_peak(&ln_g, &energies, (0.11, 3.01), 117);
+    assert!(
+        (beta - x / gap).abs() < 1e-3,
+        "beta = {} vs {}",
+        beta,
+        x / gap
+    );
+    assert!((c - exact).abs() < 1e-6, "C = {} vs {}", c, exact);
+
+    // A common ln(g) offset cancels, even one that would overflow exp
+    let shifted = heat_capacity(&[1000.0, 1000.0], &energies, beta);
+    assert!((shifted - c).abs() < 1e-12);
+}
+
+/// A linear ln g has the same slope everywhere, even on an uneven grid.
+#[test]
+fn microcanonical_beta_of_linear_ln_g() {
+    use wanglandau::thermo::microcanonical_beta;
+
+    let energies = [-4.0, -3.5, -2.0, -1.75, 0.0, 3.0];
+    let ln_g: Vec<f64> = energies.iter().map(|e| 10.0 + 0.8 * e).collect();
+    let beta = microcanonical_beta(&ln_g, &energies);
+    assert_eq!(beta.len(), 5);
+    for b in beta {
+        assert!((b - 0.8).abs() < 1e-12, "slope {}", b);
+    }
+
+    // An S-loop in ln g shows up as a rise in beta
+    let ln_g = [0.0, 3.0, 5.0, 7.5, 9.0];
+    let beta = microcanonical_beta(&ln_g, &[0.0, 1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(beta, [3.0, 2.0, 2.5, 1.5]);
+    assert!(microcanonical_beta(&[1.0], &[0.0]).is_empty());
+}
+
+/// A two-level system with a degenerate upper level follows the Boltzmann weights.
+#[test]
+fn two_level_distribution_matches_boltzmann() {
+    // One ground state at E = 0 and three excited states at E = 1.5
+    let (ln_g, energies) = ([0.0, 3f64.ln()], [0.0, 1.5]);
+    for beta in [0.0, 0.5, 1.0, 4.0] {
+        let p = canonical_distribution(&ln_g, &energies, beta);
+        let excited = 3.0 * (-1.5 * beta).exp();
+        let z = 1.0 + excited;
+        assert!((p[0] - 1.0 / z).abs() < 1e-12, "beta {}: {:?}", beta, p);
+        assert!((p[1] - excited / z).abs() < 1e-12, "beta {}: {:?}", beta, p);
+    }
+
+    // An offset of ln(g) in the thousands changes nothing
+    let shifted = [5000.0, 5000.0 + 3f64.ln()];
+    let p = canonical_distribution(&shifted, &energies, 1.0);
+    let q = canonical_distribution(&ln_g, &energies, 1.0);
+    assert!((p[0] - q[0]).abs() < 1e-12 && (p[1] - q[1]).abs() < 1e-12);
+
+    // Forbidden bins carry no probability
+    let p = canonical_distribution(&[0.0, f64::NEG_INFINITY, 0.0], &[0.0, 1.0, 2.0], 1.0);
+    assert_eq!(p[1], 0.0);
+    assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+}
+
+/// Two mirror-image wells have equal free energy at the transition temperature.
+#[test]
+fn double_well_phases_coexist_at_transition() {
+    // The high well is the low one shifted by ΔE = 4 with ΔS = 6 more entropy,
+    // so F_high - F_low = ΔE - ΔS / β vanishes at β = 1.5
+    let well_ln_g = [0.0, 1.0, 0.0];
+    let well_e = [0.0, 0.5, 1.0];
+    let ln_g: Vec<f64> = well_ln_g
+        .iter()
+        .chain(&well_ln_g.map(|g| g + 6.0))
+        .copied()
+        .collect();
+    let energies: Vec<f64> = well_e
+        .iter()
+        .chain(&well_e.map(|e| e + 4.0))
+        .copied()
+        .collect();
+
+    let df = |beta| phase_free_energy_difference(&ln_g, &energies, beta, 3);
+    assert!(df(1.5).abs() < 1e-12, "{}", df(1.5));
+    for beta in [0.5, 1.0, 2.0, 3.0] {
+        assert!(
+            (df(beta) - (4.0 - 6.0 / beta)).abs() < 1e-12,
+            "beta {}",
+            beta
+        );
+    }
+    assert!(df(1.0) < 0.0 && df(2.0) > 0.0);
+
+    // At coexistence the canonical weight splits evenly between the wells
+    let p = canonical_distribution(&ln_g, &energies, 1.5);
+    assert!((p[..3].iter().sum::<f64>() - 0.5).abs() < 1e-12);
+}
@@ -0,0 +1,62 @@
+//! Test recording the walker's states and ln(g) over a run.
+
+mod common;
+
+use common::{dice_driver, Dice};
+
+/// One state is kept per interval, and every recorded state is a valid die.
+#[test]
+fn trajectory_is_downsampled() {
+    // A tolerance that is never reached keeps the run going for all steps
+    let mut drv = dice_driver(0.0, 2);
+    assert!(drv.trajectory().is_empty());
+
+    drv.record_trajectory(7);
+    drv.run(1000);
+
+    assert_eq!(drv.trajectory().len(), 1000 / 7);
+    assert!(drv.trajectory().iter().all(|d| (1..=6).contains(&d.0)));
+
+    // Restarting discards old records; interval 1 keeps every state
+    drv.record_trajectory(1);
+    drv.step();
+    assert_eq!(drv.trajectory(), &[drv.state().clone()] as &[Dice]);
+}
+
+/// One snapshot per interval plus the final one, unless they coincide.
+#[test]
+fn ln_g_snapshots_are_counted() {
+    let snapshots = dice_driver(0.0, 2).run_recording_ln_g(1000, 100);
+    assert_eq!(snapshots.len(), 10);
+    assert!(snapshots.iter().all(|s| s.len() == 6));
+
+    let snapshots = dice_driver(0.0, 2).run_recording_ln_g(1050, 100);
+    assert_eq!(snapshots.len(), 11);
+    for snapshot in &snapshots {
+        let min = snapshot.iter().copied().fold(f64::INFINITY, f64::min);
+        assert_eq!(min, 0.0);
+    }
+}
+
+/// Snapshots approach the converged ln g, which is the last one.
+#[test]
+fn ln_g_snapshots_converge() {
+    use wanglandau::analysis::tv_distance;
+
+    let mut drv = dice_driver(1e-6, 5);
+    let snapshots = drv.run_recording_ln_g(10_000_000, 200);
+    assert!(drv.ln_f() < 1e-6);
+    assert_eq!(snapshots.len() as u64, drv.step_count() / 200 + 1);
+
+    let last = snapshots.last().unwrap();
+    let min = drv.ln_g().iter().copied().fold(f64::INFINITY, f64::min);
+    for (s, g) in last.iter().zip(drv.ln_g()) {
+        assert!((s - (g - min)).abs() < 1e-12);
+    }
+
+    let distance: Vec<f64> = snapshots.iter().map(|s| tv_distance(s, last)).collect();
+    let quarter = distance.len() / 4;
+    let early = distance[..quarter].iter().sum::<f64>() / quarter as f64;
+    let late = distance[distance.len() - quarter..].iter().sum::<f64>() / quarter as f64;
+    assert!(late < early, "early {} late {}", early, late);
+}
@@ -0,0 +1,53 @@
+//! Test the stage-to-stage convergence monitors (total variation and KL).
+
+mod common;
+
+use common::dice_driver;
+
+/// The distance between consecutive stages shrinks with ln f and ends up near zero.
+#[test]
+fn tv_history_decreases_to_zero() {
+    let mut drv = dice_driver(1e-6, 5).with_tv_monitor(0.0);
+    drv.run(10_000_000);
+
+    let tv = drv.tv_history();
+    assert!(tv.len() >= 10, "too few stages: {}", tv.len());
+    assert!(tv.iter().all(|&d| (0.0..=1.0).contains(&d)));
+
+    let half = tv.len() / 2;
+    let early = tv[..half].iter().sum::<f64>() / half as f64;
+    let late = tv[half..].iter().sum::<f64>() / (tv.len() - half) as f64;
+    assert!(late < early, "no decrease: early {} late {}", early, late);
+    assert!(
+        *tv.last().unwrap() < 1e-3,
+        "final tv {}",
+        tv.last().unwrap()
+    );
+}
+
+/// A loose threshold stops the run before the schedule's tolerance is reached.
+#[test]
+fn tv_threshold_stops_early() {
+    let mut drv = dice_driver(1e-12, 5).with_tv_monitor(1e-2);
+    drv.run(10_000_000);
+
+    assert!(*drv.tv_history().last().unwrap() < 1e-2);
+    assert!(drv.ln_f() > 1e-12, "ran to the schedule tolerance");
+    assert!(dice_driver(1e-12, 5).tv_history().is_empty());
+}
+
+/// The KL criterion stops the run once consecutive stages agree.
+#[test]
+fn kl_stop_triggers_convergence() {
+    let params = wanglandau::prelude::Params {
+        kl_stop: Some(1e-5),
+        ..Default::default()
+    };
+    let mut drv = common::dice_driver_with(params, 1e-12, 5).with_stage_histograms();
+    let converged = (0..10_000_000).any(|_| drv.step());
+
+    assert!(converged, "KL criterion never fired");
+    assert!(drv.ln_f() > 1e-12, "ran to the schedule tolerance");
+    assert!(drv.stage_histograms().len() > 2);
+    assert!(common::spread(drv.ln_g()) < 0.5, "{:?}", drv.ln_g());
+}